@@ -1,6 +1,11 @@
 use eframe::egui;
 use egui::Widget;
-use egui_smith_chart::{Plane, SmithChart};
+use egui_smith_chart::{
+    Complex, FrequencyTicks, GridDensity, Marker, Palette, Plane, Region, SmithChart,
+    SmithChartState, SmithChartStyle, Topology, Trace, TraceHit, Tuner, ValueDisplay,
+};
+
+const CHART_ID: &str = "smith-chart-demo";
 
 fn main() {
     let options = eframe::NativeOptions {
@@ -23,8 +28,40 @@ fn main() {
 struct SmithChartDemo {
     chart_size: f32,
     chart_plane: Plane,
+    chart_palette: Palette,
+    value_display: ValueDisplay,
+    grid_density: Option<GridDensity>,
     mouse_vswr: bool,
+    high_contrast: bool,
+    transmission_coefficient: bool,
+    ruler: bool,
+    wavelength_readout: bool,
+    stub_helper: bool,
+    admittance_ghost: bool,
+    load: Complex<f32>,
+    tuner: Option<Tuner>,
     chart_debug: bool,
+    trace: Trace,
+    last_click: Option<Complex<f32>>,
+    hovered: Option<TraceHit>,
+    tuned_frequency: f64,
+}
+
+/// Series RLC resonator swept across its resonance, seen from a 50 Ω line
+fn series_rlc() -> Trace {
+    let (r, l, c) = (20.0, 10e-9, 1e-12);
+    let frequencies: Vec<f64> = (0..=200).map(|i| 0.5e9 + i as f64 * 20e6).collect();
+    let impedance: Vec<Complex<f32>> = frequencies
+        .iter()
+        .map(|f| {
+            let w = 2.0 * std::f64::consts::PI * f;
+            Complex::new(r, (w * l - 1.0 / (w * c)) as f32)
+        })
+        .collect();
+    Trace::from_impedance("series RLC", &impedance, Complex::new(50.0, 0.0))
+        .frequencies(frequencies)
+        .annotate(1.59e9, "resonance")
+        .frequency_ticks(FrequencyTicks::Step(500e6))
 }
 
 impl Default for SmithChartDemo {
@@ -32,8 +69,23 @@ impl Default for SmithChartDemo {
         Self {
             chart_size: 400.0,
             chart_plane: Plane::Impedance,
+            chart_palette: Palette::Default,
+            value_display: ValueDisplay::Both,
+            grid_density: None,
             mouse_vswr: false,
+            high_contrast: false,
+            transmission_coefficient: false,
+            ruler: false,
+            wavelength_readout: false,
+            stub_helper: false,
+            admittance_ghost: false,
+            load: Complex::new(50.0, 0.0),
+            tuner: None,
             chart_debug: false,
+            trace: series_rlc(),
+            last_click: None,
+            hovered: None,
+            tuned_frequency: 2.0e9,
         }
     }
 }
@@ -62,13 +114,78 @@ impl eframe::App for SmithChartDemo {
                             Plane::Both.to_string(),
                         );
                     });
+                egui::ComboBox::from_label("Palette")
+                    .selected_text(self.chart_palette.to_string())
+                    .show_ui(ui, |ui| {
+                        for palette in [Palette::Default, Palette::OkabeIto, Palette::TolBright] {
+                            ui.selectable_value(
+                                &mut self.chart_palette,
+                                palette,
+                                palette.to_string(),
+                            );
+                        }
+                    });
+                egui::ComboBox::from_label("Values")
+                    .selected_text(self.value_display.to_string())
+                    .show_ui(ui, |ui| {
+                        for display in [
+                            ValueDisplay::Normalized,
+                            ValueDisplay::Absolute,
+                            ValueDisplay::Both,
+                        ] {
+                            ui.selectable_value(
+                                &mut self.value_display,
+                                display,
+                                display.to_string(),
+                            );
+                        }
+                    });
+                egui::ComboBox::from_label("Grid")
+                    .selected_text(
+                        self.grid_density
+                            .map_or("adaptive".to_string(), |density| density.to_string()),
+                    )
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.grid_density, None, "adaptive");
+                        for density in [
+                            GridDensity::Coarse,
+                            GridDensity::Medium,
+                            GridDensity::Fine,
+                            GridDensity::Paper,
+                        ] {
+                            ui.selectable_value(
+                                &mut self.grid_density,
+                                Some(density),
+                                density.to_string(),
+                            );
+                        }
+                    });
                 egui::Slider::new(&mut self.chart_size, 64.0..=2048.0)
                     .text("Chart size")
                     .ui(ui);
                 ui.checkbox(&mut self.mouse_vswr, "Mouse VSWR");
+                ui.checkbox(&mut self.high_contrast, "High contrast");
+                ui.checkbox(
+                    &mut self.transmission_coefficient,
+                    "Transmission coefficient",
+                );
+                ui.checkbox(&mut self.ruler, "Ruler");
+                ui.checkbox(&mut self.wavelength_readout, "Wavelength readout");
+                ui.checkbox(&mut self.stub_helper, "Stub helper");
+                ui.checkbox(&mut self.admittance_ghost, "Admittance ghost");
                 ui.checkbox(&mut self.chart_debug, "Debug");
             });
 
+            ui.collapsing("tuner", |ui| {
+                let mut enabled = self.tuner.is_some();
+                if ui.checkbox(&mut enabled, "Tune the trace").changed() {
+                    self.tuner = enabled.then(|| Tuner::new(Topology::L, self.tuned_frequency));
+                }
+                if let Some(tuner) = &mut self.tuner {
+                    tuner.ui(ui);
+                }
+            });
+
             ui.separator(); //---------------------------------------------------------------------------
 
             // ui.heading("Plot Point");
@@ -83,13 +200,78 @@ impl eframe::App for SmithChartDemo {
 
             ui.separator(); //---------------------------------------------------------------------------
 
+            let style = if self.high_contrast {
+                SmithChartStyle::high_contrast(ui.visuals().dark_mode)
+            } else {
+                SmithChartStyle::default().with_palette(self.chart_palette)
+            };
             ui.horizontal(|ui| {
-                SmithChart::new("smith-chart-demo")
+                egui::Slider::new(&mut self.tuned_frequency, 0.5e9..=4.5e9)
+                    .text("Tuned frequency")
+                    .ui(ui);
+                ui.label("Load");
+                egui_smith_chart::impedance_picker(ui, &mut self.load);
+                if ui.button("Add marker").clicked() {
+                    let id = egui::Id::new(CHART_ID);
+                    let mut state = SmithChartState::load(ui.ctx(), id).unwrap_or_default();
+                    state.markers.push(Marker {
+                        trace: 0,
+                        frequency: 1.6e9,
+                    });
+                    state.store(ui.ctx(), id);
+                }
+                if let Some(z) = self.last_click {
+                    ui.label(format!("clicked z = {:.3}", z));
+                }
+                if let Some(hit) = &self.hovered {
+                    ui.label(format!("hovering sample {}", hit.index));
+                }
+            });
+
+            let last_click = &mut self.last_click;
+            let hovered = &mut self.hovered;
+            *hovered = None;
+            ui.horizontal(|ui| {
+                let mut chart = SmithChart::new(CHART_ID)
                     .size(self.chart_size)
-                    .plane(Plane::Impedance)
+                    .plane(self.chart_plane)
+                    .style(style)
                     .mouse_vswr(self.mouse_vswr)
+                    .transmission_coefficient(self.transmission_coefficient)
+                    .value_display(self.value_display)
+                    .ruler(self.ruler)
+                    .wavelength_readout(self.wavelength_readout)
+                    .stub_helper(self.stub_helper)
+                    .admittance_ghost(self.admittance_ghost)
+                    .adaptive_grid(true)
+                    .grid_labels(true)
+                    .legend(true)
+                    .trace_tooltips(true)
+                    .resonance_markers(true)
+                    .settings_popup(true)
                     .debug(self.chart_debug)
-                    .show(ui);
+                    .trace(self.trace.clone().marker_at(self.tuned_frequency))
+                    .on_click(|z| *last_click = Some(z))
+                    .on_trace_hover(|hit| *hovered = Some(hit.clone()))
+                    .region(Region::vswr(
+                        2.0,
+                        egui::Color32::from_rgba_unmultiplied(0, 160, 0, 24),
+                    ))
+                    .on_paint(|painter, transform| {
+                        // VSWR 2 limit
+                        painter.circle_stroke(
+                            transform.gamma_to_screen(&Complex::new(0.0, 0.0)),
+                            transform.scale(1.0 / 3.0),
+                            egui::Stroke::new(1.0, egui::Color32::GRAY),
+                        );
+                    });
+                if let Some(tuner) = &self.tuner {
+                    chart = chart.trace(tuner.apply(&self.trace, Complex::new(50.0, 0.0)));
+                }
+                if let Some(density) = self.grid_density {
+                    chart = chart.grid_density(density);
+                }
+                chart.show(ui);
             });
         });
     }
@@ -0,0 +1,341 @@
+//! Complex numbers, with just the arithmetic the chart needs so GUI-only apps don't
+//! pull in the `num` crates. With the `num` feature they convert to and from
+//! `num::Complex`.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Floating point type of the parts of a [`Complex`], `f32` or `f64`
+pub trait Real:
+    Copy
+    + PartialOrd
+    + fmt::Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    fn from_i32(value: i32) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn sqrt(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn powf(self, exponent: Self) -> Self;
+    fn powi(self, exponent: i32) -> Self;
+    fn is_finite(self) -> bool;
+    fn is_nan(self) -> bool;
+}
+
+/// Complex number `re + j im`, e.g. a reflection coefficient or an impedance. With the
+/// `serde` feature it is written as `[re, im]`, like `num::Complex`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(from = "[T; 2]", into = "[T; 2]"),
+    serde(bound = "T: Clone + serde::Serialize + serde::de::DeserializeOwned")
+)]
+pub struct Complex<T> {
+    pub re: T,
+    pub im: T,
+}
+impl<T> Complex<T> {
+    pub const fn new(re: T, im: T) -> Self {
+        Self { re, im }
+    }
+}
+impl<T: Real> Complex<T> {
+    /// Imaginary unit j
+    pub fn i() -> Self {
+        Self::new(T::ZERO, T::ONE)
+    }
+
+    /// `r e^(jθ)`
+    pub fn from_polar(r: T, theta: T) -> Self {
+        Self::new(r * theta.cos(), r * theta.sin())
+    }
+
+    /// Magnitude and angle in radians
+    pub fn to_polar(self) -> (T, T) {
+        (self.norm(), self.arg())
+    }
+
+    /// Magnitude
+    pub fn norm(self) -> T {
+        self.re.hypot(self.im)
+    }
+
+    /// Squared magnitude, cheaper than [`Self::norm`]
+    pub fn norm_sqr(self) -> T {
+        self.re * self.re + self.im * self.im
+    }
+
+    /// Angle in radians, in (-π, π]
+    pub fn arg(self) -> T {
+        self.im.atan2(self.re)
+    }
+
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    /// 1 / z
+    pub fn inv(self) -> Self {
+        let norm_sqr = self.norm_sqr();
+        Self::new(self.re / norm_sqr, -self.im / norm_sqr)
+    }
+
+    pub fn scale(self, factor: T) -> Self {
+        Self::new(self.re * factor, self.im * factor)
+    }
+
+    pub fn unscale(self, factor: T) -> Self {
+        Self::new(self.re / factor, self.im / factor)
+    }
+
+    /// Principal square root
+    pub fn sqrt(self) -> Self {
+        let (r, theta) = self.to_polar();
+        Self::from_polar(r.sqrt(), theta / (T::ONE + T::ONE))
+    }
+
+    pub fn exp(self) -> Self {
+        Self::from_polar(self.re.exp(), self.im)
+    }
+
+    /// Principal natural logarithm
+    pub fn ln(self) -> Self {
+        let (r, theta) = self.to_polar();
+        Self::new(r.ln(), theta)
+    }
+
+    pub fn powf(self, exponent: T) -> Self {
+        let (r, theta) = self.to_polar();
+        Self::from_polar(r.powf(exponent), theta * exponent)
+    }
+
+    pub fn powi(self, exponent: i32) -> Self {
+        let (r, theta) = self.to_polar();
+        Self::from_polar(r.powi(exponent), theta * T::from_i32(exponent))
+    }
+
+    /// Neither part is infinite or NaN
+    pub fn is_finite(self) -> bool {
+        self.re.is_finite() && self.im.is_finite()
+    }
+
+    /// Either part is infinite and neither is NaN
+    pub fn is_infinite(self) -> bool {
+        !self.is_nan() && !self.is_finite()
+    }
+
+    /// Either part is NaN
+    pub fn is_nan(self) -> bool {
+        self.re.is_nan() || self.im.is_nan()
+    }
+}
+
+impl<T: Real> From<T> for Complex<T> {
+    fn from(re: T) -> Self {
+        Self::new(re, T::ZERO)
+    }
+}
+impl<T> From<[T; 2]> for Complex<T> {
+    fn from([re, im]: [T; 2]) -> Self {
+        Self { re, im }
+    }
+}
+impl<T> From<Complex<T>> for [T; 2] {
+    fn from(z: Complex<T>) -> Self {
+        [z.re, z.im]
+    }
+}
+#[cfg(feature = "num")]
+impl<T> From<num::Complex<T>> for Complex<T> {
+    fn from(z: num::Complex<T>) -> Self {
+        Self { re: z.re, im: z.im }
+    }
+}
+#[cfg(feature = "num")]
+impl<T> From<Complex<T>> for num::Complex<T> {
+    fn from(z: Complex<T>) -> Self {
+        num::Complex::new(z.re, z.im)
+    }
+}
+
+impl<T: Real> Add for Complex<T> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+}
+impl<T: Real> Sub for Complex<T> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+}
+impl<T: Real> Mul for Complex<T> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+impl<T: Real> Div for Complex<T> {
+    type Output = Self;
+    fn div(self, other: Self) -> Self {
+        let norm_sqr = other.norm_sqr();
+        Self::new(
+            (self.re * other.re + self.im * other.im) / norm_sqr,
+            (self.im * other.re - self.re * other.im) / norm_sqr,
+        )
+    }
+}
+impl<T: Real> Neg for Complex<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+impl<T: Real> std::iter::Sum for Complex<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::new(T::ZERO, T::ZERO), |a, b| a + b)
+    }
+}
+
+// each operator with a real or references on either side, plus assignment
+macro_rules! impl_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident) => {
+        impl<T: Real> $op<T> for Complex<T> {
+            type Output = Self;
+            fn $method(self, other: T) -> Self {
+                $op::$method(self, Self::from(other))
+            }
+        }
+        impl<T: Real> $op<&Complex<T>> for Complex<T> {
+            type Output = Self;
+            fn $method(self, other: &Self) -> Self {
+                $op::$method(self, *other)
+            }
+        }
+        impl<T: Real> $op<Complex<T>> for &Complex<T> {
+            type Output = Complex<T>;
+            fn $method(self, other: Complex<T>) -> Complex<T> {
+                $op::$method(*self, other)
+            }
+        }
+        impl<T: Real> $op<&Complex<T>> for &Complex<T> {
+            type Output = Complex<T>;
+            fn $method(self, other: &Complex<T>) -> Complex<T> {
+                $op::$method(*self, *other)
+            }
+        }
+        impl<T: Real> $op<T> for &Complex<T> {
+            type Output = Complex<T>;
+            fn $method(self, other: T) -> Complex<T> {
+                $op::$method(*self, Complex::from(other))
+            }
+        }
+        impl<T: Real> $assign for Complex<T> {
+            fn $assign_method(&mut self, other: Self) {
+                *self = $op::$method(*self, other);
+            }
+        }
+        impl<T: Real> $assign<T> for Complex<T> {
+            fn $assign_method(&mut self, other: T) {
+                *self = $op::$method(*self, Complex::from(other));
+            }
+        }
+        impl_op!(@real f32, $op, $method);
+        impl_op!(@real f64, $op, $method);
+    };
+    (@real $t:ty, $op:ident, $method:ident) => {
+        impl $op<Complex<$t>> for $t {
+            type Output = Complex<$t>;
+            fn $method(self, other: Complex<$t>) -> Complex<$t> {
+                $op::$method(Complex::from(self), other)
+            }
+        }
+        impl $op<&Complex<$t>> for $t {
+            type Output = Complex<$t>;
+            fn $method(self, other: &Complex<$t>) -> Complex<$t> {
+                $op::$method(Complex::from(self), *other)
+            }
+        }
+    };
+}
+impl_op!(Add, add, AddAssign, add_assign);
+impl_op!(Sub, sub, SubAssign, sub_assign);
+impl_op!(Mul, mul, MulAssign, mul_assign);
+impl_op!(Div, div, DivAssign, div_assign);
+
+macro_rules! impl_real {
+    ($t:ty) => {
+        impl Real for $t {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            fn from_i32(value: i32) -> Self {
+                value as Self
+            }
+            fn sin(self) -> Self {
+                self.sin()
+            }
+            fn cos(self) -> Self {
+                self.cos()
+            }
+            fn hypot(self, other: Self) -> Self {
+                self.hypot(other)
+            }
+            fn atan2(self, other: Self) -> Self {
+                self.atan2(other)
+            }
+            fn sqrt(self) -> Self {
+                self.sqrt()
+            }
+            fn exp(self) -> Self {
+                self.exp()
+            }
+            fn ln(self) -> Self {
+                self.ln()
+            }
+            fn powf(self, exponent: Self) -> Self {
+                self.powf(exponent)
+            }
+            fn powi(self, exponent: i32) -> Self {
+                self.powi(exponent)
+            }
+            fn is_finite(self) -> bool {
+                self.is_finite()
+            }
+            fn is_nan(self) -> bool {
+                self.is_nan()
+            }
+        }
+    };
+}
+impl_real!(f32);
+impl_real!(f64);
+
+/// `re+imi` like `num::Complex`, with the precision applied to both parts
+impl<T: Real> fmt::Display for Complex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (sign, im) = if self.im < T::ZERO {
+            ("-", -self.im)
+        } else {
+            ("+", self.im)
+        };
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}{}{:.*}i", precision, self.re, sign, precision, im),
+            None => write!(f, "{}{}{}i", self.re, sign, im),
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Contours of scalar quantities over the reflection coefficient plane, e.g. output
+//! power or efficiency from load-pull.
+
+use std::collections::HashMap;
+
+use egui::Color32;
+
+use crate::Complex;
+
+/// Grid nodes along each axis of the square around the unit circle
+const GRID: usize = 101;
+
+/// Lines of one level of a [`ContourSet`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contour {
+    pub level: f32,
+    /// Polylines in reflection coefficients, closed ones end where they start
+    pub lines: Vec<Vec<Complex<f32>>>,
+}
+
+/// Scalar field sampled over the unit circle with its contours at chosen levels
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContourSet {
+    /// Name shown in the legend, e.g. "Pout"
+    pub name: String,
+    /// Unit appended to the level labels, e.g. "dBm"
+    pub unit: String,
+    pub color: Color32,
+    pub contours: Vec<Contour>,
+    /// Part of the optimum region where the field is at least this value,
+    /// see [`optimum_region`]
+    pub highlight: Option<f32>,
+    /// Field values at the grid nodes, row by row from Γ = −1 − j, NaN outside the data
+    field: Vec<f32>,
+}
+impl ContourSet {
+    /// Contours of `f(Γ)` at `levels`.
+    /// ```
+    /// # use egui_smith_chart::ContourSet;
+    /// let rings = ContourSet::from_fn("|Γ|", "", |gamma| gamma.norm(), &[0.5]);
+    /// assert_eq!(rings.contours[0].lines.len(), 1);
+    /// ```
+    pub fn from_fn(
+        name: impl Into<String>,
+        unit: impl Into<String>,
+        f: impl Fn(Complex<f32>) -> f32,
+        levels: &[f32],
+    ) -> Self {
+        let mut field = Vec::with_capacity(GRID * GRID);
+        for j in 0..GRID {
+            for i in 0..GRID {
+                let gamma = node(i, j);
+                field.push(if gamma.norm() <= 1.0 {
+                    f(gamma)
+                } else {
+                    f32::NAN
+                });
+            }
+        }
+        Self::from_field(name.into(), unit.into(), field, levels)
+    }
+
+    fn from_field(name: String, unit: String, field: Vec<f32>, levels: &[f32]) -> Self {
+        let contours = levels
+            .iter()
+            .map(|&level| Contour {
+                level,
+                lines: march(&field, level),
+            })
+            .collect();
+        Self {
+            name,
+            unit,
+            color: Color32::from_rgb(230, 120, 40),
+            contours,
+            highlight: None,
+            field,
+        }
+    }
+
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Highlight where the field is at least `threshold`, intersected with the
+    /// highlights of the other sets on the chart
+    pub fn highlight_above(mut self, threshold: f32) -> Self {
+        self.highlight = Some(threshold);
+        self
+    }
+
+    /// Field value at `gamma`, interpolated between the grid nodes. `None` outside the data.
+    pub fn value_at(&self, gamma: Complex<f32>) -> Option<f32> {
+        let x = (gamma.re + 1.0) / 2.0 * (GRID - 1) as f32;
+        let y = (gamma.im + 1.0) / 2.0 * (GRID - 1) as f32;
+        if !(0.0..(GRID - 1) as f32).contains(&x) || !(0.0..(GRID - 1) as f32).contains(&y) {
+            return None;
+        }
+        let (i, j) = (x as usize, y as usize);
+        let (tx, ty) = (x.fract(), y.fract());
+        let v = |i: usize, j: usize| self.field[j * GRID + i];
+        let bottom = v(i, j) * (1.0 - tx) + v(i + 1, j) * tx;
+        let top = v(i, j + 1) * (1.0 - tx) + v(i + 1, j + 1) * tx;
+        let value = bottom * (1.0 - ty) + top * ty;
+        value.is_finite().then_some(value)
+    }
+}
+
+/// Region where every set with a highlight threshold is at or above it, e.g. both
+/// output power and efficiency high enough. Returned as rectangles of
+/// `(lower left, upper right)` reflection coefficients, one per run of grid cells.
+pub fn optimum_region(sets: &[ContourSet]) -> Vec<(Complex<f32>, Complex<f32>)> {
+    let thresholds: Vec<_> = sets
+        .iter()
+        .filter_map(|set| Some((set, set.highlight?)))
+        .collect();
+    if thresholds.is_empty() {
+        return Vec::new();
+    }
+    let inside = |i: usize, j: usize| {
+        thresholds
+            .iter()
+            .all(|(set, threshold)| set.field[j * GRID + i] >= *threshold)
+    };
+
+    let mut rects = Vec::new();
+    for j in 0..GRID - 1 {
+        let mut run_start = None;
+        for i in 0..GRID {
+            let cell = i < GRID - 1
+                && inside(i, j)
+                && inside(i + 1, j)
+                && inside(i, j + 1)
+                && inside(i + 1, j + 1);
+            match (run_start, cell) {
+                (None, true) => run_start = Some(i),
+                (Some(start), false) => {
+                    rects.push((node(start, j), node(i, j + 1)));
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+    rects
+}
+
+/// Output power in dBm of the Cripps loadline model: current limited below the
+/// optimum load resistance `r_opt`, voltage limited above it.
+/// ```
+/// # use egui_smith_chart::{contour::loadline_power_dbm, ContourSet};
+/// // 40 dBm device with a 12.5 Ω optimum load on a 50 Ω chart
+/// let power = ContourSet::from_fn(
+///     "Pout",
+///     "dBm",
+///     |gamma| loadline_power_dbm(gamma, 50.0, 12.5, 40.0),
+///     &[39.0, 38.0, 37.0],
+/// );
+/// ```
+pub fn loadline_power_dbm(gamma: Complex<f32>, z0: f32, r_opt: f32, p_max_dbm: f32) -> f32 {
+    let z = z0 * (1.0 + gamma) / (1.0 - gamma);
+    let current_limited = z.re / r_opt;
+    let voltage_limited = r_opt * z.re / z.norm_sqr();
+    p_max_dbm + 10.0 * current_limited.min(voltage_limited).log10()
+}
+
+/// Reflection coefficient of grid node `(i, j)`
+pub(crate) fn node(i: usize, j: usize) -> Complex<f32> {
+    let step = 2.0 / (GRID - 1) as f32;
+    Complex::new(-1.0 + i as f32 * step, -1.0 + j as f32 * step)
+}
+
+/// Cell edge: lower left node and whether the edge runs up from it instead of right
+type Edge = (usize, usize, bool);
+
+/// Contour polylines of `field` at `level` by marching squares
+fn march(field: &[f32], level: f32) -> Vec<Vec<Complex<f32>>> {
+    let v = |i: usize, j: usize| field[j * GRID + i];
+
+    // crossing point of the level on an edge
+    let point = |(i, j, up): Edge| {
+        let (i1, j1) = if up { (i, j + 1) } else { (i + 1, j) };
+        let (v0, v1) = (v(i, j), v(i1, j1));
+        let t = ((level - v0) / (v1 - v0)).clamp(0.0, 1.0);
+        node(i, j) * (1.0 - t) + node(i1, j1) * t
+    };
+
+    let mut segments: Vec<(Edge, Edge)> = Vec::new();
+    for j in 0..GRID - 1 {
+        for i in 0..GRID - 1 {
+            let corners = [v(i, j), v(i + 1, j), v(i + 1, j + 1), v(i, j + 1)];
+            if corners.iter().any(|v| !v.is_finite()) {
+                continue;
+            }
+            let above = corners.map(|v| v >= level);
+            // edges counter-clockwise from the bottom, between corners k and k + 1
+            let edges = [
+                (i, j, false),
+                (i + 1, j, true),
+                (i, j + 1, false),
+                (i, j, true),
+            ];
+            let crossing: Vec<Edge> = (0..4)
+                .filter(|&k| above[k] != above[(k + 1) % 4])
+                .map(|k| edges[k])
+                .collect();
+            match crossing[..] {
+                [a, b] => segments.push((a, b)),
+                // saddle, keep the corners above the level apart
+                [bottom, right, top, left] if above[0] => {
+                    segments.push((left, bottom));
+                    segments.push((right, top));
+                }
+                [bottom, right, top, left] => {
+                    segments.push((bottom, right));
+                    segments.push((top, left));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut by_edge: HashMap<Edge, Vec<usize>> = HashMap::new();
+    for (k, (a, b)) in segments.iter().enumerate() {
+        by_edge.entry(*a).or_default().push(k);
+        by_edge.entry(*b).or_default().push(k);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut lines = Vec::new();
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let mut edges = vec![segments[start].0, segments[start].1];
+        // extend the end, then the start of the line
+        for _ in 0..2 {
+            while let Some(&k) = by_edge[edges.last().unwrap()].iter().find(|&&k| !used[k]) {
+                used[k] = true;
+                let (a, b) = segments[k];
+                edges.push(if a == *edges.last().unwrap() { b } else { a });
+            }
+            edges.reverse();
+        }
+        lines.push(edges.into_iter().map(point).collect());
+    }
+    lines
+}
@@ -0,0 +1,183 @@
+//! Curves given by a closure, e.g. theoretical loci.
+
+use std::ops::RangeInclusive;
+
+use egui::{Color32, Pos2};
+
+use crate::{ChartTransform, Complex};
+
+/// Deepest subdivision of a curve segment
+const MAX_DEPTH: u32 = 10;
+
+/// Largest allowed distance between the curve and its polyline, in points
+const TOLERANCE: f32 = 0.25;
+
+/// What the values of a [`Curve`] closure are
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Domain {
+    /// Reflection coefficients
+    #[default]
+    Gamma,
+    /// Impedances normalized to the chart Z0
+    Impedance,
+}
+
+/// Circle in the reflection coefficient plane, e.g. a stability or gain circle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Circle {
+    pub center: Complex<f32>,
+    pub radius: f32,
+}
+impl Circle {
+    /// Curve tracing the circle, starting at the top
+    pub fn curve(self) -> Curve<'static> {
+        let top = std::f32::consts::FRAC_PI_2;
+        Curve::new(
+            move |t| self.center + Complex::from_polar(self.radius, t),
+            top..=top + std::f32::consts::TAU,
+        )
+    }
+
+    /// Whether `gamma` lies inside the circle
+    pub fn contains(&self, gamma: Complex<f32>) -> bool {
+        (gamma - self.center).norm() < self.radius
+    }
+}
+
+/// Parametric curve sampled from a closure, with the step adapted to the curvature
+pub struct Curve<'a> {
+    function: Box<dyn Fn(f32) -> Complex<f32> + 'a>,
+    range: RangeInclusive<f32>,
+    pub(crate) domain: Domain,
+    pub(crate) color: Option<Color32>,
+    pub(crate) width: f32,
+    pub(crate) label: Option<String>,
+}
+impl<'a> Curve<'a> {
+    /// Curve of `function(t)` for `t` in `range`, values are reflection coefficients
+    /// unless changed with [`Curve::domain`]
+    pub fn new(function: impl Fn(f32) -> Complex<f32> + 'a, range: RangeInclusive<f32>) -> Self {
+        Self {
+            function: Box::new(function),
+            range,
+            domain: Domain::Gamma,
+            color: None,
+            width: 1.5,
+            label: None,
+        }
+    }
+
+    /// Single point drawn as a dot, e.g. an optimum
+    pub fn point(gamma: Complex<f32>) -> Curve<'static> {
+        Curve::new(move |_| gamma, 0.0..=0.0)
+    }
+
+    /// Text shown at the start of the curve
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn domain(mut self, domain: Domain) -> Self {
+        self.domain = domain;
+        self
+    }
+
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Reflection coefficient at parameter `t`
+    pub fn gamma(&self, t: f32) -> Complex<f32> {
+        let value = (self.function)(t);
+        match self.domain {
+            Domain::Gamma => value,
+            Domain::Impedance => (value - 1.0) / (value + 1.0),
+        }
+    }
+
+    /// Whether the curve is a single point
+    pub(crate) fn is_point(&self) -> bool {
+        self.range.start() == self.range.end()
+    }
+
+    /// Start of the parameter range
+    pub(crate) fn start(&self) -> f32 {
+        *self.range.start()
+    }
+
+    /// Screen polylines of the curve. The curve is split where it isn't finite.
+    pub(crate) fn polylines(&self, transform: &ChartTransform) -> Vec<Vec<Pos2>> {
+        let sample = |t: f32| {
+            let gamma = self.gamma(t);
+            (gamma.re.is_finite() && gamma.im.is_finite())
+                .then(|| transform.gamma_to_screen(&gamma))
+        };
+
+        let mut lines = Vec::new();
+        let mut line = Vec::new();
+        let (start, end) = (*self.range.start(), *self.range.end());
+        // coarse uniform steps first so small features aren't skipped entirely
+        let steps = 16;
+        let mut previous = (start, sample(start));
+        for i in 1..=steps {
+            let t = start + (end - start) * i as f32 / steps as f32;
+            let next = (t, sample(t));
+            match (previous.1, next.1) {
+                (Some(a), Some(b)) => {
+                    if line.is_empty() {
+                        line.push(a);
+                    }
+                    subdivide(&sample, previous.0, a, t, b, 0, &mut line);
+                }
+                _ => {
+                    if line.len() > 1 {
+                        lines.push(std::mem::take(&mut line));
+                    }
+                    line.clear();
+                }
+            }
+            previous = next;
+        }
+        if line.len() > 1 {
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+/// Append points of the curve between `a` (excluded) and `b` (included) to `line`,
+/// halving the parameter step until the midpoint lies close to the chord
+fn subdivide(
+    sample: &impl Fn(f32) -> Option<Pos2>,
+    t0: f32,
+    a: Pos2,
+    t1: f32,
+    b: Pos2,
+    depth: u32,
+    line: &mut Vec<Pos2>,
+) {
+    let tm = (t0 + t1) / 2.0;
+    if depth < MAX_DEPTH {
+        if let Some(m) = sample(tm) {
+            let chord = b - a;
+            let deviation = if chord.length() > 0.0 {
+                (chord.x * (m.y - a.y) - chord.y * (m.x - a.x)).abs() / chord.length()
+            } else {
+                (m - a).length()
+            };
+            if deviation > TOLERANCE {
+                subdivide(sample, t0, a, tm, m, depth + 1, line);
+                subdivide(sample, tm, m, t1, b, depth + 1, line);
+                return;
+            }
+        }
+    }
+    line.push(b);
+}
@@ -0,0 +1,100 @@
+//! Metadata and statistics of a trace to show next to the chart.
+
+use egui::{Grid, Response, Ui};
+
+use crate::{Locale, NumberFormat, Trace};
+
+/// Name, source file, port, reference impedance, sweep range, VSWR extremes and best
+/// match of a trace, e.g. of [`crate::SmithChartResponse::selected`]. Shows a
+/// placeholder while there is no trace, so it can stay in the layout as the selection
+/// changes.
+pub struct TraceDetails<'a> {
+    id_source: egui::Id,
+    trace: Option<&'a Trace>,
+    format: NumberFormat,
+    locale: Locale,
+}
+impl<'a> TraceDetails<'a> {
+    pub fn new(id_source: impl std::hash::Hash, trace: Option<&'a Trace>) -> Self {
+        Self {
+            id_source: egui::Id::new(id_source),
+            trace,
+            format: NumberFormat::default(),
+            locale: Locale::default(),
+        }
+    }
+
+    /// Number format of the values, e.g. the one of the chart
+    pub fn format(mut self, format: NumberFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Translated labels, e.g. the ones of the chart
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn show(self, ui: &mut Ui) -> Response {
+        let trace = match self.trace {
+            Some(trace) => trace,
+            None => return ui.weak(&self.locale.no_trace_selected),
+        };
+        let format = &self.format;
+        let locale = &self.locale;
+        let sweep = |index: usize| {
+            if trace.is_swept() {
+                format.frequency(trace.sweep_at(index))
+            } else {
+                format!("{} {}", locale.sample, index)
+            }
+        };
+        Grid::new(self.id_source)
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                let mut row = |label: &str, value: String| {
+                    ui.label(label);
+                    ui.label(value);
+                    ui.end_row();
+                };
+                row(&locale.trace_name, trace.name.clone());
+                if let Some(source) = &trace.source {
+                    row(&locale.source, source.clone());
+                }
+                if let Some(port) = trace.port_label(locale) {
+                    row(&locale.port, port);
+                }
+                row(
+                    &locale.characteristic_impedance,
+                    match trace.native_z0() {
+                        Some(z0) => format.complex(z0, "Ω"),
+                        None => locale.chart_z0.clone(),
+                    },
+                );
+                row(&locale.samples, trace.gamma.len().to_string());
+                if let (Some(range), true) = (trace.sweep_range(), trace.is_swept()) {
+                    row(
+                        &locale.frequency,
+                        format!(
+                            "{} – {}",
+                            format.frequency(*range.start()),
+                            format.frequency(*range.end())
+                        ),
+                    );
+                }
+                if let Some(stats) = trace.stats() {
+                    row(&locale.min_vswr, format.number(stats.min_vswr));
+                    row(&locale.max_vswr, format.number(stats.max_vswr));
+                    row(&locale.best_match, sweep(stats.best_match_index));
+                    row(
+                        &locale.reflection_coefficient,
+                        format.polar(stats.best_match),
+                    );
+                    row(&locale.return_loss, format.db(stats.return_loss()));
+                }
+            })
+            .response
+    }
+}
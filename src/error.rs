@@ -0,0 +1,128 @@
+//! What can go wrong when reading files and converting data for the chart.
+
+use std::fmt;
+
+use crate::Complex;
+
+/// Why data couldn't be read or converted, returned by the loaders in
+/// [`crate::touchstone`], [`crate::loadpull`] and [`crate::json`] as well as the
+/// conversions of [`crate::Network`] and [`crate::Trace`]
+#[derive(Debug)]
+pub enum SmithChartError {
+    Io(std::io::Error),
+    #[cfg(feature = "json")]
+    Json(serde_json::Error),
+    /// The system clipboard refused the chart image
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    Clipboard(arboard::Error),
+    /// A value isn't a number, with the line it is on
+    InvalidNumber {
+        line: usize,
+        text: String,
+    },
+    /// A line has a different number of values than the header
+    ColumnCount {
+        line: usize,
+    },
+    /// The number of values doesn't fill the last frequency point
+    Incomplete,
+    /// The port count of a Touchstone file can't be told from the file name
+    UnknownPorts,
+    /// A Touchstone option other than S-parameters, or a file extension that no loader
+    /// reads
+    UnsupportedFormat(String),
+    /// The document was written by a newer version of the JSON format
+    UnsupportedVersion(u32),
+    /// No pair of columns gives the load of a load-pull file
+    MissingLoad,
+    /// A trace has a different number of frequencies and values
+    LengthMismatch(String),
+    /// A color isn't `#rrggbb` or `#rrggbbaa`
+    InvalidColor(String),
+    /// The network has a different number of ports than the conversion needs
+    PortCount {
+        expected: usize,
+        found: usize,
+    },
+    /// Reflection coefficients can't be referred to this impedance, e.g. 0 Ω
+    InvalidRenormalization(Complex<f32>),
+    /// Two traces combined sample by sample are normalized to different impedances
+    ReferenceMismatch(String, String),
+}
+impl fmt::Display for SmithChartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            #[cfg(feature = "json")]
+            Self::Json(e) => write!(f, "{}", e),
+            #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+            Self::Clipboard(e) => write!(f, "clipboard: {}", e),
+            Self::InvalidNumber { line, text } => {
+                write!(f, "line {}: invalid number \"{}\"", line, text)
+            }
+            Self::ColumnCount { line } => write!(f, "line {}: wrong number of values", line),
+            Self::Incomplete => write!(f, "incomplete data"),
+            Self::UnknownPorts => write!(f, "file name should end in .sNp"),
+            Self::UnsupportedFormat(what) => write!(f, "unsupported format \"{}\"", what),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {}", version)
+            }
+            Self::MissingLoad => write!(
+                f,
+                "no load columns, expected gamma_re/gamma_im, gamma_mag/gamma_ang or r/x"
+            ),
+            Self::LengthMismatch(name) => {
+                write!(
+                    f,
+                    "trace \"{}\": frequencies and gamma differ in length",
+                    name
+                )
+            }
+            Self::InvalidColor(color) => write!(f, "invalid color \"{}\"", color),
+            Self::PortCount { expected, found } => {
+                write!(
+                    f,
+                    "expected a {}-port network, got {} ports",
+                    expected, found
+                )
+            }
+            Self::InvalidRenormalization(z0) => {
+                write!(f, "can't renormalize to {} Ω", z0)
+            }
+            Self::ReferenceMismatch(a, b) => write!(
+                f,
+                "traces \"{}\" and \"{}\" are normalized to different impedances",
+                a, b
+            ),
+        }
+    }
+}
+impl std::error::Error for SmithChartError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            #[cfg(feature = "json")]
+            Self::Json(e) => Some(e),
+            #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+            Self::Clipboard(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+impl From<std::io::Error> for SmithChartError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for SmithChartError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+#[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+impl From<arboard::Error> for SmithChartError {
+    fn from(e: arboard::Error) -> Self {
+        Self::Clipboard(e)
+    }
+}
@@ -0,0 +1,325 @@
+//! Number formatting used by readouts, labels and markers.
+
+use crate::Complex;
+
+/// SI prefixes from femto to tera, indexed by `exponent / 3 + 5`.
+const SI_PREFIXES: [&str; 10] = ["f", "p", "n", "µ", "m", "", "k", "M", "G", "T"];
+
+/// Typographic minus sign, reads better than '-' next to units
+const MINUS: char = '\u{2212}';
+
+/// Unit used to display frequencies
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FrequencyUnit {
+    Hz,
+    KHz,
+    MHz,
+    GHz,
+    /// Pick the prefix from the magnitude of each value
+    Auto,
+}
+impl FrequencyUnit {
+    /// Decimal exponent of the unit, `None` for [`FrequencyUnit::Auto`]
+    pub fn exponent(&self) -> Option<i32> {
+        match self {
+            Self::Hz => Some(0),
+            Self::KHz => Some(3),
+            Self::MHz => Some(6),
+            Self::GHz => Some(9),
+            Self::Auto => None,
+        }
+    }
+}
+impl std::fmt::Display for FrequencyUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Hz => "Hz",
+            Self::KHz => "kHz",
+            Self::MHz => "MHz",
+            Self::GHz => "GHz",
+            Self::Auto => "auto",
+        })
+    }
+}
+
+/// Whether impedances are shown normalized to Z0, in ohms or both
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ValueDisplay {
+    /// Normalized values such as r and x
+    Normalized,
+    /// Values in ohms such as R and X
+    Absolute,
+    /// Normalized and absolute values side by side in readouts, normalized grid labels
+    Both,
+}
+impl std::fmt::Display for ValueDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Normalized => "normalized",
+            Self::Absolute => "absolute",
+            Self::Both => "both",
+        })
+    }
+}
+
+/// Whether complex readout values are written as real and imaginary part or as
+/// magnitude and angle
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ComplexForm {
+    /// e.g. "50 + j10 Ω"
+    #[default]
+    Rectangular,
+    /// e.g. "51 Ω ∠ 11.3°"
+    Polar,
+}
+impl ComplexForm {
+    /// The other form
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Rectangular => Self::Polar,
+            Self::Polar => Self::Rectangular,
+        }
+    }
+}
+impl std::fmt::Display for ComplexForm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Rectangular => "rectangular",
+            Self::Polar => "polar",
+        })
+    }
+}
+
+/// Unit of angles in polar values
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AngleUnit {
+    Degrees,
+    Radians,
+    /// Degrees followed by radians in parentheses
+    Both,
+}
+impl std::fmt::Display for AngleUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Degrees => "degrees",
+            Self::Radians => "radians",
+            Self::Both => "both",
+        })
+    }
+}
+
+/// How numbers are printed in readouts, labels and markers
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NumberFormat {
+    /// Number of significant digits in formatted values
+    pub significant_digits: usize,
+
+    /// Unit of displayed frequencies
+    pub frequency_unit: FrequencyUnit,
+
+    /// Character between the integer and fractional part, e.g. ',' for most of Europe
+    pub decimal_separator: char,
+
+    /// Normalized or absolute impedance values in readouts and grid labels
+    pub value_display: ValueDisplay,
+
+    /// Unit of the angle of polar values such as ∠Γ
+    pub angle_unit: AngleUnit,
+}
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            significant_digits: 4,
+            frequency_unit: FrequencyUnit::Auto,
+            decimal_separator: '.',
+            value_display: ValueDisplay::Both,
+            angle_unit: AngleUnit::Degrees,
+        }
+    }
+}
+impl NumberFormat {
+    /// Engineering notation with an SI prefix, e.g. "3.3 pF" or "2.45 GHz"
+    pub fn eng(&self, value: f32, unit: &str) -> String {
+        self.localize(format_eng(value as f64, unit, self.significant_digits))
+    }
+
+    /// Plain number with the configured significant digits
+    pub fn number(&self, value: f32) -> String {
+        self.localize(format_sig(value as f64, self.significant_digits))
+    }
+
+    /// Plain number with an explicit sign, e.g. "+0.5" or "−1.2"
+    pub fn signed(&self, value: f32) -> String {
+        let s = self.number(value);
+        if s.starts_with(MINUS) {
+            s
+        } else {
+            format!("+{}", s)
+        }
+    }
+
+    /// Engineering notation with an explicit sign, e.g. "+25 Ω"
+    pub fn signed_eng(&self, value: f32, unit: &str) -> String {
+        let s = self.eng(value, unit);
+        if s.starts_with(MINUS) {
+            s
+        } else {
+            format!("+{}", s)
+        }
+    }
+
+    /// Complex number in rectangular form, e.g. "50 + j10 Ω"
+    pub fn complex(&self, value: Complex<f32>, unit: &str) -> String {
+        let sign = if value.im < 0.0 { MINUS } else { '+' };
+        let s = format!(
+            "{} {} j{}",
+            self.number(value.re),
+            sign,
+            self.number(value.im.abs())
+        );
+        if unit.is_empty() {
+            s
+        } else {
+            format!("{} {}", s, unit)
+        }
+    }
+
+    /// Complex number in polar form with the angle in the configured [`AngleUnit`],
+    /// e.g. "1.2 ∠ −15°"
+    pub fn polar(&self, value: Complex<f32>) -> String {
+        format!(
+            "{} ∠ {}",
+            self.number(value.norm()),
+            self.angle(value.arg())
+        )
+    }
+
+    /// Complex number in `form`, with the unit after the magnitude in polar form, e.g.
+    /// "51 Ω ∠ 11.3°"
+    pub fn complex_in(&self, value: Complex<f32>, unit: &str, form: ComplexForm) -> String {
+        match form {
+            ComplexForm::Rectangular => self.complex(value, unit),
+            ComplexForm::Polar if unit.is_empty() => self.polar(value),
+            ComplexForm::Polar => format!(
+                "{} {} ∠ {}",
+                self.number(value.norm()),
+                unit,
+                self.angle(value.arg())
+            ),
+        }
+    }
+
+    /// Angle given in radians, in the configured [`AngleUnit`]
+    pub fn angle(&self, radians: f32) -> String {
+        match self.angle_unit {
+            AngleUnit::Degrees => format!("{}°", self.number(radians.to_degrees())),
+            AngleUnit::Radians => format!("{} rad", self.number(radians)),
+            AngleUnit::Both => format!(
+                "{}° ({} rad)",
+                self.number(radians.to_degrees()),
+                self.number(radians)
+            ),
+        }
+    }
+
+    /// Frequency in the configured [`FrequencyUnit`], e.g. "2450 MHz"
+    pub fn frequency(&self, hz: f64) -> String {
+        match self.frequency_unit.exponent() {
+            Some(exponent) => self.localize(format!(
+                "{} {}",
+                format_sig(hz / 10f64.powi(exponent), self.significant_digits),
+                self.frequency_unit
+            )),
+            None => self.localize(format_eng(hz, "Hz", self.significant_digits)),
+        }
+    }
+
+    /// Decibel value without prefix scaling, e.g. "−12.7 dB". The return loss of a
+    /// perfect match is infinite and written as "∞ dB", its |Γ| as "−∞ dB":
+    /// ```
+    /// # use egui_smith_chart::NumberFormat;
+    /// let format = NumberFormat::default();
+    /// assert_eq!(format.db(-20.0 * 0f32.log10()), "∞ dB");
+    /// assert_eq!(format.db(20.0 * 0f32.log10()), "−∞ dB");
+    /// ```
+    pub fn db(&self, value: f32) -> String {
+        self.localize(format!(
+            "{} dB",
+            format_sig(value as f64, self.significant_digits)
+        ))
+    }
+
+    /// Apply the decimal separator to a formatted number
+    fn localize(&self, s: String) -> String {
+        if self.decimal_separator == '.' {
+            s
+        } else {
+            s.replace('.', &self.decimal_separator.to_string())
+        }
+    }
+}
+
+/// Format `value` in engineering notation with `significant_digits` digits
+/// and the SI prefix matching its magnitude.
+///
+/// Trailing zeros are dropped, so `format_eng(3.3e-12, "F", 4)` gives "3.3 pF".
+pub fn format_eng(value: f64, unit: &str, significant_digits: usize) -> String {
+    let (mantissa, prefix) = if value == 0.0 || !value.is_finite() {
+        (value, "")
+    } else {
+        let rounded = round_sig(value, significant_digits);
+        let exponent = (rounded.abs().log10().floor() as i32).div_euclid(3) * 3;
+        let exponent = exponent.clamp(-15, 12);
+        let index = (exponent / 3 + 5) as usize;
+        (rounded / 10f64.powi(exponent), SI_PREFIXES[index])
+    };
+
+    let number = format_sig(mantissa, significant_digits);
+    match (prefix.is_empty(), unit.is_empty()) {
+        (true, true) => number,
+        _ => format!("{} {}{}", number, prefix, unit),
+    }
+}
+
+/// Format `value` with `significant_digits` digits, without exponent or prefix.
+///
+/// Infinite values, e.g. the impedance of an open circuit, are written as "∞".
+pub fn format_sig(value: f64, significant_digits: usize) -> String {
+    if value.is_infinite() {
+        return if value > 0.0 {
+            "∞".to_string()
+        } else {
+            format!("{}∞", MINUS)
+        };
+    }
+    if value.is_nan() {
+        return value.to_string();
+    }
+    let digits = significant_digits.max(1) as i32;
+    let decimals = if value == 0.0 {
+        digits - 1
+    } else {
+        digits - 1 - round_sig(value, significant_digits).abs().log10().floor() as i32
+    };
+
+    let mut s = format!("{:.*}", decimals.max(0) as usize, value.abs());
+    if s.contains('.') {
+        let trimmed = s.trim_end_matches('0').trim_end_matches('.').len();
+        s.truncate(trimmed);
+    }
+    if value < 0.0 && s.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        s.insert(0, MINUS);
+    }
+    s
+}
+
+/// Round `value` to `significant_digits` significant digits
+fn round_sig(value: f64, significant_digits: usize) -> f64 {
+    if value == 0.0 {
+        return 0.0;
+    }
+    let digits = significant_digits.max(1) as i32;
+    let scale = 10f64.powi(digits - 1 - value.abs().log10().floor() as i32);
+    (value * scale).round() / scale
+}
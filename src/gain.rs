@@ -0,0 +1,115 @@
+//! Constant gain circles of two-port amplifiers.
+
+use egui::Color32;
+
+use crate::{Circle, Complex, Matrix2, Stability};
+
+/// Which termination a gain circle family constrains
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GainKind {
+    /// Operating power gain, circles of load reflection coefficients
+    Operating,
+    /// Available power gain, circles of source reflection coefficients
+    Available,
+}
+
+/// Family of constant gain circles in steps below the maximum gain
+#[derive(Clone, Debug, PartialEq)]
+pub struct GainCircles {
+    /// Two-port S-parameters at the design frequency
+    pub s: Matrix2,
+    pub kind: GainKind,
+    /// Gain difference between neighbouring circles in dB
+    pub step_db: f32,
+    /// Number of circles below the maximum
+    pub count: usize,
+    pub color: Color32,
+}
+impl GainCircles {
+    /// Operating power gain circles in the load plane
+    pub fn operating(s: Matrix2) -> Self {
+        Self {
+            s,
+            kind: GainKind::Operating,
+            step_db: 1.0,
+            count: 5,
+            color: Color32::from_rgb(60, 150, 230),
+        }
+    }
+
+    /// Available power gain circles in the source plane
+    pub fn available(s: Matrix2) -> Self {
+        Self {
+            kind: GainKind::Available,
+            color: Color32::from_rgb(60, 190, 110),
+            ..Self::operating(s)
+        }
+    }
+
+    pub fn step(mut self, step_db: f32) -> Self {
+        self.step_db = step_db;
+        self
+    }
+
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Maximum gain in dB: the maximum available gain if the two-port is
+    /// unconditionally stable, the maximum stable gain otherwise
+    pub fn max_gain_db(&self) -> f32 {
+        let [[_, s12], [s21, _]] = self.s;
+        let k = Stability::new(0.0, &self.s).k;
+        let msg = s21.norm() / s12.norm();
+        let gain = if k > 1.0 {
+            msg * (k - (k * k - 1.0).sqrt())
+        } else {
+            msg
+        };
+        10.0 * gain.log10()
+    }
+
+    /// Circle of terminations giving `gain_db`, `None` if the gain can't be reached
+    pub fn circle(&self, gain_db: f32) -> Option<Circle> {
+        let [[s11, s12], [s21, s22]] = self.s;
+        let (own, other) = match self.kind {
+            GainKind::Operating => (s22, s11),
+            GainKind::Available => (s11, s22),
+        };
+        let delta = s11 * s22 - s12 * s21;
+        let k = Stability::new(0.0, &self.s).k;
+        let loop_gain = (s12 * s21).norm();
+
+        let g = 10f32.powf(gain_db / 10.0) / s21.norm_sqr();
+        let d = 1.0 + g * (own.norm_sqr() - delta.norm_sqr());
+        let radicand = 1.0 - 2.0 * k * loop_gain * g + loop_gain * loop_gain * g * g;
+        // the maximum gain circle has zero radius up to rounding
+        (radicand >= -1e-4).then(|| Circle {
+            center: g * (own - delta * other.conj()).conj() / d,
+            radius: radicand.max(0.0).sqrt() / d.abs(),
+        })
+    }
+
+    /// Circles from the maximum gain down in `step_db` steps, with their gain in dB.
+    /// The first has zero radius when the maximum gain is finite.
+    pub fn circles(&self) -> Vec<(f32, Circle)> {
+        let max = self.max_gain_db();
+        (0..=self.count)
+            .filter_map(|n| {
+                let gain = max - n as f32 * self.step_db;
+                Some((gain, self.circle(gain)?))
+            })
+            .collect()
+    }
+
+    /// Termination giving the maximum gain
+    pub fn optimum(&self) -> Option<Complex<f32>> {
+        self.circle(self.max_gain_db()).map(|circle| circle.center)
+    }
+}
@@ -0,0 +1,358 @@
+//! Grid circle values, the zoom-dependent level of detail and the grid cache shared by
+//! all charts.
+
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use egui::epaint::{Mesh, Tessellator};
+use egui::{Color32, Context, Id, Pos2, Shape, Stroke, Vec2};
+
+use crate::{Complex, LineStyle, NumberFormat, Plane, SmithChartStyle};
+
+/// Normalized resistances of the grid circles with the detail level they appear at
+pub(crate) const RESISTANCES: [(f32, u8); 32] = [
+    (0.0, 0),
+    (1.0 / 3.0, 0),
+    (1.0, 0),
+    (3.0, 0),
+    (0.2, 1),
+    (0.5, 1),
+    (2.0, 1),
+    (5.0, 1),
+    (10.0, 1),
+    (0.1, 2),
+    (0.3, 2),
+    (0.4, 2),
+    (0.6, 2),
+    (0.7, 2),
+    (0.8, 2),
+    (0.9, 2),
+    (1.5, 2),
+    (4.0, 2),
+    (20.0, 2),
+    (0.05, 3),
+    (0.15, 3),
+    (0.25, 3),
+    (0.35, 3),
+    (0.45, 3),
+    (1.2, 3),
+    (1.4, 3),
+    (1.6, 3),
+    (1.8, 3),
+    (2.5, 3),
+    (6.0, 3),
+    (8.0, 3),
+    (50.0, 3),
+];
+
+/// Positive normalized reactances of the grid arcs with the detail level they appear at
+pub(crate) const REACTANCES: [(f32, u8); 30] = [
+    (0.4, 0),
+    (1.0, 0),
+    (3.0, 0),
+    (0.2, 1),
+    (0.6, 1),
+    (2.0, 1),
+    (5.0, 1),
+    (10.0, 1),
+    (0.1, 2),
+    (0.3, 2),
+    (0.5, 2),
+    (0.7, 2),
+    (0.8, 2),
+    (0.9, 2),
+    (1.5, 2),
+    (4.0, 2),
+    (20.0, 2),
+    (0.05, 3),
+    (0.15, 3),
+    (0.25, 3),
+    (0.35, 3),
+    (0.45, 3),
+    (1.2, 3),
+    (1.4, 3),
+    (1.6, 3),
+    (1.8, 3),
+    (2.5, 3),
+    (6.0, 3),
+    (8.0, 3),
+    (50.0, 3),
+];
+
+/// How the real axis is drawn, see [`crate::SmithChart::real_axis`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RealAxis {
+    pub visible: bool,
+    /// Stroke of the axis and its ticks, `None` for a regular grid line
+    pub stroke: Option<Stroke>,
+    /// Length in points of the ticks at the coarse and medium resistance values,
+    /// 0 for no ticks
+    pub tick_length: f32,
+}
+impl Default for RealAxis {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            stroke: None,
+            tick_length: 0.0,
+        }
+    }
+}
+impl RealAxis {
+    /// No real axis at all
+    pub fn hidden() -> Self {
+        Self {
+            visible: false,
+            ..Default::default()
+        }
+    }
+
+    pub fn stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+
+    /// Tick the axis where the coarse and medium resistance circles cross it
+    pub fn ticks(mut self, length: f32) -> Self {
+        self.tick_length = length;
+        self
+    }
+}
+
+/// Grid circle or arc of a given value, see [`crate::SmithChart::emphasize`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GridCircle {
+    /// Circle of constant normalized resistance
+    Resistance(f32),
+    /// Arc of constant normalized reactance, 0 is the real axis
+    Reactance(f32),
+    /// Circle of constant normalized conductance
+    Conductance(f32),
+    /// Arc of constant normalized susceptance, 0 is the real axis
+    Susceptance(f32),
+}
+impl GridCircle {
+    /// Whether the circle belongs to the admittance grid
+    pub(crate) fn is_admittance(&self) -> bool {
+        matches!(self, Self::Conductance(_) | Self::Susceptance(_))
+    }
+}
+
+/// Fixed amount of grid detail, regardless of the chart size
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum GridDensity {
+    /// A few circles and arcs
+    Coarse,
+    /// The usual values of a pocket chart
+    Medium,
+    /// Circles every 0.1 up to 1
+    Fine,
+    /// Close to a printed chart, with circles every 0.05 up to 0.5.
+    /// Labels stop at the fine circles.
+    Paper,
+}
+impl GridDensity {
+    /// Finest level of grid circles drawn
+    pub(crate) fn grid_level(&self) -> u8 {
+        match self {
+            Self::Coarse => 0,
+            Self::Medium => 1,
+            Self::Fine => 2,
+            Self::Paper => 3,
+        }
+    }
+
+    /// Finest level of labelled circles
+    pub(crate) fn label_level(&self) -> u8 {
+        self.grid_level().min(2)
+    }
+}
+impl std::fmt::Display for GridDensity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Coarse => "coarse",
+            Self::Medium => "medium",
+            Self::Fine => "fine",
+            Self::Paper => "paper",
+        })
+    }
+}
+
+/// Radius of the unit circle in points above which each finer level of circles is drawn
+const GRID_THRESHOLDS: [f32; 2] = [150.0, 350.0];
+
+/// Radius of the unit circle in points above which each level of labels is drawn.
+/// Labels need more room than their circles, so they come in later.
+const LABEL_THRESHOLDS: [f32; 3] = [80.0, 300.0, 700.0];
+
+/// Finest level of grid circles for a unit circle of `radius` points
+pub(crate) fn grid_level(radius: f32) -> u8 {
+    GRID_THRESHOLDS.iter().filter(|&&t| radius >= t).count() as u8
+}
+
+/// Finest level of labelled circles for a unit circle of `radius` points,
+/// `None` if even the coarse circles are too crowded for labels
+pub(crate) fn label_level(radius: f32) -> Option<u8> {
+    (LABEL_THRESHOLDS.iter().filter(|&&t| radius >= t).count() as u8).checked_sub(1)
+}
+
+/// Number of grids kept in the cache, enough for a window of differently sized charts
+const CACHE_SIZE: usize = 16;
+
+/// Temp data key marking contexts that paint for vector output
+const VECTOR_OUTPUT: &str = "egui_smith_chart_vector_output";
+
+/// Paint grids in `ctx` as lines instead of meshes, for exporting the painted shapes
+pub(crate) fn keep_vectors(ctx: &Context) {
+    ctx.data().insert_temp(Id::new(VECTOR_OUTPUT), true);
+}
+
+/// Run `paint` with the grids of `ctx` built afresh as lines, so the output doesn't
+/// depend on what the cache holds or the tessellation options it was filled with
+pub(crate) fn uncached<R>(ctx: &Context, paint: impl FnOnce() -> R) -> R {
+    let id = Id::new(VECTOR_OUTPUT);
+    let marked = ctx.data().get_temp::<bool>(id).is_some();
+    keep_vectors(ctx);
+    let result = paint();
+    if !marked {
+        ctx.data().remove::<bool>(id);
+    }
+    result
+}
+
+/// Everything the grid of a full chart depends on, the key of the grid cache
+pub(crate) struct GridKey<'a> {
+    pub size: Vec2,
+    pub center: Complex<f32>,
+    pub zoom: f32,
+    pub pixels_per_point: f32,
+    pub plane: Plane,
+    pub z0: Complex<f32>,
+    pub format: &'a NumberFormat,
+    pub style: &'a SmithChartStyle,
+    pub grid_color: Color32,
+    pub line: Stroke,
+    pub density: Option<GridDensity>,
+    pub adaptive: bool,
+    pub emphasized: Option<&'a [GridCircle]>,
+    pub real_axis: RealAxis,
+    pub angle_ticks: bool,
+    pub radial_labels: Option<f32>,
+    pub labels: bool,
+}
+impl Hash for GridKey<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // floats by their bits, like epaint hashes its shapes
+        let line_style = |line_style: LineStyle| match line_style {
+            LineStyle::Solid => [0, 0, 0],
+            LineStyle::Dashed { length, gap } => [1, length.to_bits(), gap.to_bits()],
+            LineStyle::Dotted { spacing } => [2, spacing.to_bits(), 0],
+        };
+        let circle = |circle: &GridCircle| match *circle {
+            GridCircle::Resistance(r) => (0, r.to_bits()),
+            GridCircle::Reactance(x) => (1, x.to_bits()),
+            GridCircle::Conductance(g) => (2, g.to_bits()),
+            GridCircle::Susceptance(b) => (3, b.to_bits()),
+        };
+        let style = self.style;
+        [
+            self.size.x,
+            self.size.y,
+            self.center.re,
+            self.center.im,
+            self.zoom,
+            self.pixels_per_point,
+            self.z0.re,
+            self.z0.im,
+            self.line.width,
+            self.real_axis.tick_length,
+            style.font_size,
+            style.strong_grid_width,
+        ]
+        .map(f32::to_bits)
+        .hash(state);
+        (self.plane, self.format, self.grid_color, self.line.color).hash(state);
+        (style.strong_grid_color, style.admittance_grid_color).hash(state);
+        line_style(style.grid_line_style).hash(state);
+        line_style(style.admittance_grid_line_style).hash(state);
+        (self.density, self.adaptive, self.angle_ticks, self.labels).hash(state);
+        self.radial_labels.map(f32::to_bits).hash(state);
+        let axis_stroke = self.real_axis.stroke;
+        (
+            self.real_axis.visible,
+            axis_stroke.map(|s| (s.width.to_bits(), s.color)),
+        )
+            .hash(state);
+        self.emphasized.map(<[_]>::len).hash(state);
+        for emphasized in self.emphasized.into_iter().flatten() {
+            circle(emphasized).hash(state);
+        }
+    }
+}
+
+/// Recently built grids relative to the chart corner, most recently used last
+#[derive(Clone, Default)]
+struct GridCache(Vec<(Id, Arc<Vec<Shape>>)>);
+
+/// Grid shapes for the configuration hashed into `key`, placed at the chart corner
+/// `origin`, and whether they were built. Charts with the same key share one grid
+/// through the egui context, so `build` only runs, and its lines are only tessellated,
+/// when no chart built the grid recently. Contexts marked with [`keep_vectors`] get
+/// the lines of `build` as they are.
+pub(crate) fn cached(
+    ctx: &Context,
+    key: impl Hash,
+    origin: Pos2,
+    build: impl FnOnce() -> Vec<Shape>,
+) -> (Vec<Shape>, bool) {
+    let vectors: Option<bool> = ctx.data().get_temp(Id::new(VECTOR_OUTPUT));
+    if vectors.is_some() {
+        return (build(), true);
+    }
+    let cache_id = Id::new("egui_smith_chart_grid_cache");
+    let key = Id::new(key);
+    let mut cache: GridCache = ctx.data().get_temp(cache_id).unwrap_or_default();
+    let hit = cache.0.iter().position(|(k, _)| *k == key);
+    let grid = match hit {
+        Some(i) => cache.0.remove(i).1,
+        None => {
+            diagnostic!(debug, "grid cache miss, rebuilding {:?}", key);
+            let mut shapes = tessellate(ctx, build());
+            for shape in &mut shapes {
+                shape.translate(-origin.to_vec2());
+            }
+            Arc::new(shapes)
+        }
+    };
+    cache.0.push((key, grid.clone()));
+    if cache.0.len() > CACHE_SIZE {
+        cache.0.remove(0);
+    }
+    ctx.data().insert_temp(cache_id, cache);
+
+    let mut shapes = grid.as_ref().clone();
+    for shape in &mut shapes {
+        shape.translate(origin.to_vec2());
+    }
+    (shapes, hit.is_none())
+}
+
+/// `shapes` with everything but text tessellated into a single mesh. Text is left to
+/// egui, as its texture coordinates depend on the size of the growing font atlas.
+fn tessellate(ctx: &Context, shapes: Vec<Shape>) -> Vec<Shape> {
+    // one context lock at a time
+    let options = *ctx.tessellation_options();
+    let font_image_size = ctx.fonts().font_image_size();
+    let mut tessellator =
+        Tessellator::new(ctx.pixels_per_point(), options, font_image_size, Vec::new());
+    let mut mesh = Mesh::default();
+    let mut text = Vec::new();
+    for shape in shapes {
+        match shape {
+            Shape::Text(_) => text.push(shape),
+            shape => tessellator.tessellate_shape(shape, &mut mesh),
+        }
+    }
+    std::iter::once(Shape::mesh(mesh)).chain(text).collect()
+}
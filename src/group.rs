@@ -0,0 +1,128 @@
+//! Traces shown, hidden and styled together.
+
+use egui::Color32;
+
+use crate::{Palette, Trace};
+
+/// How the color changes across the members of a [`TraceGroup`]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Variation {
+    /// Same color for every trace
+    #[default]
+    None,
+    /// Hue rotated by up to this many degrees at the last trace
+    Hue(f32),
+    /// Opacity falling to this fraction at the last trace
+    Alpha(f32),
+}
+
+/// Named set of traces, e.g. the reflection parameters of one multi-port or a family
+/// of process corners.
+///
+/// Style set on the group overrides the style of its traces. The group is a single
+/// legend entry that expands into its traces.
+/// ```
+/// # use egui_smith_chart::{Trace, TraceGroup, Variation};
+/// # fn corners(ss: Trace, tt: Trace, ff: Trace) -> TraceGroup {
+/// TraceGroup::new("corners", vec![ss, tt, ff])
+///     .color(egui::Color32::LIGHT_BLUE)
+///     .variation(Variation::Hue(90.0))
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TraceGroup {
+    pub name: String,
+    pub traces: Vec<Trace>,
+    pub visible: bool,
+    pub color: Option<Color32>,
+    pub width: Option<f32>,
+    pub variation: Variation,
+}
+impl TraceGroup {
+    pub fn new(name: impl Into<String>, traces: Vec<Trace>) -> Self {
+        Self {
+            name: name.into(),
+            traces,
+            visible: true,
+            color: None,
+            width: None,
+            variation: Variation::None,
+        }
+    }
+
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Ramp the color of the traces, starting from the group color
+    pub fn variation(mut self, variation: Variation) -> Self {
+        self.variation = variation;
+        self
+    }
+
+    /// Traces with the group name and style applied
+    pub(crate) fn into_traces(self) -> impl Iterator<Item = Trace> {
+        let Self {
+            name,
+            traces,
+            visible,
+            color,
+            width,
+            variation,
+        } = self;
+        let base = color
+            .or_else(|| traces.first().and_then(|trace| trace.color))
+            .unwrap_or_else(|| Palette::Default.color(0));
+        let last = traces.len().saturating_sub(1).max(1) as f32;
+        traces.into_iter().enumerate().map(move |(i, mut trace)| {
+            let t = i as f32 / last;
+            trace.group = Some(name.clone());
+            trace.visible &= visible;
+            trace.color = match variation {
+                Variation::None => color.or(trace.color),
+                Variation::Hue(degrees) => Some(rotate_hue(base, t * degrees)),
+                Variation::Alpha(min) => Some(base.linear_multiply(1.0 - t * (1.0 - min))),
+            };
+            trace.width = width.unwrap_or(trace.width);
+            trace
+        })
+    }
+}
+
+/// `color` with its hue rotated by `degrees`, keeping the luminance
+fn rotate_hue(color: Color32, degrees: f32) -> Color32 {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    // luminance preserving rotation around the gray axis, as CSS hue-rotate()
+    let matrix = [
+        [
+            0.213 + 0.787 * cos - 0.213 * sin,
+            0.715 - 0.715 * cos - 0.715 * sin,
+            0.072 - 0.072 * cos + 0.928 * sin,
+        ],
+        [
+            0.213 - 0.213 * cos + 0.143 * sin,
+            0.715 + 0.285 * cos + 0.140 * sin,
+            0.072 - 0.072 * cos - 0.283 * sin,
+        ],
+        [
+            0.213 - 0.213 * cos - 0.787 * sin,
+            0.715 - 0.715 * cos + 0.715 * sin,
+            0.072 + 0.928 * cos + 0.072 * sin,
+        ],
+    ];
+    let rgb = [color.r(), color.g(), color.b()].map(|c| c as f32);
+    let [r, g, b] = matrix
+        .map(|row| (row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]).clamp(0.0, 255.0) as u8);
+    Color32::from_rgba_premultiplied(r, g, b, color.a())
+}
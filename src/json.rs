@@ -0,0 +1,325 @@
+//! JSON interchange of traces and markers, for handing data over from other tools
+//! such as Python scripts without writing Touchstone files.
+//!
+//! Complex values are `[re, im]` pairs, frequencies are in Hz and colors are
+//! `"#rrggbb"` or `"#rrggbbaa"`. NaN and infinite values, which JSON has no numbers for,
+//! are written as `null` and read back as NaN. Everything but `name` and `gamma` is
+//! optional:
+//! ```json
+//! {
+//!   "version": 1,
+//!   "traces": [
+//!     {
+//!       "name": "antenna",
+//!       "z0": [50.0, 0.0],
+//!       "frequencies": [2.40e9, 2.45e9, 2.50e9],
+//!       "gamma": [[0.31, -0.12], [0.05, 0.02], [-0.22, 0.18]],
+//!       "color": "#40a0ff",
+//!       "annotations": [{ "frequency": 2.45e9, "text": "center" }]
+//!     }
+//!   ],
+//!   "markers": [{ "trace": 0, "frequency": 2.45e9 }]
+//! }
+//! ```
+//! From numpy, `gamma` is `np.stack([s11.real, s11.imag], axis=-1).tolist()`.
+
+use egui::{Color32, Context, Id};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Annotation, Complex, Interpolation, Marker, Plane, SmithChart, SmithChartError,
+    SmithChartState, SmithChartStyle, Trace,
+};
+
+/// Schema version written by [`export`]
+pub const VERSION: u32 = 1;
+
+/// Top level of the JSON document
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Document {
+    pub version: u32,
+    pub traces: Vec<TraceData>,
+    #[serde(default)]
+    pub markers: Vec<Marker>,
+}
+
+/// A [`Trace`] in the JSON document
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TraceData {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z0: Option<[f32; 2]>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "nullable")]
+    pub frequencies: Vec<f64>,
+    #[serde(with = "nullable_pairs")]
+    pub gamma: Vec<[f32; 2]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default = "default_width")]
+    pub width: f32,
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+    #[serde(default)]
+    pub interpolation: Interpolation,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<AnnotationData>,
+}
+
+fn default_width() -> f32 {
+    2.0
+}
+
+fn default_opacity() -> f32 {
+    1.0
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+/// An [`Annotation`] in the JSON document
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnnotationData {
+    pub frequency: f64,
+    pub text: String,
+}
+
+impl From<&Trace> for TraceData {
+    fn from(trace: &Trace) -> Self {
+        Self {
+            name: trace.name.clone(),
+            group: trace.group.clone(),
+            z0: trace.z0.map(|z0| [z0.re, z0.im]),
+            frequencies: trace.frequencies.clone(),
+            gamma: trace.gamma.iter().map(|g| [g.re, g.im]).collect(),
+            color: trace.color.map(|color| {
+                let [r, g, b, a] = color.to_srgba_unmultiplied();
+                if a == u8::MAX {
+                    format!("#{:02x}{:02x}{:02x}", r, g, b)
+                } else {
+                    format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+                }
+            }),
+            width: trace.width,
+            opacity: trace.opacity,
+            visible: trace.visible,
+            interpolation: trace.interpolation,
+            annotations: trace
+                .annotations
+                .iter()
+                .map(|annotation| AnnotationData {
+                    frequency: annotation.frequency,
+                    text: annotation.text.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+impl TryFrom<TraceData> for Trace {
+    type Error = SmithChartError;
+
+    fn try_from(data: TraceData) -> Result<Self, SmithChartError> {
+        if !data.frequencies.is_empty() && data.frequencies.len() != data.gamma.len() {
+            return Err(SmithChartError::LengthMismatch(data.name));
+        }
+        let gamma: Vec<_> = data
+            .gamma
+            .iter()
+            .map(|&[re, im]| Complex::new(re, im))
+            .collect();
+        let mut trace = Trace::new(data.name, gamma)
+            .frequencies(data.frequencies)
+            .width(data.width)
+            .opacity(data.opacity)
+            .visible(data.visible)
+            .interpolation(data.interpolation);
+        trace.group = data.group;
+        if let Some([re, im]) = data.z0 {
+            trace = trace.z0(Complex::new(re, im));
+        }
+        if let Some(color) = data.color {
+            trace = trace.color(parse_color(&color).ok_or(SmithChartError::InvalidColor(color))?);
+        }
+        for annotation in data.annotations {
+            trace = trace.annotation(Annotation::new(annotation.frequency, annotation.text));
+        }
+        Ok(trace)
+    }
+}
+
+/// JSON document holding `traces` and `markers`:
+/// ```
+/// # use egui_smith_chart::{json, Complex, Trace};
+/// let trace = Trace::new("probe", vec![Complex::new(f32::NAN, 0.0)]).width(3.0);
+/// let (traces, _) = json::import(&json::export(&[trace], &[])).unwrap();
+/// assert!(traces[0].gamma[0].re.is_nan());
+/// assert_eq!(traces[0].width, 3.0);
+/// ```
+pub fn export(traces: &[Trace], markers: &[Marker]) -> String {
+    let document = Document {
+        version: VERSION,
+        traces: traces.iter().map(TraceData::from).collect(),
+        markers: markers.to_vec(),
+    };
+    serde_json::to_string_pretty(&document).expect("traces always serialize")
+}
+
+/// Traces and markers of a JSON document
+pub fn import(text: &str) -> Result<(Vec<Trace>, Vec<Marker>), SmithChartError> {
+    let document: Document = serde_json::from_str(text)?;
+    if document.version > VERSION {
+        return Err(SmithChartError::UnsupportedVersion(document.version));
+    }
+    let traces = document
+        .traces
+        .into_iter()
+        .map(Trace::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok((traces, document.markers))
+}
+
+/// Everything needed to show a chart the way it was exported with
+/// [`SmithChart::export_session`], e.g. to share a setup with a colleague:
+/// ```no_run
+/// # use egui_smith_chart::{json::Session, SmithChartError};
+/// # fn ui(ui: &mut egui::Ui, text: &str) -> Result<(), SmithChartError> {
+/// let session = Session::import(ui.ctx(), "chart", text)?;
+/// // every frame
+/// session.chart("chart").size(400.0).show(ui);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Session {
+    pub z0: Complex<f32>,
+    pub plane: Plane,
+    pub style: SmithChartStyle,
+    pub traces: Vec<Trace>,
+    /// Markers, view and hidden traces
+    pub state: SmithChartState,
+}
+
+/// [`Session`] in the JSON document
+#[derive(Serialize, Deserialize)]
+struct SessionData {
+    version: u32,
+    z0: [f32; 2],
+    plane: Plane,
+    style: SmithChartStyle,
+    traces: Vec<TraceData>,
+    state: SmithChartState,
+}
+
+impl Session {
+    pub fn to_json(&self) -> String {
+        let data = SessionData {
+            version: VERSION,
+            z0: [self.z0.re, self.z0.im],
+            plane: self.plane,
+            style: self.style.clone(),
+            traces: self.traces.iter().map(TraceData::from).collect(),
+            state: self.state.clone(),
+        };
+        serde_json::to_string_pretty(&data).expect("sessions always serialize")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, SmithChartError> {
+        let data: SessionData = serde_json::from_str(text)?;
+        if data.version > VERSION {
+            return Err(SmithChartError::UnsupportedVersion(data.version));
+        }
+        Ok(Self {
+            z0: Complex::new(data.z0[0], data.z0[1]),
+            plane: data.plane,
+            style: data.style,
+            traces: data
+                .traces
+                .into_iter()
+                .map(Trace::try_from)
+                .collect::<Result<_, _>>()?,
+            state: data.state,
+        })
+    }
+
+    /// Read a session and put its markers and view into the state of the chart
+    /// with `id_source`
+    pub fn import(
+        ctx: &Context,
+        id_source: impl std::hash::Hash,
+        text: &str,
+    ) -> Result<Self, SmithChartError> {
+        let session = Self::from_json(text)?;
+        session.state.clone().store(ctx, Id::new(id_source));
+        Ok(session)
+    }
+
+    /// Chart configured like the exported one, to be built on further
+    pub fn chart<'a>(&self, id_source: impl std::hash::Hash) -> SmithChart<'a> {
+        self.traces.iter().fold(
+            SmithChart::new(id_source)
+                .z0(self.z0)
+                .plane(self.plane)
+                .style(self.style.clone()),
+            |chart, trace| chart.trace(trace.clone()),
+        )
+    }
+}
+
+/// Numbers that are `null` in JSON when they aren't finite
+mod nullable {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(values: &[f64], serializer: S) -> Result<S::Ok, S::Error> {
+        let values: Vec<_> = values.iter().map(|v| v.is_finite().then_some(*v)).collect();
+        values.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f64>, D::Error> {
+        let values = Vec::<Option<f64>>::deserialize(deserializer)?;
+        Ok(values.into_iter().map(|v| v.unwrap_or(f64::NAN)).collect())
+    }
+}
+
+/// `[re, im]` pairs whose parts are `null` in JSON when they aren't finite
+mod nullable_pairs {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(values: &[[f32; 2]], serializer: S) -> Result<S::Ok, S::Error> {
+        let finite = |v: f32| v.is_finite().then_some(v);
+        let values: Vec<_> = values
+            .iter()
+            .map(|&[re, im]| [finite(re), finite(im)])
+            .collect();
+        values.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<[f32; 2]>, D::Error> {
+        let values = Vec::<[Option<f32>; 2]>::deserialize(deserializer)?;
+        Ok(values
+            .into_iter()
+            .map(|pair| pair.map(|v| v.unwrap_or(f32::NAN)))
+            .collect())
+    }
+}
+
+/// `#rrggbb` or `#rrggbbaa`
+fn parse_color(text: &str) -> Option<Color32> {
+    let hex = text.strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { u8::MAX };
+    Some(Color32::from_rgba_unmultiplied(
+        channel(0)?,
+        channel(2)?,
+        channel(4)?,
+        alpha,
+    ))
+}
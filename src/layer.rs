@@ -0,0 +1,75 @@
+//! Paint order of the chart elements.
+
+use std::ops::{Index, IndexMut};
+
+use egui::Shape;
+
+/// Group of chart elements painted together, see [`crate::SmithChart::layer_order`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Layer {
+    /// Background fill
+    Background,
+    /// Resistance and reactance circles
+    Grid,
+    /// Shaded [`crate::Region`]s
+    Regions,
+    /// Traces and curves
+    Traces,
+    /// Markers and their labels
+    Markers,
+    /// Annotations, selection band and [`crate::SmithChart::on_paint`] decorations
+    Overlays,
+    /// Cursor circles and readout text
+    Readout,
+}
+impl Layer {
+    /// All layers, bottom to top in the default order
+    pub const ALL: [Layer; 7] = [
+        Layer::Background,
+        Layer::Grid,
+        Layer::Regions,
+        Layer::Traces,
+        Layer::Markers,
+        Layer::Overlays,
+        Layer::Readout,
+    ];
+}
+impl std::fmt::Display for Layer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Background => "Background",
+            Self::Grid => "Grid",
+            Self::Regions => "Regions",
+            Self::Traces => "Traces",
+            Self::Markers => "Markers",
+            Self::Overlays => "Overlays",
+            Self::Readout => "Readout",
+        })
+    }
+}
+
+/// Shapes collected per layer before painting
+#[derive(Default)]
+pub(crate) struct LayerShapes([Vec<Shape>; 7]);
+impl LayerShapes {
+    pub fn iter(&self) -> impl Iterator<Item = &Shape> {
+        self.0.iter().flatten()
+    }
+
+    pub fn take(&mut self, layer: Layer) -> Vec<Shape> {
+        std::mem::take(&mut self[layer])
+    }
+}
+impl Index<Layer> for LayerShapes {
+    type Output = Vec<Shape>;
+
+    fn index(&self, layer: Layer) -> &Vec<Shape> {
+        &self.0[layer as usize]
+    }
+}
+impl IndexMut<Layer> for LayerShapes {
+    fn index_mut(&mut self, layer: Layer) -> &mut Vec<Shape> {
+        &mut self.0[layer as usize]
+    }
+}
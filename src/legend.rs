@@ -0,0 +1,159 @@
+//! Legend listing the traces, with trace groups and the traces of each file folded
+//! into one entry.
+
+use egui::{pos2, vec2, Color32, Rect, Vec2};
+
+use crate::{Line, Palette, Trace};
+
+/// Distance of the legend from the corner of the chart
+const MARGIN: Vec2 = vec2(8.0, 8.0);
+
+/// Space between the entries and the border of the legend
+pub(crate) const PADDING: f32 = 4.0;
+
+/// Width of the color swatch before each entry
+pub(crate) const SWATCH: f32 = 16.0;
+
+/// Indentation of the traces of an expanded group
+const INDENT: f32 = 12.0;
+
+/// Where the legend of a [`crate::SmithChart`] goes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum LegendPlacement {
+    TopLeft,
+    TopRight,
+    /// Shares the corner with the readout lines, best without hover readouts
+    BottomLeft,
+    BottomRight,
+    /// Right of the chart, in space allocated next to it
+    Outside,
+    /// Not painted, for the app to render the entries of
+    /// [`crate::SmithChartResponse::legend`] itself
+    External,
+}
+
+/// Row of the legend
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegendEntry {
+    pub text: String,
+    /// Color of the trace, or of every trace of a group
+    pub colors: Vec<Color32>,
+    /// Group the entry stands for, clicking it expands or collapses the group and
+    /// clicking its swatch hides or shows the group
+    pub group: Option<String>,
+    /// Nesting level, 1 for the traces of an expanded group
+    pub depth: u8,
+    /// The trace, or every trace of the group, is hidden
+    pub hidden: bool,
+}
+
+/// Entries for `traces` and then `lines`, in the order they were added. Each group
+/// takes the place of its first trace and lists its traces below when it is in
+/// `expanded`. Traces loaded from the same file form a group named after the file,
+/// unless they have a group of their own or are the only trace of the file.
+pub(crate) fn entries(
+    traces: &[Trace],
+    lines: &[Line],
+    palette: Palette,
+    expanded: &[String],
+) -> Vec<LegendEntry> {
+    let color = |i: usize| traces[i].color.unwrap_or(palette.color(i));
+    let mut entries = Vec::new();
+    let mut seen_groups = Vec::new();
+    for (i, trace) in traces.iter().enumerate() {
+        let group = trace.legend_group();
+        if matches!(group, Some(group) if seen_groups.contains(&group)) {
+            continue;
+        }
+        let members: Vec<usize> = match group {
+            Some(group) => (i..traces.len())
+                .filter(|&j| traces[j].legend_group() == Some(group))
+                .collect(),
+            None => vec![i],
+        };
+        // a file with a single trace needs no section of its own
+        let group = match group {
+            Some(group) if trace.group.is_some() || members.len() > 1 => group,
+            _ => {
+                entries.push(LegendEntry {
+                    text: trace.name.clone(),
+                    colors: vec![color(i)],
+                    group: None,
+                    depth: 0,
+                    hidden: !trace.visible,
+                });
+                continue;
+            }
+        };
+        seen_groups.push(group);
+
+        let open = expanded.contains(group);
+        entries.push(LegendEntry {
+            text: format!("{} {}", if open { "⏷" } else { "⏵" }, group),
+            colors: members.iter().map(|&j| color(j)).collect(),
+            group: Some(group.clone()),
+            depth: 0,
+            hidden: members.iter().all(|&j| !traces[j].visible),
+        });
+        if open {
+            entries.extend(members.into_iter().map(|j| LegendEntry {
+                text: traces[j].name.clone(),
+                colors: vec![color(j)],
+                group: None,
+                depth: 1,
+                hidden: !traces[j].visible,
+            }));
+        }
+    }
+    entries.extend(lines.iter().enumerate().map(|(i, line)| LegendEntry {
+        text: line.name.clone(),
+        colors: vec![line.color.unwrap_or(palette.color(traces.len() + i))],
+        group: None,
+        depth: 0,
+        hidden: false,
+    }));
+    entries
+}
+
+/// Screen rects of the rows of the legend of the chart in `rect`, for entries whose
+/// texts are `text_widths` wide. Empty for [`LegendPlacement::External`].
+pub(crate) fn row_rects(
+    rect: Rect,
+    placement: LegendPlacement,
+    entries: &[LegendEntry],
+    text_widths: &[f32],
+    row_height: f32,
+) -> Vec<Rect> {
+    let widths: Vec<f32> = entries
+        .iter()
+        .zip(text_widths)
+        .map(|(entry, text_width)| entry.depth as f32 * INDENT + SWATCH + 4.0 + text_width)
+        .collect();
+    let size = vec2(
+        widths.iter().copied().fold(0.0, f32::max),
+        entries.len() as f32 * row_height,
+    );
+    let min = match placement {
+        LegendPlacement::TopLeft => rect.left_top() + MARGIN,
+        LegendPlacement::TopRight => pos2(rect.right() - MARGIN.x - size.x, rect.top() + MARGIN.y),
+        LegendPlacement::BottomLeft => {
+            pos2(rect.left() + MARGIN.x, rect.bottom() - MARGIN.y - size.y)
+        }
+        LegendPlacement::BottomRight => rect.right_bottom() - MARGIN - size,
+        LegendPlacement::Outside => rect.right_top() + vec2(MARGIN.x, 0.0),
+        LegendPlacement::External => return Vec::new(),
+    };
+    entries
+        .iter()
+        .zip(widths)
+        .enumerate()
+        .map(|(row, (entry, width))| {
+            let indent = entry.depth as f32 * INDENT;
+            Rect::from_min_size(
+                min + vec2(indent, row as f32 * row_height),
+                vec2(width - indent, row_height),
+            )
+        })
+        .collect()
+}
@@ -2,11 +2,106 @@ use std::{borrow::Borrow, error::Error, rc::Rc};
 
 use egui::epaint::PathShape;
 use egui::{
+    emath,
     plot::{self, PlotPoints},
-    pos2, vec2, Align2, Color32, FontId, Id, Painter, Pos2, Rect, Sense, Stroke, Vec2,
+    pos2, vec2, Align, Align2, Color32, FontId, Id, Painter, Pos2, Rect, Sense, Shape, Stroke,
+    Vec2,
 };
-use num::traits::Pow;
-use num::Complex;
+
+/// Diagnostics for the logger of the host app, e.g. `diagnostic!(warn, "...")`. They
+/// go through the `log` facade with the `log` feature and are compiled out without it.
+macro_rules! diagnostic {
+    ($level:ident, $($arg:tt)+) => {{
+        #[cfg(feature = "log")]
+        log::$level!(target: "egui_smith_chart", $($arg)+);
+        #[cfg(not(feature = "log"))]
+        if false {
+            let _ = format_args!($($arg)+);
+        }
+    }};
+}
+
+mod complex;
+pub mod contour;
+mod curve;
+mod details;
+mod error;
+pub mod format;
+mod gain;
+mod grid;
+mod group;
+#[cfg(feature = "json")]
+pub mod json;
+mod layer;
+mod legend;
+mod line;
+#[cfg(feature = "rfd")]
+pub mod loader;
+pub mod loadpull;
+mod locale;
+mod magnitude;
+mod math;
+pub mod measure;
+pub mod network;
+mod noise;
+mod pdf;
+mod picker;
+mod points;
+pub mod preprocess;
+mod raster;
+mod region;
+pub mod report;
+mod response;
+mod settings;
+mod shortcuts;
+mod snippet;
+mod stability;
+mod state;
+mod style;
+mod svg;
+mod tolerance;
+pub mod touchstone;
+mod trace;
+mod transform;
+mod tuner;
+mod vector;
+
+pub use complex::{Complex, Real};
+pub use contour::{Contour, ContourSet};
+pub use curve::{Circle, Curve, Domain};
+pub use details::TraceDetails;
+pub use error::SmithChartError;
+pub use format::{AngleUnit, ComplexForm, FrequencyUnit, NumberFormat, ValueDisplay};
+pub use gain::{GainCircles, GainKind};
+pub use grid::{GridCircle, GridDensity, RealAxis};
+pub use group::{TraceGroup, Variation};
+pub use layer::Layer;
+use layer::LayerShapes;
+pub use legend::{LegendEntry, LegendPlacement};
+pub use line::Line;
+pub use locale::Locale;
+pub use magnitude::MagnitudePlot;
+pub use network::{Matrix2, Network, Parameters};
+pub use noise::NoiseCircles;
+pub use picker::impedance_picker;
+pub use points::SmithPoints;
+use preprocess::Density;
+pub use region::{Region, SpecLimit, SpecMask};
+pub use response::{Cursor, PaintStats, SmithChartResponse};
+pub use settings::ChartSettings;
+pub use shortcuts::{Command, Shortcuts};
+pub use snippet::CodeLanguage;
+use snippet::SnippetPoint;
+pub use stability::{Stability, StabilityPlot};
+pub use state::{Marker, SmithChartState};
+pub use style::{LineStyle, Palette, SmithChartStyle};
+pub use tolerance::Uncertainty;
+pub use trace::{
+    Annotation, ComparisonLocus, Deviation, FrequencyScale, FrequencyTicks, Interpolation,
+    Resonance, ResonanceKind, Trace, TraceHit, TraceStats, Validity,
+};
+pub use transform::ChartTransform;
+pub use tuner::{Component, Element, Topology, Tuner};
 
 // TODO: add theme support
 // TODO: don't normalized to clipping plane, it's not necessarily a square if the window is resized.
@@ -14,25 +109,43 @@ use num::Complex;
 // signature pink debug colour
 const DEBUG_PINK: Color32 = Color32::from_rgb(255, 0, 255);
 
-#[derive(PartialEq, Eq)]
+// distance in normalized conductance from g=1 within which the stub helper shows
+const STUB_SNAP: f32 = 0.05;
+
+// extra significant digits in readouts with shift held, and pointer speed while
+// dragging with the precision modifier of the shortcuts held
+const PRECISION_DIGITS: usize = 2;
+const PRECISION_DRAG: f32 = 0.2;
+
+// position relative to a hovered marker and radius of its close button
+const CLOSE_OFFSET: Vec2 = vec2(-9.0, -9.0);
+const CLOSE_RADIUS: f32 = 4.0;
+
+// callbacks set by the application, see the `on_*` builders of `SmithChart`
+type ClickCallback<'a> = Box<dyn FnMut(Complex<f32>) + 'a>;
+type MarkerCallback<'a> = Box<dyn FnMut(usize, &Marker) + 'a>;
+type HoverCallback<'a> = Box<dyn FnMut(&TraceHit) + 'a>;
+type PaintCallback<'a> = Box<dyn Fn(&Painter, &ChartTransform) + 'a>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Plane {
     Impedance,
     Admittance,
     Both,
 }
-impl ToString for Plane {
-    fn to_string(&self) -> String {
-        match self {
+impl std::fmt::Display for Plane {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
             Self::Impedance => "impedance",
             Self::Admittance => "admittance",
             Self::Both => "impedance and admittance",
-        }
-        .to_string()
+        })
     }
 }
 
 #[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
-pub struct SmithChart {
+pub struct SmithChart<'a> {
     id_source: Id,
 
     /// Characteristic impedance
@@ -48,8 +161,123 @@ pub struct SmithChart {
 
     /// Enable drawing of VSWR circle under mouse position
     mouse_vswr: bool,
+
+    /// Formatting of readout values
+    format: NumberFormat,
+
+    /// Built-in strings
+    locale: Locale,
+
+    style: SmithChartStyle,
+
+    traces: Vec<Trace>,
+
+    /// Parametric curves drawn over the traces
+    curves: Vec<Curve<'a>>,
+
+    /// Lines over borrowed data, painted with the traces
+    lines: Vec<Line<'a>>,
+
+    /// List the traces in a legend, with groups folded into one entry
+    legend: bool,
+    legend_placement: LegendPlacement,
+
+    /// Show the transmission coefficient T = 1 + Γ under the cursor and at markers
+    transmission_coefficient: bool,
+
+    /// Compact rendering without labels, readouts or interaction
+    thumbnail: bool,
+
+    /// Mirror the cursor and selected markers through the center
+    admittance_ghost: bool,
+
+    /// Drags on empty chart space measure instead of selecting markers
+    ruler: bool,
+
+    /// Reference and measurement trace indices for deviation lines and metrics
+    comparison: Option<(usize, usize)>,
+    comparison_locus: ComparisonLocus,
+
+    /// Paint cursor circles and readout, otherwise the values are only returned
+    hover_readout: bool,
+    /// Show the cursor position on the wavelengths toward generator scale
+    wavelength_readout: bool,
+    /// Show the matching stub near the unit-conductance circle
+    stub_helper: bool,
+
+    /// List marker impedances in the readout
+    marker_readout: bool,
+    /// Mark where the reactance of swept traces crosses zero
+    resonance_markers: bool,
+    /// Form of complex values in marker and cursor readouts
+    readout_form: ComplexForm,
+    /// Normalize marker readouts to each trace's own reference impedance
+    native_reference: bool,
+
+    /// Add finer grid circles as the chart is enlarged or zoomed
+    adaptive_grid: bool,
+    /// Grid detail pinned by the caller, overriding the adaptive grid
+    grid_density: Option<GridDensity>,
+    /// Grid circles drawn with the strong stroke, `None` for the plane's own 0 and 1 circles
+    emphasized: Option<Vec<GridCircle>>,
+    real_axis: RealAxis,
+    /// Degree ticks of ∠Γ around the rim
+    angle_ticks: bool,
+    /// Direction in degrees of the spoke labelled with |Γ|
+    radial_labels: Option<f32>,
+    grid_labels: bool,
+
+    /// Shaded areas painted under the traces
+    regions: Vec<Region>,
+    /// Limits the traces are checked against
+    spec_masks: Vec<SpecMask>,
+
+    /// Point densities painted with the regions
+    densities: Vec<Density>,
+
+    /// Labelled contours painted with the regions
+    contours: Vec<ContourSet>,
+
+    /// Paint order of the chart elements
+    layers: Vec<Layer>,
+    hidden_layers: Vec<Layer>,
+
+    /// Markers placed by the application this frame, shown after the interactive ones
+    markers: Vec<Marker>,
+
+    /// Distance in points within which traces and markers react to the pointer
+    pick_radius: f32,
+    /// Show the metadata of the trace under the pointer in a tooltip
+    trace_tooltips: bool,
+    /// Gear icon opening a popup to edit grid, overlays and style
+    settings_popup: bool,
+
+    /// Offer copying markers or the clicked point as code on right click
+    context_menu: bool,
+    /// Accept samples with |Γ| > 1 of active devices without flagging them
+    extended: bool,
+
+    /// Drop a marker where the chart is double-clicked
+    double_click_markers: bool,
+    /// Clicking empty chart space freezes the cursor readout there
+    freeze_on_click: bool,
+    /// Interactions sensed by the chart, `None` to sense what the configuration uses
+    sense: Option<Sense>,
+    /// Only display the chart, without any interaction or cursor
+    read_only: bool,
+    /// Highlight the chart while loadable files are dragged over it
+    file_drop: bool,
+    /// Whether the user's changes from the state have been applied, see `resolve`
+    resolved: bool,
+
+    shortcuts: Shortcuts,
+
+    on_click: Option<ClickCallback<'a>>,
+    on_marker_moved: Option<MarkerCallback<'a>>,
+    on_trace_hover: Option<HoverCallback<'a>>,
+    on_paint: Option<PaintCallback<'a>>,
 }
-impl SmithChart {
+impl<'a> SmithChart<'a> {
     pub fn new(id_source: impl std::hash::Hash) -> Self {
         Self {
             id_source: Id::new(id_source),
@@ -58,10 +286,61 @@ impl SmithChart {
             size: 64.0,
             debug: false,
             mouse_vswr: false,
+            format: NumberFormat::default(),
+            locale: Locale::default(),
+            style: SmithChartStyle::default(),
+            traces: Vec::new(),
+            curves: Vec::new(),
+            lines: Vec::new(),
+            legend: false,
+            legend_placement: LegendPlacement::TopLeft,
+            transmission_coefficient: false,
+            thumbnail: false,
+            admittance_ghost: false,
+            ruler: false,
+            comparison: None,
+            comparison_locus: ComparisonLocus::Whiskers,
+            hover_readout: true,
+            wavelength_readout: false,
+            stub_helper: false,
+            marker_readout: true,
+            resonance_markers: false,
+            readout_form: ComplexForm::Rectangular,
+            native_reference: false,
+            adaptive_grid: false,
+            grid_density: None,
+            emphasized: None,
+            real_axis: RealAxis::default(),
+            angle_ticks: false,
+            radial_labels: None,
+            grid_labels: false,
+            regions: Vec::new(),
+            spec_masks: Vec::new(),
+            densities: Vec::new(),
+            contours: Vec::new(),
+            layers: Layer::ALL.to_vec(),
+            hidden_layers: Vec::new(),
+            markers: Vec::new(),
+            context_menu: false,
+            extended: false,
+            double_click_markers: false,
+            freeze_on_click: false,
+            sense: None,
+            read_only: false,
+            file_drop: false,
+            resolved: false,
+            shortcuts: Shortcuts::default(),
+            pick_radius: 8.0,
+            trace_tooltips: false,
+            settings_popup: false,
+            on_click: None,
+            on_marker_moved: None,
+            on_trace_hover: None,
+            on_paint: None,
         }
     }
 
-    pub fn show(&self, ui: &mut egui::Ui) -> egui::Response {
+    pub fn show(mut self, ui: &mut egui::Ui) -> SmithChartResponse {
         // Widget code can be broken up in four steps:
         //  1. Decide a size for the widget
         //  2. Allocate space for it
@@ -75,286 +354,3399 @@ impl SmithChart {
 
         // 2. Allocating space:
         // This is where we get a region of the screen assigned.
-        // We also tell the Ui to sense clicks in the allocated region.
-        let (rect, mut response) = ui.allocate_exact_size(desired_size, egui::Sense::hover());
-        let mut painter = ui.painter().with_clip_rect(rect);
-
-        let mut local_pos = None;
-        if let Some(pos) = response.hover_pos() {
-            local_pos = Some(self.abs_to_local(&rect, &pos.to_vec2()));
+        // Clicks and drags are only sensed when something uses them, so the chart
+        // doesn't steal drags from e.g. a surrounding scroll area, unless the caller
+        // picked the sense.
+        let mut state = SmithChartState::load(ui.ctx(), self.id_source).unwrap_or_default();
+        self.resolve(&state);
+        let mut sense = Sense::hover();
+        if self.on_click.is_some()
+            || ((!self.traces.is_empty()
+                || self.context_menu
+                || self.settings_popup
+                || self.double_click_markers
+                || self.freeze_on_click)
+                && !self.thumbnail)
+        {
+            sense = sense.union(Sense::click());
+        }
+        if (!state.markers.is_empty() || self.ruler) && !self.thumbnail {
+            sense = sense.union(Sense::drag());
         }
+        let sense = match self.sense {
+            _ if self.read_only => Sense::hover(),
+            Some(sense) => sense,
+            None => sense,
+        };
+        let (rect, response) = ui.allocate_exact_size(desired_size, sense);
 
-        // 4. Paint!
-        // Make sure we need to paint:
-        if ui.is_rect_visible(rect) {
-            // let (response, painter) =
-            //     ui.allocate_painter(Vec2::new(ui.available_width(), 300.0), Sense::hover());
+        // a legend outside the chart takes space of its own, which also senses its clicks
+        let outside_legend = (self.legend && self.legend_placement == LegendPlacement::Outside)
+            .then(|| self.legend_rows(ui.ctx(), rect, &state).1)
+            .and_then(|rows| rows.into_iter().reduce(Rect::union))
+            .map(|rows| {
+                let frame = rows.expand(legend::PADDING);
+                let space = Rect::from_min_max(
+                    pos2(rect.right(), rect.top()),
+                    pos2(frame.right(), frame.bottom().max(rect.bottom())),
+                );
+                ui.allocate_rect(space, Sense::click())
+            });
 
-            // We will follow the current style by asking
-            // "how should something that is being interacted with be painted?".
-            // This will, for instance, give us different colors when the widget is hovered or clicked.
-            let visuals = ui.style().interact(&response);
-            let normal_line = Stroke::new(1.0, visuals.fg_stroke.color);
-            let strong_line = Stroke::new(3.0, visuals.fg_stroke.color);
-            // All coordinates are in absolute screen coordinates so we use `rect` to place the elements.
-            let rect = rect.expand(visuals.expansion);
+        if self.read_only {
+            return self.show_read_only(ui, rect, response, state);
+        }
 
-            // draw reactance circles
-            let coarse_reactances = vec![0.4, 1.0, 3.0];
-            for x in coarse_reactances {
-                self.reactance_arc(ui, &mut painter, x, &normal_line);
-                self.reactance_arc(ui, &mut painter, -x, &normal_line);
+        // 3. Handle interactions
+        // ctrl + scroll or pinch zooms around the pointer
+        if let Some(pos) = response.hover_pos().filter(|_| !self.thumbnail) {
+            let zoom_delta = ui.input().zoom_delta();
+            if zoom_delta != 1.0 {
+                let anchor = state.transform(rect).screen_to_gamma(pos);
+                state.zoom_around(anchor, zoom_delta);
             }
+        }
+        let transform = state.transform(rect);
 
-            // draw resistance circles
-            let coarse_resistances = [0.0, 1.0 / 3.0, 1.0, 3.0];
-            for r in coarse_resistances {
-                self.resistance_circle(ui, &mut painter, r, &normal_line);
+        let hovered = response
+            .hover_pos()
+            .and_then(|pos| transform.nearest_trace_point(&self.traces, pos))
+            .filter(|hit| hit.distance <= self.pick_radius);
+        if let Some(hit) = &hovered {
+            if let Some(on_trace_hover) = &mut self.on_trace_hover {
+                on_trace_hover(hit);
             }
-            // emphasize r=0 and r=1
-            for r in [0.0, 1.0] {
-                self.resistance_circle(ui, &mut painter, r, &strong_line);
-            }
-
-            // zero reactance/susceptance curve (x-axis)
-            let xaxis_start_abs = self.local_to_abs(&rect, &vec2(-1.0, 0.0));
-            let xaxis_end_abs = self.local_to_abs(&rect, &vec2(1.0, 0.0));
-            painter.line_segment(
-                [xaxis_start_abs.to_pos2(), xaxis_end_abs.to_pos2()],
-                normal_line,
-            );
+        }
+        state.hovered_trace = hovered.as_ref().map(|hit| hit.trace);
 
-            // plot points/curves to Smith chart
-            // match plot_points {
-            //     PlotPoints::Points(points) => {
-            //         for p in points {
-            //             let gamma = self.z_to_gamma(p);
-            //             let local = self.gamma_to_local(&gamma);
-            //             let center_pos = self.local_to_abs(&rect, &local).to_pos2();
-            //             painter.circle_filled(center_pos, 8.0, Color32::YELLOW);
-            //         }
-            //     },
-            //     PlotPoints::Range(_) => todo!(),
-            // }
-
-            if let Some(local_pos) = local_pos {
-                let mouse_impedance = self.gamma_to_z(&Complex {
-                    re: local_pos.x,
-                    im: local_pos.y,
+        // double-clicking drops a marker on the trace under the pointer, or else a free
+        // marker at the pointer. Its second click doesn't also act as a single click.
+        let double_clicked =
+            response.double_clicked() && self.double_click_markers && !self.thumbnail;
+        if double_clicked {
+            if let Some(hit) = &hovered {
+                state.markers.push(Marker {
+                    trace: hit.trace,
+                    frequency: self.traces[hit.trace].sweep_at(hit.index),
                 });
-                if self.debug {
-                    println!(
-                        "Mouse Local (Gamma) = ({}, {}), z = {:?}",
-                        local_pos.x, local_pos.y, mouse_impedance
-                    );
+                state.selected_markers = vec![state.markers.len() - 1];
+            } else if let Some(pos) = response.interact_pointer_pos() {
+                let gamma = transform.screen_to_gamma(pos);
+                if gamma.norm() <= 1.0 {
+                    state.free_markers.push(gamma);
                 }
+            }
+        }
+        let clicked = response.clicked() && !double_clicked;
 
-                // check if mouse is inside the Smith chart
-                if local_pos.length() < 1.0 {
-                    // draw resistance and reactance circles under mouse
-                    self.resistance_circle(
-                        ui,
-                        &mut painter,
-                        mouse_impedance.re,
-                        &Stroke::new(1.0, Color32::GREEN),
-                    );
-                    self.reactance_arc(
-                        ui,
-                        &mut painter,
-                        mouse_impedance.im,
-                        &Stroke::new(1.0, Color32::RED),
-                    );
-
-                    const font_size: f32 = 14.0;
-                    painter.text(
-                        rect.left_bottom() + vec2(0.0, -3.0 * font_size),
-                        Align2::LEFT_CENTER,
-                        format!("Z0 = {:.3}", self.Z0),
-                        FontId::monospace(font_size),
-                        Color32::WHITE,
-                    );
-                    painter.text(
-                        rect.left_bottom() + vec2(0.0, -2.0 * font_size),
-                        Align2::LEFT_CENTER,
-                        format!(
-                            "r = {:+.3}, R = {:+2.3}",
-                            mouse_impedance.re,
-                            (mouse_impedance * self.Z0).re
-                        ),
-                        FontId::monospace(font_size),
-                        Color32::GREEN,
-                    );
-                    painter.text(
-                        rect.left_bottom() + vec2(0.0, -font_size),
-                        Align2::LEFT_CENTER,
-                        format!(
-                            "x = {:+.3}, X = {:+2.3}",
-                            mouse_impedance.im,
-                            (mouse_impedance * self.Z0).im
-                        ),
-                        FontId::monospace(font_size),
-                        Color32::RED,
-                    );
+        let legend_click = match &outside_legend {
+            Some(legend) => legend.interact_pointer_pos().filter(|_| legend.clicked()),
+            None => response.interact_pointer_pos().filter(|_| clicked),
+        };
+        let legend_group = legend_click
+            .filter(|_| self.legend)
+            .and_then(|pos| self.legend_group_at(ui, rect, &state, pos));
+        let gear_clicked = response
+            .interact_pointer_pos()
+            .filter(|_| clicked && self.settings_popup && !self.thumbnail)
+            .filter(|&pos| gear_rect(rect).contains(pos))
+            .is_some();
+        if gear_clicked {
+            state.settings_open = !state.settings_open;
+        } else if let Some((group, swatch)) = legend_group {
+            // clicking a group in the legend expands or collapses it, clicking its swatch
+            // hides or shows it
+            let toggled = if swatch {
+                &mut state.hidden_groups
+            } else {
+                &mut state.expanded_groups
+            };
+            if let Some(i) = toggled.iter().position(|g| *g == group) {
+                toggled.remove(i);
+            } else {
+                toggled.push(group);
+            }
+        } else if clicked {
+            // clicking a trace selects it, clicking elsewhere clears the selection
+            state.selected_trace = state.hovered_trace;
 
-                    // draw VSWR circle
-                    if self.mouse_vswr {
-                        let rel_center = egui::vec2(0.0, 0.0);
-                        let rel_radius = local_pos.length();
-                        let center = self.local_to_abs(&painter.clip_rect(), &rel_center);
-                        let radius = self.scale(&painter.clip_rect(), rel_radius);
-                        painter.circle(
-                            center.to_pos2(),
-                            radius,
-                            Color32::TRANSPARENT,
-                            Stroke::new(1.0, Color32::GOLD),
-                        );
+            if let Some(pos) = response.interact_pointer_pos() {
+                let gamma = transform.screen_to_gamma(pos);
+                let z = self.gamma_to_z(&gamma);
+                if gamma.norm() <= 1.0 {
+                    if let Some(on_click) = &mut self.on_click {
+                        on_click(z);
                     }
                 }
+
+                // clicking empty chart space freezes the readout, the next click frees it
+                let empty = hovered.is_none()
+                    && self.marker_near(&transform, &state, pos).is_none()
+                    && self.close_button(&transform, &state, pos).is_none();
+                if self.freeze_on_click && empty {
+                    state.frozen_cursor = match state.frozen_cursor {
+                        Some(_) => None,
+                        None => Some(gamma).filter(|gamma| gamma.norm() <= 1.0),
+                    };
+                }
             }
+        }
 
-            // draw debug features
-            if self.debug {
-                let center = self.local_to_abs(&rect, &vec2(0.0, 0.0)).to_pos2();
-                painter.circle(
-                    center,
-                    1.0,
-                    Color32::TRANSPARENT,
-                    Stroke::new(5.0, DEBUG_PINK),
-                );
+        if response.clicked() {
+            response.request_focus();
+        }
+        // holding shift over the chart shows more digits
+        if response.hovered() && ui.input().modifiers.shift {
+            self.format.significant_digits += PRECISION_DIGITS;
+        }
+        let pointer_gamma = response
+            .hover_pos()
+            .map(|pos| transform.screen_to_gamma(pos))
+            .filter(|gamma| gamma.norm() <= 1.0);
+        // hovering only takes the keys while no other widget, e.g. a text field, has focus
+        let focused = response.has_focus() || (response.hovered() && ui.memory().focus().is_none());
+        let commands = if focused && !self.thumbnail {
+            self.shortcuts.pressed(&mut ui.input_mut())
+        } else {
+            Vec::new()
+        };
+        for &command in &commands {
+            self.run_command(command, &mut state, hovered.as_ref(), pointer_gamma);
+        }
+        let planes = [Plane::Impedance, Plane::Admittance, Plane::Both];
+        let configured = planes.iter().position(|&p| p == self.plane).unwrap_or(0);
+        self.plane = planes[(configured + state.plane_toggles as usize) % planes.len()];
+        self.mouse_vswr ^= state.vswr_toggled;
 
-                if let Some(pos) = response.hover_pos() {
-                    painter.line_segment([center, pos], Stroke::new(1.0, Color32::DARK_RED));
-                }
+        self.handle_markers(ui, &response, clicked, &transform, &mut state);
 
-                // bounding box
-                painter.rect(
-                    rect,
-                    egui::Rounding::none(),
-                    Color32::TRANSPARENT,
-                    Stroke::new(1.0, DEBUG_PINK),
-                );
+        if response.secondary_clicked() {
+            state.context_gamma = response
+                .interact_pointer_pos()
+                .map(|pos| transform.screen_to_gamma(pos));
+        }
+
+        // 4. Paint!
+        // Make sure we need to paint:
+        let mut stats = PaintStats::default();
+        if ui.is_rect_visible(rect) {
+            // We will follow the current style by asking
+            // "how should something that is being interacted with be painted?".
+            // This will, for instance, give us different colors when the widget is hovered or clicked.
+            let visuals = ui.style().interact(&response);
+            let fg_color = visuals.fg_stroke.color;
+            let text_color = visuals.text_color();
+            // grow with the widget expansion of the interaction state, like egui buttons
+            let rect = rect.expand(visuals.expansion);
+            stats = if self.thumbnail {
+                let painter = ui.painter().with_clip_rect(rect);
+                self.paint_thumbnail(&painter, rect, fg_color, &state)
+            } else {
+                // readouts follow the slowed down pointer of a fine drag
+                let pointer = state
+                    .drag_pos
+                    .filter(|_| response.dragged())
+                    .or(response.hover_pos());
+                self.paint_chart(ui.painter(), rect, fg_color, text_color, &state, pointer)
+            };
+        }
+        if self.settings_popup && !self.thumbnail {
+            self.settings_window(ui, rect, &mut state);
+        }
+
+        let tooltip_trace = response
+            .hover_pos()
+            .filter(|_| self.trace_tooltips && !self.thumbnail && !response.dragged())
+            .and_then(|pos| transform.nearest_trace(&self.traces, pos))
+            .filter(|(_, distance)| *distance <= self.pick_radius);
+        if let Some((i, _)) = tooltip_trace {
+            self.trace_tooltip(ui.ctx(), i);
+        }
+
+        let mut dropped_files = Vec::new();
+        if self.file_drop && self.pointer_over_drop(ui, rect) {
+            // browsers don't tell the names of hovered files
+            let hovered: Vec<_> = (ui.input().raw.hovered_files.iter())
+                .map(|file| file_name(file.path.as_deref(), ""))
+                .filter(|name| loadable(name))
+                .collect();
+            if !hovered.is_empty() {
+                self.paint_drop_preview(&ui.painter().with_clip_rect(rect), rect, &hovered);
             }
+            dropped_files = (ui.input().raw.dropped_files.iter())
+                .filter(|file| loadable(&file_name(file.path.as_deref(), &file.name)))
+                .cloned()
+                .collect();
         }
 
+        let legend = self.legend_entries(&state);
+        let deviation = self.deviation();
+        let cursor = state
+            .frozen_cursor
+            .or_else(|| {
+                response
+                    .hover_pos()
+                    .map(|pos| transform.screen_to_gamma(pos))
+            })
+            .filter(|gamma| gamma.norm() <= 1.0)
+            .map(|gamma| self.cursor(gamma));
+        let pinned_cursors = state
+            .pinned_cursors
+            .map(|gamma| gamma.map(|g| self.cursor(g)));
+        #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+        let mut copy_error = None;
+        let response = if self.context_menu && !self.thumbnail {
+            #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+            let chart_size = response.rect.size().min_elem();
+            response.context_menu(|ui| {
+                self.copy_menu(ui, &state);
+                #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+                if let Err(e) = self.image_menu(ui, chart_size) {
+                    copy_error = Some(e);
+                }
+                ui.separator();
+                self.marker_menu(ui, &mut state);
+                self.readout_menu(ui, &mut state);
+            })
+        } else {
+            response
+        };
+        let deleted_markers = std::mem::take(&mut state.deleted_markers);
+        let deleted_free_markers = std::mem::take(&mut state.deleted_free_markers);
+        let selected_trace = state.selected_trace;
+        let selected_markers = state.selected_markers.clone();
+        state.store(ui.ctx(), self.id_source);
+
         // All done! Return the interaction response so the user can check what happened
         // (hovered, clicked, ...) and maybe show a tooltip:
-        response
+        SmithChartResponse {
+            response,
+            transform,
+            hovered,
+            selected_trace,
+            selected_markers,
+            deviation,
+            cursor,
+            pinned_cursors,
+            commands,
+            deleted_markers,
+            deleted_free_markers,
+            dropped_files,
+            #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+            copy_error,
+            legend,
+            stats,
+            traces: self.traces,
+        }
     }
 
-    /// Impedance, Admittance, or Both
-    pub fn plane(mut self, plane: Plane) -> Self {
-        self.plane = plane;
-        self
+    /// Paint the chart with the view and hidden traces of `state` and nothing that
+    /// follows the pointer
+    fn show_read_only(
+        mut self,
+        ui: &egui::Ui,
+        rect: Rect,
+        response: egui::Response,
+        state: SmithChartState,
+    ) -> SmithChartResponse {
+        let transform = state.transform(rect);
+        let legend = self.legend_entries(&state);
+        self.hover_readout = false;
+        let mut stats = PaintStats::default();
+        if ui.is_rect_visible(rect) {
+            let visuals = ui.style().noninteractive();
+            stats = if self.thumbnail {
+                let painter = ui.painter().with_clip_rect(rect);
+                self.paint_thumbnail(&painter, rect, visuals.fg_stroke.color, &state)
+            } else {
+                let text_color = visuals.text_color();
+                self.paint_chart(
+                    ui.painter(),
+                    rect,
+                    visuals.fg_stroke.color,
+                    text_color,
+                    &state,
+                    None,
+                )
+            };
+        }
+        SmithChartResponse {
+            response,
+            transform,
+            hovered: None,
+            selected_trace: state.selected_trace,
+            selected_markers: state.selected_markers,
+            deviation: self.deviation(),
+            cursor: None,
+            pinned_cursors: [None; 2],
+            commands: Vec::new(),
+            deleted_markers: Vec::new(),
+            deleted_free_markers: Vec::new(),
+            dropped_files: Vec::new(),
+            #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+            copy_error: None,
+            legend,
+            stats,
+            traces: self.traces,
+        }
     }
 
-    pub fn size(mut self, size: f32) -> Self {
-        self.size = size;
-        self
+    /// Paint the chart into `rect` without any hover decorations.
+    ///
+    /// The output only depends on the chart configuration, the egui style and `rect`,
+    /// which makes this suitable for offscreen rendering and snapshot tests: the view,
+    /// markers and cursors stored for the chart are left out and the grid is built
+    /// afresh rather than taken from the grid cache.
+    pub fn paint_static(&self, painter: &Painter, rect: Rect) {
+        grid::uncached(painter.ctx(), || {
+            self.paint(painter, rect, &SmithChartState::default())
+        });
     }
 
-    pub fn mouse_vswr(mut self, show: bool) -> Self {
-        self.mouse_vswr = show;
-        self
+    /// Paint the chart into `rect` of any painter the way it was last shown, with its
+    /// markers and pinned cursors but without the pointer. This lets host apps put the
+    /// chart on their own layer or into the frame of an offscreen context whose output
+    /// they tessellate into a texture:
+    /// ```no_run
+    /// # use egui::{Id, LayerId, Order, Rect};
+    /// # use egui_smith_chart::SmithChart;
+    /// # fn overlay(ctx: &egui::Context, chart: &SmithChart, rect: Rect) {
+    /// let painter = ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("overlay")));
+    /// chart.paint_at(&painter, rect);
+    /// # }
+    /// ```
+    pub fn paint_at(&self, painter: &Painter, rect: Rect) {
+        let state = SmithChartState::load(painter.ctx(), self.id_source).unwrap_or_default();
+        self.paint(painter, rect, &state);
     }
 
-    pub fn debug(mut self, debug: bool) -> Self {
-        self.debug = debug;
-        self
+    /// Paint the chart into `rect` with the view, markers and cursors of `state`,
+    /// without allocating space, e.g. inside a custom container or a window decoration:
+    /// ```no_run
+    /// # use egui::{vec2, Id, Rect};
+    /// # use egui_smith_chart::{SmithChart, SmithChartState};
+    /// # fn decoration(ui: &egui::Ui, chart: &SmithChart, frame_rect: Rect) {
+    /// let state = SmithChartState::load(ui.ctx(), Id::new("chart")).unwrap_or_default();
+    /// let rect = Rect::from_center_size(frame_rect.center(), vec2(120.0, 120.0));
+    /// chart.paint(ui.painter(), rect, &state);
+    /// # }
+    /// ```
+    /// Traces are drawn as configured; hiding them is up to [`SmithChart::show`].
+    pub fn paint(&self, painter: &Painter, rect: Rect, state: &SmithChartState) -> PaintStats {
+        let visuals = &painter.ctx().style().visuals.widgets.inactive;
+        let fg_color = visuals.fg_stroke.color;
+        let text_color = visuals.text_color();
+        if self.thumbnail {
+            self.paint_thumbnail(&painter.with_clip_rect(rect), rect, fg_color, state)
+        } else {
+            self.paint_chart(painter, rect, fg_color, text_color, state, None)
+        }
     }
 
-    /// return
-    fn abs_to_local(&self, rect: &Rect, abs: &Vec2) -> Vec2 {
-        let widget_origin = rect.left_top();
-        vec2(
-            (abs.x - widget_origin.x) / rect.width() * 2.0 - 1.0,
-            -(abs.y - widget_origin.y) / rect.height() * 2.0 + 1.0,
-        )
+    /// Group of the legend entry at `pos`, if it is a group, and whether `pos` is on its
+    /// swatch
+    fn legend_group_at(
+        &self,
+        ui: &egui::Ui,
+        rect: Rect,
+        state: &SmithChartState,
+        pos: Pos2,
+    ) -> Option<(String, bool)> {
+        let (entries, rows) = self.legend_rows(ui.ctx(), rect, state);
+        entries.into_iter().zip(rows).find_map(|(entry, row)| {
+            let swatch = pos.x < row.min.x + legend::SWATCH + 4.0;
+            Some((entry.group?, swatch)).filter(|_| row.contains(pos))
+        })
     }
 
-    fn local_to_abs(&self, rect: &Rect, local: &Vec2) -> Vec2 {
-        let x_normalized = (local.x + 1.0) / 2.0;
-        let y_normalized = (local.y + 1.0) / 2.0;
-        let abs_origin = rect.left_top();
-        vec2(
-            abs_origin.x + x_normalized * rect.width(),
-            abs_origin.y + (1.0 - y_normalized) * rect.height(),
+    /// Entries of the legend, if it is shown
+    fn legend_entries(&self, state: &SmithChartState) -> Vec<LegendEntry> {
+        if !self.legend {
+            return Vec::new();
+        }
+        legend::entries(
+            &self.traces,
+            &self.lines,
+            self.style.palette,
+            &state.expanded_groups,
         )
     }
 
-    fn scale(&self, rect: &Rect, x: f32) -> f32 {
-        x * rect.width() / 2.0
+    /// Legend entries and the screen rects of their rows
+    fn legend_rows(
+        &self,
+        ctx: &egui::Context,
+        rect: Rect,
+        state: &SmithChartState,
+    ) -> (Vec<LegendEntry>, Vec<Rect>) {
+        let font = FontId::proportional(self.style.font_size);
+        let entries = self.legend_entries(state);
+        let widths: Vec<f32> = entries
+            .iter()
+            .map(|entry| {
+                ctx.fonts()
+                    .layout_no_wrap(entry.text.clone(), font.clone(), Color32::WHITE)
+                    .size()
+                    .x
+            })
+            .collect();
+        let row_height = self.style.font_size * 1.2;
+        let rows = legend::row_rects(rect, self.legend_placement, &entries, &widths, row_height);
+        (entries, rows)
     }
 
-    fn resistance_circle(&self, ui: &mut egui::Ui, painter: &mut Painter, r: f32, stroke: &Stroke) {
-        let rel_center = egui::vec2(r / (1.0 + r), 0.0);
-        let rel_radius = 1.0 / (1.0 + r);
-        let center = self.local_to_abs(&painter.clip_rect(), &rel_center);
-        let radius = self.scale(&painter.clip_rect(), rel_radius);
-        //let center = egui::pos2(radius, rect.center().y);
-        painter.circle(center.to_pos2(), radius, Color32::TRANSPARENT, *stroke);
+    fn run_command(
+        &self,
+        command: Command,
+        state: &mut SmithChartState,
+        hovered: Option<&TraceHit>,
+        pointer: Option<Complex<f32>>,
+    ) {
+        match command {
+            Command::ResetView => state.reset_view(),
+            Command::ZoomIn => state.zoom_around(state.center, 1.25),
+            Command::ZoomOut => state.zoom_around(state.center, 0.8),
+            Command::TogglePlane => state.plane_toggles = (state.plane_toggles + 1) % 3,
+            Command::AddMarker => {
+                if let Some(hit) = hovered {
+                    state.markers.push(Marker {
+                        trace: hit.trace,
+                        frequency: self.traces[hit.trace].sweep_at(hit.index),
+                    });
+                }
+            }
+            Command::DeleteSelectedMarkers => {
+                state.delete_markers(state.selected_markers.clone());
+            }
+            Command::ToggleVswrCircle => state.vswr_toggled = !state.vswr_toggled,
+            Command::PinCursorA => state.pinned_cursors[0] = pointer,
+            Command::PinCursorB => state.pinned_cursors[1] = pointer,
+            Command::ToggleReadoutForm if state.selected_markers.is_empty() => {
+                state.readout_form = Some(self.current_readout_form(state).toggled());
+            }
+            Command::ToggleReadoutForm => {
+                for &i in &state.selected_markers {
+                    match state.flipped_markers.iter().position(|&m| m == i) {
+                        Some(position) => {
+                            state.flipped_markers.remove(position);
+                        }
+                        None => state.flipped_markers.push(i),
+                    }
+                }
+            }
+        }
     }
 
-    fn reactance_arc(
+    /// Place a marker at the best match of the selected trace
+    fn marker_menu(&self, ui: &mut egui::Ui, state: &mut SmithChartState) {
+        let trace = state.selected_trace;
+        if ui
+            .add_enabled(trace.is_some(), egui::Button::new("Mark best match"))
+            .clicked()
+        {
+            if let Some(trace) = trace {
+                self.add_best_match_marker(state, trace);
+            }
+            ui.close_menu();
+        }
+    }
+
+    /// Select a marker at the smallest |Γ| of trace `trace`, placing it unless there is
+    /// one already
+    fn add_best_match_marker(
         &self,
-        ui: &mut egui::Ui,
-        painter: &mut Painter,
-        x: f32, // normalized reactance
-        stroke: &Stroke,
-    ) {
-        let arc_points: Vec<Pos2> = if x.abs() >= 1.0 {
-            let yend: f32 = (2.0 * x) / (1.0 + x.powf(2.0));
-            let n = 128; // TODO: adaptive step count based on arc size
+        state: &mut SmithChartState,
+        trace: usize,
+    ) -> Option<TraceStats> {
+        let stats = self.traces.get(trace)?.stats()?;
+        let marker = Marker {
+            trace,
+            frequency: stats.best_match_sweep,
+        };
+        let index = match state.markers.iter().position(|m| *m == marker) {
+            Some(index) => index,
+            None => {
+                state.markers.push(marker);
+                state.markers.len() - 1
+            }
+        };
+        state.selected_markers = vec![index];
+        Some(stats)
+    }
 
-            fn x_gt_one_arc(x: f32, gi: f32) -> f32 {
-                1.0 - f32::sqrt((gi * (2.0 - x * gi)) / x)
+    /// Switch between rectangular and polar readouts
+    fn readout_menu(&self, ui: &mut egui::Ui, state: &mut SmithChartState) {
+        let mut polar = self.current_readout_form(state) == ComplexForm::Polar;
+        if ui
+            .checkbox(&mut polar, &self.locale.polar_readouts)
+            .changed()
+        {
+            state.readout_form = Some(if polar {
+                ComplexForm::Polar
+            } else {
+                ComplexForm::Rectangular
+            });
+            state.flipped_markers.clear();
+            ui.close_menu();
+        }
+    }
+
+    /// Form of readout values picked by the user, or else the configured one
+    fn current_readout_form(&self, state: &SmithChartState) -> ComplexForm {
+        state.readout_form.unwrap_or(self.readout_form)
+    }
+
+    /// The pointer is over `rect`. Where its position is unknown, as on some platforms
+    /// while files are dragged from outside the app, the drop goes to no chart rather
+    /// than to every chart in the window.
+    fn pointer_over_drop(&self, ui: &egui::Ui, rect: Rect) -> bool {
+        ui.input()
+            .pointer
+            .hover_pos()
+            .is_some_and(|pos| rect.contains(pos))
+    }
+
+    /// Apply what the user changed on the chart to the builder setup: the edited
+    /// settings, hidden traces and groups, and the difference or ratio locus. Shared by
+    /// [`SmithChart::show`] and the exports so they paint the same chart; only the first
+    /// call has an effect.
+    fn resolve(&mut self, state: &SmithChartState) {
+        if std::mem::replace(&mut self.resolved, true) {
+            return;
+        }
+        if let Some(settings) = state.settings.clone() {
+            self.apply_settings(settings);
+        }
+        for &i in &state.hidden_traces {
+            if let Some(trace) = self.traces.get_mut(i) {
+                trace.visible = false;
+            }
+        }
+        for trace in &mut self.traces {
+            if let Some(group) = trace.legend_group() {
+                trace.visible &= !state.hidden_groups.contains(group);
             }
+        }
+        // the difference or ratio locus is drawn like any other trace
+        let derived = self
+            .comparison_traces()
+            .and_then(|(reference, measurement)| match self.comparison_locus {
+                ComparisonLocus::Whiskers => None,
+                ComparisonLocus::Difference => Some(measurement.difference(reference)),
+                ComparisonLocus::Ratio => Some(measurement.ratio(reference)),
+            });
+        self.traces.extend(derived);
+    }
 
-            (0..=n)
-                .map(|i| {
-                    let gi = egui::remap(i as f32, 0.0..=(n as f32), 0.0..=yend);
-                    self.local_to_abs(&painter.clip_rect(), &vec2(x_gt_one_arc(x, gi), gi))
-                        .to_pos2()
-                })
+    /// The chart setup as the settings popup edits it
+    fn settings(&self) -> ChartSettings {
+        ChartSettings {
+            plane: self.plane,
+            grid_density: self.grid_density,
+            adaptive_grid: self.adaptive_grid,
+            grid_labels: self.grid_labels,
+            angle_ticks: self.angle_ticks,
+            legend: self.legend,
+            hidden_layers: self.hidden_layers.clone(),
+            style: self.style.clone(),
+        }
+    }
+
+    /// Replace the chart setup with settings edited by the user
+    fn apply_settings(&mut self, settings: ChartSettings) {
+        self.plane = settings.plane;
+        self.grid_density = settings.grid_density;
+        self.adaptive_grid = settings.adaptive_grid;
+        self.grid_labels = settings.grid_labels;
+        self.angle_ticks = settings.angle_ticks;
+        self.legend = settings.legend;
+        self.hidden_layers = settings.hidden_layers;
+        self.style = settings.style;
+    }
+
+    /// Gear icon in the corner of the chart and the settings popup it opens. Changes
+    /// take effect on the next frame.
+    fn settings_window(&self, ui: &egui::Ui, rect: Rect, state: &mut SmithChartState) {
+        let gear = gear_rect(rect);
+        let color = if state.settings_open || ui.rect_contains_pointer(gear) {
+            ui.visuals().strong_text_color()
+        } else {
+            ui.visuals().weak_text_color()
+        };
+        ui.painter().with_clip_rect(rect).text(
+            gear.center(),
+            Align2::CENTER_CENTER,
+            "⚙",
+            FontId::proportional(gear.height()),
+            color,
+        );
+        if !state.settings_open {
+            return;
+        }
+
+        let mut settings = state.settings.clone().unwrap_or_else(|| self.settings());
+        let (mut open, mut changed, mut reset) = (true, false, false);
+        egui::Window::new("Chart settings")
+            .id(self.id_source.with("settings"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_pos(gear.left_bottom())
+            .show(ui.ctx(), |ui| {
+                changed = settings::edit(ui, &mut settings, &self.locale);
+                ui.separator();
+                reset = ui
+                    .add_enabled(state.settings.is_some(), egui::Button::new("Reset"))
+                    .clicked();
+            });
+        state.settings_open = open;
+        if reset {
+            state.settings = None;
+        } else if changed {
+            state.settings = Some(settings);
+        }
+        if reset || changed {
+            ui.ctx().request_repaint();
+        }
+    }
+
+    /// Tooltip with the name, source file, port and reference impedance of trace `i`
+    fn trace_tooltip(&self, ctx: &egui::Context, i: usize) {
+        let trace = &self.traces[i];
+        let z0 = trace.native_z0().unwrap_or(self.Z0);
+        egui::show_tooltip_at_pointer(ctx, self.id_source.with("trace tooltip"), |ui| {
+            ui.strong(&trace.name);
+            if let Some(source) = &trace.source {
+                ui.label(source);
+            }
+            if let Some(port) = trace.port_label(&self.locale) {
+                ui.label(port);
+            }
+            ui.label(format!(
+                "{} = {}",
+                self.locale.characteristic_impedance,
+                self.format.complex(z0, "Ω")
+            ));
+        });
+    }
+
+    /// Highlight of the chart with a prompt naming the files that would be loaded
+    fn paint_drop_preview(&self, painter: &Painter, rect: Rect, files: &[String]) {
+        let visuals = &painter.ctx().style().visuals;
+        let names = match files {
+            [file] => file.clone(),
+            _ => format!("{} files", files.len()),
+        };
+        painter.rect(
+            rect.shrink(2.0),
+            4.0,
+            visuals.selection.bg_fill.linear_multiply(0.3),
+            Stroke::new(2.0, visuals.selection.stroke.color),
+        );
+        painter.text(
+            rect.center(),
+            Align2::CENTER_CENTER,
+            format!("{} {}", self.locale.drop_to_load, names).trim_end(),
+            FontId::proportional(16.0),
+            visuals.strong_text_color(),
+        );
+    }
+
+    /// Copy the selected markers, all markers including the free ones if none are
+    /// selected, or else the right-clicked point as code
+    fn copy_menu(&self, ui: &mut egui::Ui, state: &SmithChartState) {
+        let markers: Vec<(usize, &Marker)> = if state.selected_markers.is_empty() {
+            state
+                .markers
+                .iter()
+                .chain(&self.markers)
+                .enumerate()
                 .collect()
         } else {
-            let xstart = (x.powf(2.0) - 1.0) / (x.powf(2.0) + 1.0);
-            let n = 128; // TODO: adaptive step count based on arc size
+            state
+                .selected_markers
+                .iter()
+                .filter_map(|&i| Some((i, state.markers.get(i)?)))
+                .collect()
+        };
+        let mut points: Vec<SnippetPoint> = markers
+            .into_iter()
+            .filter_map(|(i, marker)| {
+                let trace = self.traces.get(marker.trace)?;
+                let gamma = trace.gamma_at(marker.frequency)?;
+                Some(SnippetPoint {
+                    label: format!("M{} on {}", i + 1, trace.name),
+                    frequency: trace.is_swept().then_some(marker.frequency),
+                    impedance: math::scale(self.gamma_to_z(&gamma), self.Z0),
+                })
+            })
+            .collect();
+        if state.selected_markers.is_empty() {
+            points.extend(
+                state
+                    .free_markers
+                    .iter()
+                    .enumerate()
+                    .map(|(i, gamma)| SnippetPoint {
+                        label: format!("P{}", i + 1),
+                        frequency: None,
+                        impedance: math::scale(self.gamma_to_z(gamma), self.Z0),
+                    }),
+            );
+        }
+        if points.is_empty() {
+            points.extend(state.context_gamma.map(|gamma| SnippetPoint {
+                label: "clicked point".to_string(),
+                frequency: None,
+                impedance: math::scale(self.gamma_to_z(&gamma), self.Z0),
+            }));
+        }
 
-            fn x_lt_one_arc(x: f32, gr: f32) -> f32 {
-                if x > 0.0 {
-                    1.0 / x - f32::sqrt(x.powf(-2.0) - (gr - 1.0).pow(2.0))
-                } else {
-                    1.0 / x + f32::sqrt(x.powf(-2.0) - (gr - 1.0).pow(2.0))
+        ui.add_enabled_ui(!points.is_empty(), |ui| {
+            for language in [CodeLanguage::Python, CodeLanguage::Matlab] {
+                if ui.button(format!("Copy as {}", language)).clicked() {
+                    ui.output().copied_text = snippet::snippet(language, &points, self.Z0);
+                    ui.close_menu();
                 }
             }
+        });
+    }
 
-            (0..=n)
-                .map(|i| {
-                    let gr = egui::remap(i as f32, 0.0..=(n as f32), xstart..=1.0);
-                    self.local_to_abs(&painter.clip_rect(), &vec2(gr, x_lt_one_arc(x, gr)))
-                        .to_pos2()
-                })
-                .collect()
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    fn image_menu(&mut self, ui: &mut egui::Ui, size: f32) -> Result<(), SmithChartError> {
+        if ui.button("Copy image").clicked() {
+            ui.close_menu();
+            return self.copy_image(ui.ctx(), size);
+        }
+        Ok(())
+    }
+
+    /// Marker selection, rubber band selection, dragging and deletion.
+    ///
+    /// Dragging a selected marker moves all selected markers by the same number of samples.
+    fn handle_markers(
+        &mut self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        clicked: bool,
+        transform: &ChartTransform,
+        state: &mut SmithChartState,
+    ) {
+        let shift = ui.input().modifiers.shift;
+
+        let pointer = match response.interact_pointer_pos() {
+            Some(pos) => pos,
+            None => return,
         };
-        painter.add(PathShape::line(arc_points, *stroke));
+
+        if clicked {
+            let close = self
+                .close_button(transform, state, pointer)
+                .filter(|(_, button)| button.distance(pointer) <= CLOSE_RADIUS + 1.0);
+            if let Some((i, _)) = close {
+                if i < state.markers.len() {
+                    state.delete_markers(vec![i]);
+                } else {
+                    let gamma = state.free_markers.remove(i - state.markers.len());
+                    state.deleted_free_markers.push(gamma);
+                }
+                return;
+            }
+            match self.marker_near(transform, state, pointer) {
+                Some(i) if shift => {
+                    if let Some(position) = state.selected_markers.iter().position(|m| *m == i) {
+                        state.selected_markers.remove(position);
+                    } else {
+                        state.selected_markers.push(i);
+                    }
+                }
+                Some(i) => state.selected_markers = vec![i],
+                None if !shift => state.selected_markers.clear(),
+                None => {}
+            }
+        }
+
+        if response.drag_started() {
+            state.drag_pos = Some(pointer);
+            state.dragged_marker = self.marker_near(transform, state, pointer);
+            match state.dragged_marker {
+                Some(i) if !state.selected_markers.contains(&i) => {
+                    state.selected_markers = vec![i];
+                }
+                Some(_) => {}
+                None if self.ruler => state.ruler = Some(transform.screen_to_gamma(pointer)),
+                None => state.rubber_band = Some(pointer),
+            }
+        }
+
+        if response.dragged() {
+            // the precision modifier slows the pointer down for fine positioning
+            let precise = self.shortcuts.precision_held(ui.input().modifiers);
+            let speed = if precise { PRECISION_DRAG } else { 1.0 };
+            let pointer = state.drag_pos.unwrap_or(pointer) + response.drag_delta() * speed;
+            state.drag_pos = Some(pointer);
+            if let Some(marker) = state.dragged_marker.and_then(|i| state.markers.get(i)) {
+                let nearest = self
+                    .traces
+                    .get(marker.trace)
+                    .and_then(|trace| transform.nearest_sweep(trace, pointer));
+                if let Some(frequency) = nearest {
+                    let delta = frequency - marker.frequency;
+                    if delta != 0.0 {
+                        self.move_markers(state, delta);
+                    }
+                }
+            }
+        }
+
+        if response.drag_released() {
+            // where the drag was drawn, which lags the pointer after slowed dragging
+            let end = state.drag_pos.take().unwrap_or(pointer);
+            // dropping markers outside the chart deletes them
+            if state.dragged_marker.take().is_some() && !transform.rect().contains(end) {
+                state.delete_markers(state.selected_markers.clone());
+            }
+            state.ruler = None;
+            if let Some(start) = state.rubber_band.take() {
+                let band = Rect::from_two_pos(start, end);
+                if !shift {
+                    state.selected_markers.clear();
+                }
+                for (i, marker) in state.markers.iter().enumerate() {
+                    let inside = self
+                        .marker_gamma(marker)
+                        .is_some_and(|gamma| band.contains(transform.gamma_to_screen(&gamma)));
+                    if inside && !state.selected_markers.contains(&i) {
+                        state.selected_markers.push(i);
+                    }
+                }
+            }
+        }
     }
 
-    fn local_to_gamma(&self, local: &Vec2) -> Complex<f32> {
-        Complex {
-            re: local.x,
-            im: -local.y,
+    /// Move the selected markers by `delta` along their traces, staying within the sweep
+    fn move_markers(&mut self, state: &mut SmithChartState, delta: f64) {
+        for &i in &state.selected_markers {
+            let marker = match state.markers.get_mut(i) {
+                Some(marker) => marker,
+                None => continue,
+            };
+            let range = match self.traces.get(marker.trace).and_then(Trace::sweep_range) {
+                Some(range) => range,
+                None => continue,
+            };
+            let frequency = (marker.frequency + delta).clamp(*range.start(), *range.end());
+            if frequency != marker.frequency {
+                marker.frequency = frequency;
+                if let Some(on_marker_moved) = &mut self.on_marker_moved {
+                    on_marker_moved(i, marker);
+                }
+            }
         }
     }
 
-    fn gamma_to_local(&self, gamma: &Complex<f32>) -> Vec2 {
-        vec2(gamma.re, -gamma.im)
+    /// Marker within the pick radius of `pos`
+    fn marker_near(
+        &self,
+        transform: &ChartTransform,
+        state: &SmithChartState,
+        pos: Pos2,
+    ) -> Option<usize> {
+        state
+            .markers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, marker)| {
+                let gamma = self.marker_gamma(marker)?;
+                Some((i, transform.gamma_to_screen(&gamma).distance(pos)))
+            })
+            .filter(|(_, distance)| *distance <= self.pick_radius)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
     }
 
-    fn gamma_to_z(&self, gamma: &Complex<f32>) -> Complex<f32> {
-        (Complex::from(1.0) + gamma) / (Complex::from(1.0) - gamma)
+    /// Interactive or free marker showing its close button for the pointer at `pos`,
+    /// with the center of the button. Free markers are numbered after the interactive
+    /// ones.
+    fn close_button(
+        &self,
+        transform: &ChartTransform,
+        state: &SmithChartState,
+        pos: Pos2,
+    ) -> Option<(usize, Pos2)> {
+        let markers = state.markers.iter().map(|marker| self.marker_gamma(marker));
+        let free_markers = state.free_markers.iter().map(|&gamma| Some(gamma));
+        markers
+            .chain(free_markers)
+            .enumerate()
+            .filter_map(|(i, gamma)| Some((i, transform.gamma_to_screen(&gamma?))))
+            .filter(|&(_, marker)| {
+                // the button stays while the pointer moves from the marker to it
+                marker.distance(pos) <= self.pick_radius
+                    || (marker + CLOSE_OFFSET).distance(pos) <= CLOSE_RADIUS + 1.0
+            })
+            .min_by(|a, b| a.1.distance(pos).total_cmp(&b.1.distance(pos)))
+            .map(|(i, marker)| (i, marker + CLOSE_OFFSET))
     }
 
-    fn z_to_gamma(&self, z: &Complex<f32>) -> Complex<f32> {
-        (z - Complex::from(1.0)) / (z + Complex::from(1.0))
+    /// Interactive markers from `state` followed by the markers placed by the application
+    fn all_markers<'s>(&'s self, state: &'s SmithChartState) -> impl Iterator<Item = Marker> + 's {
+        let trace_markers = self.traces.iter().enumerate().flat_map(|(i, trace)| {
+            trace.markers.iter().map(move |&frequency| Marker {
+                trace: i,
+                frequency,
+            })
+        });
+        state
+            .markers
+            .iter()
+            .cloned()
+            .chain(self.markers.iter().cloned())
+            .chain(trace_markers)
+    }
+
+    fn marker_gamma(&self, marker: &Marker) -> Option<Complex<f32>> {
+        self.traces.get(marker.trace)?.gamma_at(marker.frequency)
+    }
+
+    /// Paint the compact chart: unit and r = 1 circles, traces, curve points and markers
+    fn paint_thumbnail(
+        &self,
+        painter: &Painter,
+        rect: Rect,
+        fg_color: Color32,
+        state: &SmithChartState,
+    ) -> PaintStats {
+        #[cfg(not(target_arch = "wasm32"))]
+        let paint_start = std::time::Instant::now();
+
+        let transform = ChartTransform::new(rect);
+        let style = &self.style;
+        let grid = Stroke::new(1.0, style.grid_color.unwrap_or(fg_color));
+        let mut shapes = Vec::new();
+
+        if let Some(background) = style.background {
+            shapes.push(Shape::rect_filled(rect, 0.0, background));
+        }
+        // the thumbnail grid is a single mesh shared like the full grid
+        let key = (
+            "thumbnail",
+            [
+                rect.width(),
+                rect.height(),
+                grid.width,
+                painter.ctx().pixels_per_point(),
+            ]
+            .map(f32::to_bits),
+            grid.color,
+        );
+        let (grid_shapes, grid_rebuilt) = grid::cached(painter.ctx(), key, rect.min, || {
+            let mut grid_shapes: Vec<_> = [0.0, 1.0]
+                .map(|r| self.resistance_circle(&transform, r, &grid))
+                .into();
+            grid_shapes.push(Shape::line_segment(
+                [
+                    transform.gamma_to_screen(&Complex::new(-1.0, 0.0)),
+                    transform.gamma_to_screen(&Complex::new(1.0, 0.0)),
+                ],
+                grid,
+            ));
+            grid_shapes
+        });
+        shapes.extend(grid_shapes);
+
+        for (i, trace) in self
+            .traces
+            .iter()
+            .enumerate()
+            .filter(|(_, trace)| trace.visible)
+        {
+            let color = trace.color.unwrap_or(style.palette.color(i));
+            let points: Vec<Pos2> = trace
+                .gamma
+                .iter()
+                .map(|gamma| transform.gamma_to_screen(gamma))
+                .collect();
+            if let [point] = points[..] {
+                shapes.push(Shape::circle_filled(point, 2.0, color));
+            } else {
+                shapes.push(Shape::line(points, Stroke::new(1.0, color)));
+            }
+        }
+        for (i, curve) in self.curves.iter().enumerate() {
+            let color = curve
+                .color
+                .unwrap_or(style.palette.color(self.traces.len() + i));
+            if curve.is_point() {
+                shapes.push(Shape::circle_filled(
+                    transform.gamma_to_screen(&curve.gamma(curve.start())),
+                    2.0,
+                    color,
+                ));
+            } else {
+                for line in curve.polylines(&transform) {
+                    shapes.push(Shape::line(line, Stroke::new(1.0, color)));
+                }
+            }
+        }
+        for marker in self.all_markers(state) {
+            if let Some(gamma) = self.marker_gamma(&marker) {
+                let color = self.style.text_color.unwrap_or(fg_color);
+                shapes.push(Shape::circle_filled(
+                    transform.gamma_to_screen(&gamma),
+                    2.0,
+                    color,
+                ));
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        let paint_time = Some(paint_start.elapsed());
+        #[cfg(target_arch = "wasm32")]
+        let paint_time = None;
+        let stats = PaintStats {
+            shapes: shapes.len(),
+            points: shapes.iter().map(shape_points).sum(),
+            grid_rebuilt,
+            paint_time,
+        };
+        painter.extend(shapes);
+        stats
+    }
+
+    /// Paint grid, cursor decorations and readouts. `fg_color` and `text_color` are
+    /// used where the style doesn't override them. Everything but a legend outside the
+    /// chart is clipped to `rect`.
+    fn paint_chart(
+        &self,
+        outer_painter: &Painter,
+        rect: Rect,
+        fg_color: Color32,
+        text_color: Color32,
+        state: &SmithChartState,
+        hover_pos: Option<Pos2>,
+    ) -> PaintStats {
+        let painter = &outer_painter.with_clip_rect(rect);
+        let transform = state.transform(rect);
+        let hover_gamma = hover_pos.map(|pos| transform.screen_to_gamma(pos));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let paint_start = std::time::Instant::now();
+
+        let style = &self.style;
+        let grid_color = style.grid_color.unwrap_or(fg_color);
+        let text_color = style.text_color.unwrap_or(text_color);
+        let error_color = painter.ctx().style().visuals.error_fg_color;
+        let normal_line = Stroke::new(style.grid_width, grid_color);
+
+        // Geometry is collected per layer first and handed to the painter in
+        // layer order, which also lets the debug overlay report what was submitted.
+        let mut shapes = LayerShapes::default();
+
+        if let Some(background) = style.background {
+            shapes[Layer::Background].push(Shape::rect_filled(rect, 0.0, background));
+        }
+
+        for region in &self.regions {
+            shapes[Layer::Regions].push(region.shape(&transform));
+        }
+        for density in &self.densities {
+            shapes[Layer::Regions].push(density.shape(&transform));
+        }
+
+        // charts of the same size, view and configuration share their grid
+        let key = grid::GridKey {
+            size: rect.size(),
+            center: transform.center(),
+            zoom: transform.zoom(),
+            pixels_per_point: painter.ctx().pixels_per_point(),
+            plane: self.plane,
+            z0: self.Z0,
+            format: &self.format,
+            style: &self.style,
+            grid_color,
+            line: normal_line,
+            density: self.grid_density,
+            adaptive: self.adaptive_grid,
+            emphasized: self.emphasized.as_deref(),
+            real_axis: self.real_axis,
+            angle_ticks: self.angle_ticks,
+            radial_labels: self.radial_labels,
+            labels: self.grid_labels,
+        };
+        let (grid_shapes, grid_rebuilt) = grid::cached(painter.ctx(), key, rect.min, || {
+            self.grid_shapes(painter, &transform, grid_color, normal_line)
+        });
+        shapes[Layer::Grid].extend(grid_shapes);
+
+        let mut labels = Vec::new();
+
+        // where all highlighted contour sets meet their thresholds
+        for (min, max) in contour::optimum_region(&self.contours) {
+            let corners = [
+                min,
+                Complex::new(max.re, min.im),
+                max,
+                Complex::new(min.re, max.im),
+            ];
+            shapes[Layer::Regions].push(Shape::convex_polygon(
+                corners
+                    .iter()
+                    .map(|gamma| transform.gamma_to_screen(gamma))
+                    .collect(),
+                style.contour_highlight,
+                Stroke::none(),
+            ));
+        }
+
+        for set in &self.contours {
+            let stroke = Stroke::new(1.0, set.color);
+            for contour in &set.contours {
+                for line in &contour.lines {
+                    let points: Vec<Pos2> = line
+                        .iter()
+                        .map(|gamma| transform.gamma_to_screen(gamma))
+                        .collect();
+                    // label the middle of each line, away from where it may be cut off
+                    labels.push((
+                        Layer::Regions,
+                        points[points.len() / 2],
+                        Align2::CENTER_CENTER,
+                        format!("{} {}", self.format.number(contour.level), set.unit),
+                    ));
+                    shapes[Layer::Regions].push(Shape::line(points, stroke));
+                }
+            }
+        }
+
+        // passing area of each spec mask, labelled at its top
+        for mask in &self.spec_masks {
+            shapes[Layer::Regions].push(mask.shape(&transform, style.spec_pass));
+            labels.push((
+                Layer::Regions,
+                transform.gamma_to_screen(&Complex::new(0.0, mask.max_gamma())),
+                Align2::CENTER_BOTTOM,
+                format!(
+                    "{}: {} – {}",
+                    match mask.limit {
+                        SpecLimit::Vswr(vswr) => {
+                            format!("{} ≤ {}", self.locale.vswr, self.format.number(vswr))
+                        }
+                        SpecLimit::ReturnLoss(rl) => {
+                            format!("{} ≥ {}", self.locale.return_loss, self.format.db(rl))
+                        }
+                    },
+                    self.format.frequency(*mask.frequencies.start()),
+                    self.format.frequency(*mask.frequencies.end())
+                ),
+            ));
+        }
+
+        let mut readout = Vec::new();
+
+        // traces on top of the grid
+        for (i, trace) in self.traces.iter().enumerate() {
+            if !trace.visible {
+                continue;
+            }
+            let mut opacity = trace.opacity;
+            if state.selected_trace.is_some() && state.selected_trace != Some(i) {
+                opacity *= style.unselected_opacity;
+            }
+            let color = trace.color.unwrap_or(style.palette.color(i));
+            let color = color.linear_multiply(opacity);
+            let mut width = trace.width;
+            if state.hovered_trace == Some(i) || state.selected_trace == Some(i) {
+                width += style.trace_highlight_width;
+            }
+            // NaN or infinite samples leave a gap in the line
+            let validity = trace.checked_validity();
+            // reported once per chart and trace rather than on every frame
+            #[cfg(feature = "log")]
+            if !validity.non_finite.is_empty()
+                && first_report(
+                    painter.ctx(),
+                    (self.id_source, &trace.name, validity.non_finite.len()),
+                )
+            {
+                diagnostic!(
+                    trace,
+                    "trace \"{}\": {} non-finite samples dropped",
+                    trace.name,
+                    validity.non_finite.len()
+                );
+            }
+            let mut runs = vec![Vec::new()];
+            for gamma in &trace.gamma {
+                if gamma.is_finite() {
+                    runs.last_mut()
+                        .unwrap()
+                        .push(transform.gamma_to_screen(gamma));
+                } else if !runs.last().unwrap().is_empty() {
+                    runs.push(Vec::new());
+                }
+            }
+            // halo under the selected trace so it stands out among many
+            if state.selected_trace == Some(i) && style.selection_halo_width > 0.0 {
+                let halo = style
+                    .selection_halo_color
+                    .unwrap_or(color.linear_multiply(0.35));
+                let halo_width = width + 2.0 * style.selection_halo_width;
+                for run in &runs {
+                    shapes[Layer::Traces]
+                        .push(Shape::line(run.clone(), Stroke::new(halo_width, halo)));
+                }
+            }
+            for run in runs {
+                shapes[Layer::Traces].push(Shape::line(run, Stroke::new(width, color)));
+            }
+
+            // recolor the masked part of the sweep by compliance
+            if !self.spec_masks.is_empty() && trace.is_swept() && trace.is_reflection() {
+                let mut runs: Vec<(Color32, Vec<Pos2>)> = Vec::new();
+                let samples = trace.frequencies.iter().zip(&trace.gamma);
+                let verdicts: Vec<_> = samples
+                    .map(|(&frequency, &gamma)| {
+                        region::compliance(&self.spec_masks, frequency, gamma)
+                            .filter(|_| gamma.is_finite())
+                    })
+                    .collect();
+                for (i, pair) in verdicts.windows(2).enumerate() {
+                    let segment_color = match pair {
+                        [Some(false), Some(_)] | [Some(_), Some(false)] => style.spec_fail,
+                        [Some(true), Some(true)] => style.spec_pass,
+                        _ => continue,
+                    }
+                    .linear_multiply(opacity);
+                    let (from, to) = (
+                        transform.gamma_to_screen(&trace.gamma[i]),
+                        transform.gamma_to_screen(&trace.gamma[i + 1]),
+                    );
+                    match runs.last_mut() {
+                        Some((color, run))
+                            if *color == segment_color && run.last() == Some(&from) =>
+                        {
+                            run.push(to)
+                        }
+                        _ => runs.push((segment_color, vec![from, to])),
+                    }
+                }
+                for (color, run) in runs {
+                    shapes[Layer::Traces].push(Shape::line(run, Stroke::new(width, color)));
+                }
+            }
+
+            // flag samples that can't be right, unless active devices are expected
+            let outside = if self.extended {
+                &[][..]
+            } else {
+                &validity.outside[..]
+            };
+            for &sample in outside {
+                shapes[Layer::Overlays].push(Shape::circle_stroke(
+                    transform.gamma_to_screen(&trace.gamma[sample]),
+                    width + 3.0,
+                    Stroke::new(1.5, error_color),
+                ));
+            }
+            if !validity.non_finite.is_empty() || !outside.is_empty() {
+                readout.push((
+                    format!(
+                        "⚠ {}: {} invalid, {} with |{}| > 1",
+                        trace.name,
+                        validity.non_finite.len(),
+                        outside.len(),
+                        self.locale.reflection_coefficient
+                    ),
+                    error_color,
+                ));
+            }
+        }
+
+        for (i, line) in self.lines.iter().enumerate() {
+            let color = line
+                .color
+                .unwrap_or(style.palette.color(self.traces.len() + i));
+            shapes[Layer::Traces].extend(line.segments(&transform, Stroke::new(line.width, color)));
+        }
+
+        for (i, curve) in self.curves.iter().enumerate() {
+            let color = curve.color.unwrap_or(
+                style
+                    .palette
+                    .color(self.traces.len() + self.lines.len() + i),
+            );
+            let start = curve.gamma(curve.start());
+            if curve.is_point() {
+                shapes[Layer::Traces].push(Shape::circle_filled(
+                    transform.gamma_to_screen(&start),
+                    curve.width + 2.0,
+                    color,
+                ));
+            } else {
+                for line in curve.polylines(&transform) {
+                    shapes[Layer::Traces].push(Shape::line(line, Stroke::new(curve.width, color)));
+                }
+            }
+            if let Some(label) = &curve.label {
+                labels.push((
+                    Layer::Traces,
+                    transform.gamma_to_screen(&start) + vec2(4.0, -4.0),
+                    Align2::LEFT_BOTTOM,
+                    label.clone(),
+                ));
+            }
+        }
+
+        // legend shared by the contour sets
+        for (i, set) in self.contours.iter().enumerate() {
+            let pos = rect.right_top() + vec2(-8.0, 8.0 + i as f32 * style.font_size * 1.2);
+            shapes[Layer::Overlays].push(Shape::line_segment(
+                [
+                    pos + vec2(-16.0, style.font_size / 2.0),
+                    pos + vec2(0.0, style.font_size / 2.0),
+                ],
+                Stroke::new(2.0, set.color),
+            ));
+            labels.push((
+                Layer::Overlays,
+                pos + vec2(-20.0, 0.0),
+                Align2::RIGHT_TOP,
+                if set.unit.is_empty() {
+                    set.name.clone()
+                } else {
+                    format!("{} ({})", set.name, set.unit)
+                },
+            ));
+        }
+
+        let mut legend_shapes = Vec::new();
+        if self.legend {
+            let (entries, rows) = self.legend_rows(painter.ctx(), rect, state);
+            let frame = rows.iter().copied().reduce(Rect::union);
+            if let Some(frame) = frame.map(|frame| frame.expand(legend::PADDING)) {
+                legend_shapes.push(Shape::rect_filled(
+                    frame,
+                    2.0,
+                    style.legend_background.unwrap_or(Color32::TRANSPARENT),
+                ));
+                legend_shapes.push(Shape::rect_stroke(frame, 2.0, style.legend_border));
+            }
+            let fonts = painter.fonts();
+            for (entry, row) in entries.iter().zip(rows) {
+                // hidden traces and groups stay listed, faded out
+                let opacity = if entry.hidden { 0.3 } else { 1.0 };
+                let y = row.min.y + style.font_size / 2.0;
+                let step = legend::SWATCH / entry.colors.len() as f32;
+                for (i, color) in entry.colors.iter().enumerate() {
+                    legend_shapes.push(Shape::line_segment(
+                        [
+                            pos2(row.min.x + i as f32 * step, y),
+                            pos2(row.min.x + (i + 1) as f32 * step, y),
+                        ],
+                        Stroke::new(2.0, color.linear_multiply(opacity)),
+                    ));
+                }
+                legend_shapes.push(Shape::text(
+                    &fonts,
+                    row.min + vec2(legend::SWATCH + 4.0, 0.0),
+                    Align2::LEFT_TOP,
+                    &entry.text,
+                    FontId::proportional(style.font_size),
+                    text_color.linear_multiply(opacity),
+                ));
+            }
+        }
+        // the chart clips everything else to its rect
+        let outside_legend = if self.legend_placement == LegendPlacement::Outside {
+            std::mem::take(&mut legend_shapes)
+        } else {
+            Vec::new()
+        };
+        shapes[Layer::Overlays].extend(legend_shapes);
+
+        // annotations with leader arrows
+        for trace in self.traces.iter().filter(|trace| trace.visible) {
+            for annotation in &trace.annotations {
+                if let Some(gamma) = trace.gamma_at(annotation.frequency) {
+                    let tip = transform.gamma_to_screen(&gamma);
+                    let label = tip + annotation.offset;
+                    shapes[Layer::Overlays].extend(arrow(label, tip, Stroke::new(1.0, text_color)));
+                    let align = Align2([
+                        if annotation.offset.x < 0.0 {
+                            Align::Max
+                        } else {
+                            Align::Min
+                        },
+                        if annotation.offset.y < 0.0 {
+                            Align::Max
+                        } else {
+                            Align::Min
+                        },
+                    ]);
+                    labels.push((Layer::Overlays, label, align, annotation.text.clone()));
+                }
+            }
+        }
+
+        // frequency ticks across the traces, lighter than markers
+        {
+            let fonts = painter.fonts();
+            for (i, trace) in self.traces.iter().enumerate() {
+                let (range, ticks) = match (trace.frequency_ticks, trace.sweep_range()) {
+                    (Some(ticks), Some(range)) if trace.visible && trace.is_swept() => (
+                        range.clone(),
+                        ticks.scaled_frequencies(range, trace.frequency_scale),
+                    ),
+                    _ => continue,
+                };
+                let color = trace.color.unwrap_or(style.palette.color(i));
+                let (start, end) = range.into_inner();
+                let delta = (end - start) * 1e-3;
+                for frequency in ticks {
+                    let neighbours = (
+                        trace.gamma_at((frequency - delta).max(start)),
+                        trace.gamma_at((frequency + delta).min(end)),
+                        trace.gamma_at(frequency),
+                    );
+                    let (before, after, gamma) = match neighbours {
+                        (Some(before), Some(after), Some(gamma)) => (before, after, gamma),
+                        _ => continue,
+                    };
+                    let pos = transform.gamma_to_screen(&gamma);
+                    let along =
+                        transform.gamma_to_screen(&after) - transform.gamma_to_screen(&before);
+                    let across = along.normalized().rot90();
+                    if !across.is_finite() {
+                        continue;
+                    }
+                    shapes[Layer::Traces].push(Shape::line_segment(
+                        [pos - across * 4.0, pos + across * 4.0],
+                        Stroke::new(1.0, color),
+                    ));
+                    let align = Align2([
+                        if across.x < 0.0 {
+                            Align::Max
+                        } else {
+                            Align::Min
+                        },
+                        if across.y < 0.0 {
+                            Align::Max
+                        } else {
+                            Align::Min
+                        },
+                    ]);
+                    shapes[Layer::Traces].push(Shape::text(
+                        &fonts,
+                        pos + across * 6.0,
+                        align,
+                        self.format.frequency(frequency),
+                        FontId::proportional(style.font_size * 0.7),
+                        color,
+                    ));
+                }
+            }
+        }
+
+        // resonances as diamonds, as they aren't user markers
+        for (i, trace) in self.traces.iter().enumerate() {
+            if !self.resonance_markers || !trace.visible || !trace.is_swept() {
+                continue;
+            }
+            let color = trace.color.unwrap_or(style.palette.color(i));
+            for resonance in trace.resonances() {
+                let gamma = match trace.gamma_at(resonance.frequency) {
+                    Some(gamma) => gamma,
+                    None => continue,
+                };
+                let pos = transform.gamma_to_screen(&gamma);
+                shapes[Layer::Markers].push(Shape::closed_line(
+                    [
+                        vec2(0.0, -5.0),
+                        vec2(5.0, 0.0),
+                        vec2(0.0, 5.0),
+                        vec2(-5.0, 0.0),
+                    ]
+                    .map(|corner| pos + corner)
+                    .into(),
+                    Stroke::new(1.5, color),
+                ));
+                let resistance = math::scale(Complex::new(resonance.resistance, 0.0), self.Z0);
+                labels.push((
+                    Layer::Markers,
+                    pos + vec2(6.0, 6.0),
+                    Align2::LEFT_TOP,
+                    format!(
+                        "{} {}: {} = {}",
+                        resonance.kind,
+                        self.format.frequency(resonance.frequency),
+                        self.locale.resistance,
+                        self.format.eng(resistance.re, "Ω")
+                    ),
+                ));
+            }
+        }
+
+        // connect corresponding points of measurement and reference
+        if let Some((reference, measurement)) = self.comparison_traces() {
+            let stroke = Stroke::new(1.0, text_color.linear_multiply(0.5));
+            let whiskers = self.comparison_locus == ComparisonLocus::Whiskers;
+            for (i, gamma) in measurement.gamma.iter().enumerate().filter(|_| whiskers) {
+                if let Some(expected) = reference.gamma_at(measurement.sweep_at(i)) {
+                    shapes[Layer::Overlays].push(Shape::line_segment(
+                        [
+                            transform.gamma_to_screen(&expected),
+                            transform.gamma_to_screen(gamma),
+                        ],
+                        stroke,
+                    ));
+                }
+            }
+            if let Some(deviation) = measurement.deviation(reference) {
+                readout.push((
+                    format!(
+                        "{} max = {}, mean = {}",
+                        self.locale.deviation,
+                        self.format.number(deviation.max),
+                        self.format.number(deviation.mean)
+                    ),
+                    text_color,
+                ));
+            }
+        }
+
+        // markers on top of traces
+        for (i, marker) in self.all_markers(state).enumerate() {
+            let trace = match self.traces.get(marker.trace) {
+                Some(trace) if trace.visible => trace,
+                _ => continue,
+            };
+            if let Some(gamma) = trace.gamma_at(marker.frequency) {
+                let pos = transform.gamma_to_screen(&gamma);
+                let color = trace.color.unwrap_or(style.palette.color(marker.trace));
+                shapes[Layer::Markers].push(Shape::circle_filled(pos, 4.0, color));
+                shapes[Layer::Markers].push(Shape::circle_stroke(
+                    pos,
+                    4.0,
+                    Stroke::new(1.0, text_color),
+                ));
+                if state.selected_markers.contains(&i) {
+                    shapes[Layer::Markers].push(Shape::circle_stroke(
+                        pos,
+                        7.0,
+                        Stroke::new(1.5, text_color),
+                    ));
+                    if self.admittance_ghost {
+                        shapes[Layer::Markers].extend(self.ghost(
+                            &transform,
+                            gamma,
+                            Stroke::new(1.0, color),
+                        ));
+                    }
+                }
+                labels.push((
+                    Layer::Markers,
+                    pos + vec2(6.0, -6.0),
+                    Align2::LEFT_BOTTOM,
+                    format!("M{}", i + 1),
+                ));
+                // values relative to the chart Z0, or to the trace's own reference
+                let z = math::scale(self.gamma_to_z(&gamma), self.Z0);
+                let native = trace
+                    .native_z0()
+                    .map(|z0| (z0, self.z_to_gamma(&math::scale(z, z0.inv()))));
+                let (z0, gamma) = native
+                    .filter(|_| self.native_reference)
+                    .unwrap_or((self.Z0, gamma));
+                if self.marker_readout {
+                    let sweep = if trace.is_swept() {
+                        self.format.frequency(marker.frequency)
+                    } else {
+                        format!("#{}", marker.frequency)
+                    };
+                    let mut form = self.current_readout_form(state);
+                    if state.flipped_markers.contains(&i) {
+                        form = form.toggled();
+                    }
+                    let mut line = format!(
+                        "M{} {}: {}",
+                        i + 1,
+                        sweep,
+                        self.complex_pair(math::scale(z, z0.inv()), z, form)
+                    );
+                    if form == ComplexForm::Polar {
+                        line += &format!(
+                            ", {} = {}",
+                            self.locale.reflection_coefficient,
+                            self.format.polar(gamma)
+                        );
+                    }
+                    if z0 != self.Z0 {
+                        line += &format!(
+                            ", {} = {}",
+                            self.locale.characteristic_impedance,
+                            self.format.complex(z0, "Ω")
+                        );
+                    }
+                    readout.push((line, color));
+                    // S-parameters as specs state them, at the reference of their file
+                    if let Some((row, column)) = trace.port {
+                        let s = native.map_or(gamma, |(_, s)| s);
+                        let name = format!("S{}{}", row + 1, column + 1);
+                        readout.push((
+                            format!(
+                                "M{}: |{}| = {}, ∠{} = {}",
+                                i + 1,
+                                name,
+                                self.format.db(20.0 * s.norm().log10()),
+                                name,
+                                self.format.angle(s.arg())
+                            ),
+                            color,
+                        ));
+                    }
+                }
+                if self.transmission_coefficient {
+                    readout.push((
+                        format!(
+                            "M{}: {} = {}",
+                            i + 1,
+                            self.locale.transmission_coefficient,
+                            self.format.polar(1.0 + gamma)
+                        ),
+                        color,
+                    ));
+                }
+            }
+        }
+
+        // free markers as crosses, as they don't belong to a trace
+        for (i, gamma) in state.free_markers.iter().enumerate() {
+            let pos = transform.gamma_to_screen(gamma);
+            let stroke = Stroke::new(1.5, text_color);
+            for d in [vec2(4.0, 4.0), vec2(4.0, -4.0)] {
+                shapes[Layer::Markers].push(Shape::line_segment([pos - d, pos + d], stroke));
+            }
+            labels.push((
+                Layer::Markers,
+                pos + vec2(6.0, -6.0),
+                Align2::LEFT_BOTTOM,
+                format!("P{}", i + 1),
+            ));
+            if self.marker_readout {
+                let z = self.gamma_to_z(gamma);
+                readout.push((
+                    format!(
+                        "P{}: {}",
+                        i + 1,
+                        self.complex_pair(
+                            z,
+                            math::scale(z, self.Z0),
+                            self.current_readout_form(state)
+                        )
+                    ),
+                    text_color,
+                ));
+            }
+        }
+
+        // close button of the hovered marker
+        let close = hover_pos
+            .filter(|_| state.dragged_marker.is_none() && state.rubber_band.is_none())
+            .and_then(|pos| self.close_button(&transform, state, pos));
+        if let Some((_, center)) = close {
+            let stroke = Stroke::new(1.0, text_color);
+            let d = Vec2::splat(CLOSE_RADIUS * 0.5);
+            shapes[Layer::Overlays].push(Shape::circle_stroke(center, CLOSE_RADIUS, stroke));
+            shapes[Layer::Overlays].push(Shape::line_segment([center - d, center + d], stroke));
+            let d = vec2(d.x, -d.y);
+            shapes[Layer::Overlays].push(Shape::line_segment([center - d, center + d], stroke));
+        }
+
+        // electrical length between two selected markers
+        if let [a, b] = state.selected_markers[..] {
+            let markers: Vec<_> = self.all_markers(state).collect();
+            let gamma = |i: usize| markers.get(i).and_then(|m| self.marker_gamma(m));
+            if let (Some(from), Some(to)) = (gamma(a), gamma(b)) {
+                let rotation = measure::rotation(from, to);
+                readout.push((
+                    format!(
+                        "M{} → M{}: {}°, {} {}",
+                        a + 1,
+                        b + 1,
+                        self.format.number(rotation.degrees),
+                        self.format.number(rotation.wavelengths),
+                        self.locale.toward_generator
+                    ),
+                    text_color,
+                ));
+            }
+        }
+
+        // ruler from the drag start to the pointer
+        if let (Some(start), Some(end)) = (state.ruler, hover_gamma) {
+            let stroke = Stroke::new(1.0, text_color);
+            let (from, to) = (
+                transform.gamma_to_screen(&start),
+                transform.gamma_to_screen(&end),
+            );
+            shapes[Layer::Overlays].push(Shape::line_segment([from, to], stroke));
+
+            // clockwise arc at the start's |Γ| to the angle of the pointer
+            let rotation = measure::rotation(start, end);
+            let steps = (rotation.degrees / 2.0).ceil().max(1.0) as usize;
+            let arc = (0..=steps)
+                .map(|i| {
+                    let angle =
+                        start.arg() - (rotation.degrees * i as f32 / steps as f32).to_radians();
+                    transform.gamma_to_screen(&Complex::from_polar(start.norm(), angle))
+                })
+                .collect();
+            shapes[Layer::Overlays].push(Shape::line(
+                arc,
+                Stroke::new(1.0, text_color.linear_multiply(0.5)),
+            ));
+
+            labels.push((
+                Layer::Overlays,
+                to + vec2(8.0, 8.0),
+                Align2::LEFT_TOP,
+                format!(
+                    "{} = {}\n{}°, {} λ",
+                    self.locale.deviation,
+                    self.format.number((end - start).norm()),
+                    self.format.number(rotation.degrees),
+                    self.format.number(rotation.wavelengths)
+                ),
+            ));
+        }
+
+        // rubber band selection in progress
+        if let (Some(start), Some(end)) = (state.rubber_band, state.drag_pos.or(hover_pos)) {
+            let band = Rect::from_two_pos(start, end);
+            shapes[Layer::Overlays].push(Shape::rect_filled(
+                band,
+                0.0,
+                text_color.linear_multiply(0.1),
+            ));
+            shapes[Layer::Overlays].push(Shape::rect_stroke(
+                band,
+                0.0,
+                Stroke::new(1.0, text_color),
+            ));
+        }
+
+        // crosshair at the frozen cursor, whose readout replaces the one of the pointer
+        if let Some(gamma) = state.frozen_cursor.filter(|_| self.hover_readout) {
+            let pos = transform.gamma_to_screen(&gamma);
+            let stroke = Stroke::new(1.0, text_color.linear_multiply(0.5));
+            shapes[Layer::Readout].extend([
+                Shape::line_segment(
+                    [pos2(rect.left(), pos.y), pos2(rect.right(), pos.y)],
+                    stroke,
+                ),
+                Shape::line_segment(
+                    [pos2(pos.x, rect.top()), pos2(pos.x, rect.bottom())],
+                    stroke,
+                ),
+                Shape::circle_stroke(pos, 3.0, Stroke::new(1.5, text_color)),
+            ]);
+        }
+
+        // pinned A and B cursors with their values and the change from A to B
+        let pinned = match self.hover_readout {
+            true => state
+                .pinned_cursors
+                .map(|gamma| gamma.map(|g| self.cursor(g))),
+            false => [None; 2],
+        };
+        for (name, cursor) in ["A", "B"].into_iter().zip(pinned) {
+            if let Some(cursor) = cursor {
+                let pos = transform.gamma_to_screen(&cursor.gamma);
+                shapes[Layer::Overlays].push(Shape::circle_stroke(
+                    pos,
+                    4.0,
+                    Stroke::new(1.5, text_color),
+                ));
+                labels.push((
+                    Layer::Overlays,
+                    pos + vec2(6.0, 6.0),
+                    Align2::LEFT_TOP,
+                    name.to_string(),
+                ));
+                readout.push((
+                    format!(
+                        "{}: {}, {} = {}",
+                        name,
+                        self.complex_pair(
+                            cursor.normalized,
+                            cursor.impedance,
+                            self.current_readout_form(state)
+                        ),
+                        self.locale.reflection_coefficient,
+                        self.format.polar(cursor.gamma)
+                    ),
+                    text_color,
+                ));
+            }
+        }
+        if let [Some(a), Some(b)] = pinned {
+            let (from, to) = (
+                transform.gamma_to_screen(&a.gamma),
+                transform.gamma_to_screen(&b.gamma),
+            );
+            if from.distance(to) > 8.0 {
+                let direction = (to - from).normalized();
+                shapes[Layer::Overlays].extend(arrow(
+                    from + direction * 4.0,
+                    to - direction * 4.0,
+                    Stroke::new(1.0, text_color.linear_multiply(0.5)),
+                ));
+            }
+            readout.push((
+                format!(
+                    "A → B: Δ{} = {}, Δ{} = {}, Δ|{}| = {}",
+                    self.locale.absolute_impedance,
+                    self.format.complex(b.impedance - a.impedance, "Ω"),
+                    self.locale.reflection_coefficient,
+                    self.format.polar(b.gamma - a.gamma),
+                    self.locale.reflection_coefficient,
+                    // equal magnitudes, both 0 included, differ by 0 dB rather than NaN
+                    self.format.db(if a.gamma.norm() == b.gamma.norm() {
+                        0.0
+                    } else {
+                        20.0 * (b.gamma.norm() / a.gamma.norm()).log10()
+                    })
+                ),
+                text_color,
+            ));
+        }
+
+        let cursor_gamma = state.frozen_cursor.or(hover_gamma);
+        if let Some(hover_gamma) = cursor_gamma.filter(|_| self.hover_readout) {
+            let mouse_impedance = self.gamma_to_z(&hover_gamma);
+            diagnostic!(
+                trace,
+                "mouse gamma = ({}, {}), z = {}",
+                hover_gamma.re,
+                hover_gamma.im,
+                mouse_impedance
+            );
+
+            // check if mouse is inside the Smith chart
+            if hover_gamma.norm() < 1.0 {
+                // draw resistance and reactance circles under mouse, conductance and
+                // susceptance circles on an admittance chart
+                let cursor_circles = [
+                    self.resistance_circle(
+                        &transform,
+                        mouse_impedance.re,
+                        &Stroke::new(style.cursor_width, style.cursor_resistance),
+                    ),
+                    self.reactance_arc(
+                        &transform,
+                        mouse_impedance.im,
+                        &Stroke::new(style.cursor_width, style.cursor_reactance),
+                    ),
+                ];
+                let center = transform.gamma_to_screen(&Complex::new(0.0, 0.0));
+                let mouse_admittance = math::invert(mouse_impedance);
+                let admittance_circles = [
+                    self.resistance_circle(
+                        &transform,
+                        mouse_admittance.re,
+                        &Stroke::new(style.cursor_width, style.cursor_resistance),
+                    ),
+                    self.reactance_arc(
+                        &transform,
+                        mouse_admittance.im,
+                        &Stroke::new(style.cursor_width, style.cursor_reactance),
+                    ),
+                ]
+                .map(|shape| mirror(shape, center, None));
+                match self.plane {
+                    Plane::Admittance => shapes[Layer::Readout].extend(admittance_circles),
+                    _ => shapes[Layer::Readout].extend(cursor_circles),
+                }
+
+                let l = &self.locale;
+                let z = math::scale(mouse_impedance, self.Z0);
+                let y = math::scale(mouse_admittance, 1.0 / self.Z0);
+                readout.push((
+                    format!(
+                        "{} = {}",
+                        l.characteristic_impedance,
+                        self.format.complex(self.Z0, "Ω")
+                    ),
+                    text_color,
+                ));
+                // on the dual chart both readouts take the color of their grid
+                let (resistance_color, reactance_color) = match self.plane {
+                    Plane::Both => (grid_color, grid_color),
+                    _ => (style.cursor_resistance, style.cursor_reactance),
+                };
+                let (conductance_color, susceptance_color) = match self.plane {
+                    Plane::Both => (style.admittance_grid_color, style.admittance_grid_color),
+                    _ => (style.cursor_resistance, style.cursor_reactance),
+                };
+                if self.plane != Plane::Admittance {
+                    readout.push((
+                        self.value_pair(
+                            &l.normalized_resistance,
+                            mouse_impedance.re,
+                            &l.resistance,
+                            z.re,
+                            "Ω",
+                        ),
+                        resistance_color,
+                    ));
+                    readout.push((
+                        self.value_pair(
+                            &l.normalized_reactance,
+                            mouse_impedance.im,
+                            &l.reactance,
+                            z.im,
+                            "Ω",
+                        ),
+                        reactance_color,
+                    ));
+                }
+                if self.plane != Plane::Impedance {
+                    readout.push((
+                        self.value_pair(
+                            &l.normalized_conductance,
+                            mouse_admittance.re,
+                            &l.conductance,
+                            y.re,
+                            "S",
+                        ),
+                        conductance_color,
+                    ));
+                    readout.push((
+                        self.value_pair(
+                            &l.normalized_susceptance,
+                            mouse_admittance.im,
+                            &l.susceptance,
+                            y.im,
+                            "S",
+                        ),
+                        susceptance_color,
+                    ));
+                }
+
+                readout.push((
+                    format!(
+                        "{} = {}",
+                        l.reflection_coefficient,
+                        self.format.polar(hover_gamma)
+                    ),
+                    text_color,
+                ));
+
+                let magnitude = hover_gamma.norm();
+                readout.push((
+                    format!(
+                        "{} = {}, {} = {}",
+                        l.vswr,
+                        self.format.number((1.0 + magnitude) / (1.0 - magnitude)),
+                        l.return_loss,
+                        self.format.db(-20.0 * magnitude.log10())
+                    ),
+                    style.cursor_vswr,
+                ));
+
+                if self.transmission_coefficient {
+                    readout.push((
+                        format!(
+                            "{} = {}",
+                            l.transmission_coefficient,
+                            self.format.polar(1.0 + hover_gamma)
+                        ),
+                        text_color,
+                    ));
+                }
+
+                if self.wavelength_readout {
+                    readout.push((
+                        format!(
+                            "{} {}",
+                            self.format
+                                .number(measure::wavelengths_toward_generator(hover_gamma)),
+                            l.toward_generator
+                        ),
+                        text_color,
+                    ));
+                }
+
+                if self.stub_helper && (mouse_admittance.re - 1.0).abs() < STUB_SNAP {
+                    let stub = measure::shunt_stub(mouse_admittance);
+                    shapes[Layer::Readout].push(mirror(
+                        self.resistance_circle(
+                            &transform,
+                            1.0,
+                            &Stroke::new(style.cursor_width, style.cursor_vswr),
+                        ),
+                        center,
+                        None,
+                    ));
+                    readout.push((
+                        format!(
+                            "{} {} = {}, {} {} λ, {} {} λ",
+                            l.stub,
+                            l.normalized_susceptance,
+                            self.format.signed(stub.susceptance),
+                            l.open_stub,
+                            self.format.number(stub.open),
+                            l.short_stub,
+                            self.format.number(stub.short)
+                        ),
+                        style.cursor_vswr,
+                    ));
+                }
+
+                if self.admittance_ghost {
+                    shapes[Layer::Readout].extend(self.ghost(
+                        &transform,
+                        hover_gamma,
+                        Stroke::new(style.cursor_width, style.cursor_vswr),
+                    ));
+                }
+
+                // draw VSWR circle
+                if self.mouse_vswr {
+                    let center = transform.gamma_to_screen(&Complex::new(0.0, 0.0));
+                    let radius = transform.scale(hover_gamma.norm());
+                    shapes[Layer::Readout].push(Shape::circle_stroke(
+                        center,
+                        radius,
+                        Stroke::new(style.cursor_width, style.cursor_vswr),
+                    ));
+                }
+            }
+        }
+
+        {
+            let fonts = painter.fonts();
+            for (layer, pos, align, label) in labels {
+                shapes[layer].push(Shape::text(
+                    &fonts,
+                    pos,
+                    align,
+                    label,
+                    FontId::proportional(style.font_size),
+                    text_color,
+                ));
+            }
+
+            // stack readout lines upwards from the bottom left corner
+            for (i, (text, color)) in readout.into_iter().rev().enumerate() {
+                shapes[Layer::Readout].push(Shape::text(
+                    &fonts,
+                    rect.left_bottom() + vec2(0.0, -(i as f32 + 1.0) * style.font_size),
+                    Align2::LEFT_CENTER,
+                    text,
+                    FontId::monospace(style.font_size),
+                    color,
+                ));
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let paint_time = Some(paint_start.elapsed());
+        #[cfg(target_arch = "wasm32")]
+        let paint_time = None;
+        let stats = PaintStats {
+            shapes: shapes.iter().count(),
+            points: shapes.iter().map(shape_points).sum(),
+            grid_rebuilt,
+            paint_time,
+        };
+        for &layer in &self.layers {
+            if self.hidden_layers.contains(&layer) {
+                continue;
+            }
+            painter.extend(shapes.take(layer));
+            if layer == Layer::Overlays && !outside_legend.is_empty() {
+                outer_painter.extend(outside_legend.clone());
+            }
+            if layer == Layer::Overlays {
+                if let Some(on_paint) = &self.on_paint {
+                    on_paint(painter, &transform);
+                }
+            }
+        }
+
+        // draw debug features
+        if self.debug {
+            let center = transform.gamma_to_screen(&Complex::new(0.0, 0.0));
+            painter.circle(
+                center,
+                1.0,
+                Color32::TRANSPARENT,
+                Stroke::new(5.0, DEBUG_PINK),
+            );
+
+            if let Some(pos) = hover_pos {
+                painter.line_segment([center, pos], Stroke::new(1.0, Color32::DARK_RED));
+            }
+
+            // bounding box
+            painter.rect(
+                rect,
+                egui::Rounding::none(),
+                Color32::TRANSPARENT,
+                Stroke::new(1.0, DEBUG_PINK),
+            );
+
+            // overlay with transform, hover and tessellation info
+            let mut overlay = vec![
+                format!("rect = {:?}", rect),
+                format!(
+                    "center = {:?}, scale = {:.1} px/unit",
+                    center,
+                    transform.scale(1.0)
+                ),
+                match hover_gamma {
+                    Some(gamma) => format!("hover gamma = {:+.4} {:+.4}j", gamma.re, gamma.im),
+                    None => "hover gamma = -".to_string(),
+                },
+                format!("shapes = {}, points = {}", stats.shapes, stats.points),
+            ];
+            if let Some(paint_time) = stats.paint_time {
+                overlay.push(format!("paint = {:.0} µs", paint_time.as_micros()));
+            }
+            painter.text(
+                rect.left_top(),
+                Align2::LEFT_TOP,
+                overlay.join("\n"),
+                FontId::monospace(10.0),
+                DEBUG_PINK,
+            );
+        }
+        stats
+    }
+
+    /// Grid circles, ticks and their labels in screen coordinates
+    fn grid_shapes(
+        &self,
+        painter: &Painter,
+        transform: &ChartTransform,
+        grid_color: Color32,
+        normal_line: Stroke,
+    ) -> Vec<Shape> {
+        let style = &self.style;
+        // finer circles and labels come in as the chart grows
+        let unit_radius = transform.scale(1.0);
+        let (grid_level, label_level) = if let Some(density) = self.grid_density {
+            (density.grid_level(), Some(density.label_level()))
+        } else if self.adaptive_grid {
+            (
+                grid::grid_level(unit_radius),
+                grid::label_level(unit_radius)
+                    .map(|level| level.min(grid::grid_level(unit_radius))),
+            )
+        } else {
+            (0, Some(0))
+        };
+        let label_level = label_level.filter(|_| self.grid_labels);
+        let mut grid_labels = Vec::new();
+        let mut shapes = Vec::new();
+
+        let mut grid = Vec::new();
+
+        // draw reactance circles
+        for (x, level) in grid::REACTANCES {
+            if level > grid_level {
+                continue;
+            }
+            grid.push(self.reactance_arc(transform, x, &normal_line));
+            grid.push(self.reactance_arc(transform, -x, &normal_line));
+            if label_level.is_some_and(|max| level <= max) {
+                for x in [x, -x] {
+                    // label just inside the rim where the arc ends
+                    let end = (Complex::new(0.0, x) - 1.0) / (Complex::new(0.0, x) + 1.0);
+                    let align = Align2([
+                        if end.re > 0.0 { Align::Max } else { Align::Min },
+                        if end.im > 0.0 { Align::Min } else { Align::Max },
+                    ]);
+                    grid_labels.push((
+                        transform.gamma_to_screen(&(end * 0.98)),
+                        align,
+                        match self.format.value_display {
+                            ValueDisplay::Absolute => self.format.signed_eng(x * self.Z0.re, "Ω"),
+                            _ => self.format.signed(x),
+                        },
+                    ));
+                }
+            }
+        }
+
+        // draw resistance circles
+        for (r, level) in grid::RESISTANCES {
+            if level > grid_level {
+                continue;
+            }
+            grid.push(self.resistance_circle(transform, r, &normal_line));
+            if label_level.is_some_and(|max| level <= max) && r > 0.0 {
+                // label above the real axis, left of where the circle crosses it
+                grid_labels.push((
+                    transform.gamma_to_screen(&Complex::new((r - 1.0) / (r + 1.0), 0.0))
+                        + vec2(-2.0, -2.0),
+                    Align2::RIGHT_BOTTOM,
+                    match self.format.value_display {
+                        ValueDisplay::Absolute => self.format.eng(r * self.Z0.re, "Ω"),
+                        _ => self.format.number(r),
+                    },
+                ));
+            }
+        }
+        // zero reactance/susceptance curve (x-axis)
+        if self.real_axis.visible {
+            let stroke = self.real_axis.stroke.unwrap_or(normal_line);
+            grid.push(Shape::line_segment(
+                [
+                    transform.gamma_to_screen(&Complex::new(-1.0, 0.0)),
+                    transform.gamma_to_screen(&Complex::new(1.0, 0.0)),
+                ],
+                stroke,
+            ));
+            if self.real_axis.tick_length > 0.0 {
+                let half = vec2(0.0, self.real_axis.tick_length / 2.0);
+                for (r, level) in grid::RESISTANCES {
+                    if level > 1 {
+                        continue;
+                    }
+                    let pos = transform.gamma_to_screen(&Complex::new((r - 1.0) / (r + 1.0), 0.0));
+                    grid.push(Shape::line_segment([pos - half, pos + half], stroke));
+                }
+            }
+        }
+
+        // the admittance grid is the impedance grid mirrored through the center
+        let center = transform.gamma_to_screen(&Complex::new(0.0, 0.0));
+        match self.plane {
+            Plane::Impedance => {
+                shapes.extend(dash(grid, style.grid_line_style));
+            }
+            Plane::Admittance => {
+                let mirrored = grid.into_iter().map(|shape| mirror(shape, center, None));
+                shapes.extend(dash(mirrored, style.admittance_grid_line_style));
+                for (pos, align, _) in &mut grid_labels {
+                    *pos = center + (center - *pos);
+                    *align = Align2([flip(align.x()), flip(align.y())]);
+                }
+            }
+            Plane::Both => {
+                let mirrored: Vec<_> = grid
+                    .iter()
+                    .map(|shape| mirror(shape.clone(), center, Some(style.admittance_grid_color)))
+                    .collect();
+                shapes.extend(dash(grid, style.grid_line_style));
+                shapes.extend(dash(mirrored, style.admittance_grid_line_style));
+            }
+        }
+
+        // emphasize the r=0 and r=1 circles of the shown grids unless told otherwise
+        let emphasized = self.emphasized.clone().unwrap_or_else(|| {
+            let impedance = [GridCircle::Resistance(0.0), GridCircle::Resistance(1.0)];
+            let admittance = [GridCircle::Conductance(0.0), GridCircle::Conductance(1.0)];
+            match self.plane {
+                Plane::Impedance => impedance.to_vec(),
+                Plane::Admittance => admittance.to_vec(),
+                Plane::Both => [impedance, admittance].concat(),
+            }
+        });
+        for circle in emphasized {
+            let color = style.strong_grid_color.unwrap_or(
+                if circle.is_admittance() && self.plane == Plane::Both {
+                    style.admittance_grid_color
+                } else {
+                    grid_color
+                },
+            );
+            let stroke = Stroke::new(style.strong_grid_width, color);
+            let shape = match circle {
+                GridCircle::Resistance(r) | GridCircle::Conductance(r) => {
+                    self.resistance_circle(transform, r, &stroke)
+                }
+                GridCircle::Reactance(x) | GridCircle::Susceptance(x) if x == 0.0 => {
+                    Shape::line_segment(
+                        [
+                            transform.gamma_to_screen(&Complex::new(-1.0, 0.0)),
+                            transform.gamma_to_screen(&Complex::new(1.0, 0.0)),
+                        ],
+                        stroke,
+                    )
+                }
+                GridCircle::Reactance(x) | GridCircle::Susceptance(x) => {
+                    self.reactance_arc(transform, x, &stroke)
+                }
+            };
+            shapes.push(if circle.is_admittance() {
+                mirror(shape, center, None)
+            } else {
+                shape
+            });
+        }
+
+        // ∠Γ is the same on both planes, so the ticks are never mirrored
+        if self.angle_ticks {
+            for degrees in (-170..=180).step_by(10) {
+                let major = degrees % 30 == 0;
+                let length = if major { 10.0 } else { 5.0 } / unit_radius;
+                let angle = (degrees as f32).to_radians();
+                grid_labels.extend(major.then(|| {
+                    let (sin, cos) = angle.sin_cos();
+                    let align = |v: f32| match v {
+                        v if v > 0.3 => Align::Max,
+                        v if v < -0.3 => Align::Min,
+                        _ => Align::Center,
+                    };
+                    (
+                        transform.gamma_to_screen(&Complex::from_polar(1.0 - 1.3 * length, angle)),
+                        // toward the center
+                        Align2([align(cos), flip(align(sin))]),
+                        format!("{}°", degrees),
+                    )
+                }));
+                shapes.push(Shape::line_segment(
+                    [
+                        transform.gamma_to_screen(&Complex::from_polar(1.0, angle)),
+                        transform.gamma_to_screen(&Complex::from_polar(1.0 - length, angle)),
+                    ],
+                    normal_line,
+                ));
+            }
+        }
+
+        if let Some(degrees) = self.radial_labels {
+            let direction = Complex::from_polar(1.0, degrees.to_radians());
+            // ticks across the spoke, labels beside them
+            let across = Complex::new(0.0, 4.0 / unit_radius) * direction;
+            for i in 1..=5 {
+                let magnitude = i as f32 * 0.2;
+                let at = direction * magnitude;
+                shapes.push(Shape::line_segment(
+                    [
+                        transform.gamma_to_screen(&(at - across)),
+                        transform.gamma_to_screen(&(at + across)),
+                    ],
+                    normal_line,
+                ));
+                grid_labels.push((
+                    transform.gamma_to_screen(&(at + across * 3.0)),
+                    Align2::CENTER_CENTER,
+                    self.format.number(magnitude),
+                ));
+            }
+        }
+
+        let fonts = painter.fonts();
+        for (pos, align, label) in grid_labels {
+            shapes.push(Shape::text(
+                &fonts,
+                pos,
+                align,
+                label,
+                FontId::proportional(style.font_size * 0.75),
+                grid_color,
+            ));
+        }
+        shapes
+    }
+
+    /// Impedance, Admittance, or Both
+    pub fn plane(mut self, plane: Plane) -> Self {
+        self.plane = plane;
+        self
+    }
+
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn mouse_vswr(mut self, show: bool) -> Self {
+        self.mouse_vswr = show;
+        self
+    }
+
+    /// Show the transmission coefficient T = 1 + Γ in polar form under the cursor and
+    /// for each marker, e.g. to check the mismatch between cascaded stages
+    pub fn transmission_coefficient(mut self, show: bool) -> Self {
+        self.transmission_coefficient = show;
+        self
+    }
+
+    /// Paint the cursor circles and values under the pointer. When disabled nothing
+    /// is painted for the pointer and the values are only returned in
+    /// [`SmithChartResponse::cursor`], e.g. for a status bar.
+    pub fn hover_readout(mut self, show: bool) -> Self {
+        self.hover_readout = show;
+        self
+    }
+
+    /// Near the unit-conductance circle, highlight it and show the susceptance and
+    /// open or short stub length that match the point under the cursor
+    pub fn stub_helper(mut self, show: bool) -> Self {
+        self.stub_helper = show;
+        self
+    }
+
+    /// Add the cursor position on the wavelengths toward generator scale to the
+    /// readout, for reading line lengths off the chart
+    pub fn wavelength_readout(mut self, show: bool) -> Self {
+        self.wavelength_readout = show;
+        self
+    }
+
+    /// Formatting of readout values
+    pub fn number_format(mut self, format: NumberFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Number of significant digits in readout values
+    pub fn significant_digits(mut self, digits: usize) -> Self {
+        self.format.significant_digits = digits;
+        self
+    }
+
+    /// Show normalized or absolute impedances in readouts and grid labels
+    pub fn value_display(mut self, display: ValueDisplay) -> Self {
+        self.format.value_display = display;
+        self
+    }
+
+    /// Show angles such as ∠Γ in degrees, radians or both
+    pub fn angle_unit(mut self, unit: AngleUnit) -> Self {
+        self.format.angle_unit = unit;
+        self
+    }
+
+    /// Unit of frequencies in readouts, labels and markers
+    pub fn frequency_unit(mut self, unit: FrequencyUnit) -> Self {
+        self.format.frequency_unit = unit;
+        self
+    }
+
+    /// Character between the integer and fractional part of readout values
+    pub fn decimal_separator(mut self, separator: char) -> Self {
+        self.format.decimal_separator = separator;
+        self
+    }
+
+    /// Translated built-in strings
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Colors and strokes of the chart, e.g. one of the presets:
+    /// ```
+    /// # use egui_smith_chart::{SmithChart, SmithChartStyle};
+    /// let chart = SmithChart::new("report").style(SmithChartStyle::print());
+    /// ```
+    pub fn style(mut self, style: SmithChartStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Place a marker at the best match of trace number `trace`, the sample with the
+    /// smallest |Γ|, and select it. Returns the achieved VSWR and return loss, `None` if
+    /// the trace doesn't exist or has no finite samples:
+    /// ```no_run
+    /// # let ctx = egui::Context::default();
+    /// # let chart = egui_smith_chart::SmithChart::new("chart");
+    /// if let Some(best) = chart.mark_best_match(&ctx, 0) {
+    ///     println!("VSWR {:.2}, RL {:.1} dB", best.min_vswr, best.return_loss());
+    /// }
+    /// ```
+    /// Also offered in the right click menu for the selected trace.
+    pub fn mark_best_match(&self, ctx: &egui::Context, trace: usize) -> Option<TraceStats> {
+        let mut state = SmithChartState::load(ctx, self.id_source).unwrap_or_default();
+        let stats = self.add_best_match_marker(&mut state, trace)?;
+        state.store(ctx, self.id_source);
+        Some(stats)
+    }
+
+    /// Self-contained JSON of the chart setup: style, reference impedance, plane,
+    /// traces, markers and view, to be restored with [`json::Session::import`]
+    #[cfg(feature = "json")]
+    pub fn export_session(&self, ctx: &egui::Context) -> String {
+        json::Session {
+            z0: self.Z0,
+            plane: self.plane,
+            style: self.style.clone(),
+            traces: self.traces.clone(),
+            state: SmithChartState::load(ctx, self.id_source).unwrap_or_default(),
+        }
+        .to_json()
+    }
+
+    /// Single-page PDF of the chart as it was last shown by `ctx`, `size` points square,
+    /// with vector lines and selectable text for reports:
+    /// ```no_run
+    /// # let ctx = egui::Context::default();
+    /// # let mut chart = egui_smith_chart::SmithChart::new("chart");
+    /// std::fs::write("chart.pdf", chart.export_pdf(&ctx, 400.0)).unwrap();
+    /// ```
+    /// The cursor and pinned cursors are left out. Without a
+    /// [`SmithChartStyle::background`] the page is filled like an egui window. Text is
+    /// set in the base fonts Helvetica and Symbol, which have no glyphs for most
+    /// characters beyond Western European letters and Greek; those are written as '?'.
+    pub fn export_pdf(&mut self, ctx: &egui::Context, size: f32) -> Vec<u8> {
+        let (_, output, rect) = self.paint_page(ctx, size, 1.0);
+        pdf::document(&output.shapes, rect)
+    }
+
+    /// SVG document of the chart like [`SmithChart::export_pdf`], for web pages and
+    /// vector editors:
+    /// ```no_run
+    /// # let ctx = egui::Context::default();
+    /// # let mut chart = egui_smith_chart::SmithChart::new("chart");
+    /// std::fs::write("chart.svg", chart.export_svg(&ctx, 400.0)).unwrap();
+    /// ```
+    pub fn export_svg(&mut self, ctx: &egui::Context, size: f32) -> String {
+        let (_, output, rect) = self.paint_page(ctx, size, 1.0);
+        svg::document(&output.shapes, rect)
+    }
+
+    /// Image of the chart as it was last shown by `ctx`, `size` points square at the
+    /// pixel density of `ctx`, e.g. to save as PNG or to share. Rendered in software, so
+    /// it works outside the frame and without access to the GPU. Like
+    /// [`SmithChart::export_pdf`] it leaves out the cursors.
+    pub fn export_image(&mut self, ctx: &egui::Context, size: f32) -> egui::ColorImage {
+        let pixels_per_point = ctx.pixels_per_point();
+        let (page, output, _) = self.paint_page(ctx, size, pixels_per_point);
+        let pixels = (size * pixels_per_point).round() as usize;
+        raster::image(
+            &page.tessellate(output.shapes),
+            &output.textures_delta,
+            [pixels, pixels],
+            pixels_per_point,
+        )
+    }
+
+    /// Put [`SmithChart::export_image`] on the system clipboard, to paste the chart
+    /// into a chat or an email. Also offered in the right click menu, which reports
+    /// failures in [`SmithChartResponse::copy_error`].
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    pub fn copy_image(&mut self, ctx: &egui::Context, size: f32) -> Result<(), SmithChartError> {
+        let image = self.export_image(ctx, size);
+        let bytes: Vec<u8> = image
+            .pixels
+            .iter()
+            .flat_map(|color| color.to_srgba_unmultiplied())
+            .collect();
+        arboard::Clipboard::new()?.set_image(arboard::ImageData {
+            width: image.size[0],
+            height: image.size[1],
+            bytes: bytes.into(),
+        })?;
+        Ok(())
+    }
+
+    /// Frame of an offscreen context with the chart as it was last shown by `ctx`,
+    /// painted `size` points square without cursors and with the grid kept as lines
+    fn paint_page(
+        &mut self,
+        ctx: &egui::Context,
+        size: f32,
+        pixels_per_point: f32,
+    ) -> (egui::Context, egui::FullOutput, Rect) {
+        let mut state = SmithChartState::load(ctx, self.id_source).unwrap_or_default();
+        self.resolve(&state);
+        state.frozen_cursor = None;
+        state.pinned_cursors = [None; 2];
+        let style = ctx.style();
+
+        let page = egui::Context::default();
+        page.set_style(style.clone());
+        grid::keep_vectors(&page);
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(size));
+        page.begin_frame(egui::RawInput {
+            screen_rect: Some(rect),
+            pixels_per_point: Some(pixels_per_point),
+            ..Default::default()
+        });
+        let painter = Painter::new(page.clone(), egui::LayerId::background(), rect);
+        if self.style.background.is_none() {
+            painter.rect_filled(rect, 0.0, style.visuals.window_fill());
+        }
+        self.paint(&painter, rect, &state);
+        let output = page.end_frame();
+        (page, output, rect)
+    }
+
+    /// Add a trace to the chart, limited to its [`Trace::frequency_range`]
+    pub fn trace(mut self, trace: Trace) -> Self {
+        let mut trace = trace.trim().renormalize(self.Z0);
+        trace.checked = Some(trace.validity());
+        self.traces.push(trace);
+        self
+    }
+
+    /// Add a group of traces, e.g. from [`Network::group`]. Groups can be hidden
+    /// together by clicking their swatch in the legend or through
+    /// [`SmithChartState::hidden_groups`].
+    pub fn group(mut self, group: TraceGroup) -> Self {
+        for trace in group.into_traces() {
+            self = self.trace(trace);
+        }
+        self
+    }
+
+    /// Keyboard shortcuts, handled while the chart has focus or is hovered. Pressing
+    /// Delete removes the selected markers by default.
+    pub fn shortcuts(mut self, shortcuts: Shortcuts) -> Self {
+        self.shortcuts = shortcuts;
+        self
+    }
+
+    /// Right click menu copying the markers, or the clicked point, as Python or MATLAB
+    /// code. Off by default.
+    pub fn context_menu(mut self, enabled: bool) -> Self {
+        self.context_menu = enabled;
+        self
+    }
+
+    /// Highlight the chart with a "drop to load antenna.s1p" prompt while Touchstone,
+    /// load-pull or JSON files are dragged over it, and return the files dropped on it
+    /// in [`SmithChartResponse::dropped_files`]. Off by default.
+    pub fn file_drop(mut self, enabled: bool) -> Self {
+        self.file_drop = enabled;
+        self
+    }
+
+    /// Samples with |Γ| > 1, as measured on active devices, are expected rather than
+    /// flagged as invalid. NaN and infinite samples are flagged either way.
+    pub fn extended(mut self, extended: bool) -> Self {
+        self.extended = extended;
+        self
+    }
+
+    /// Double-clicking the chart drops a marker on the nearest trace point within the
+    /// pick radius, or else a free marker at the pointer. Off by default.
+    pub fn double_click_markers(mut self, enabled: bool) -> Self {
+        self.double_click_markers = enabled;
+        self
+    }
+
+    /// Clicking empty chart space freezes the cursor readout at that point, marked with
+    /// a crosshair, until the next click on empty space. Off by default.
+    pub fn freeze_on_click(mut self, enabled: bool) -> Self {
+        self.freeze_on_click = enabled;
+        self
+    }
+
+    /// Interactions the chart senses, overriding the automatic choice of sensing clicks
+    /// and drags only when something uses them. With [`Sense::hover`] the chart never
+    /// captures clicks or drags, e.g. inside a scroll area, and with
+    /// [`Sense::click_and_drag`] it always does.
+    pub fn sense(mut self, sense: Sense) -> Self {
+        self.sense = Some(sense);
+        self
+    }
+
+    /// Only display the chart: no hover, clicks, drags, shortcuts or cursors, which
+    /// is cheaper with many small charts and safe from accidental edits in dashboards.
+    /// The view and hidden traces of the chart state still apply.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// List the traces and lines in the top left corner, or where
+    /// [`SmithChart::legend_placement`] puts them. Each [`TraceGroup`] is one entry
+    /// that expands into its traces when clicked.
+    pub fn legend(mut self, show: bool) -> Self {
+        self.legend = show;
+        self
+    }
+
+    /// Corner of the chart or place outside it for the legend. With
+    /// [`LegendPlacement::External`] only [`SmithChartResponse::legend`] lists the
+    /// entries, for the app to show them elsewhere. The frame is styled with
+    /// [`SmithChartStyle::legend_background`] and [`SmithChartStyle::legend_border`].
+    pub fn legend_placement(mut self, placement: LegendPlacement) -> Self {
+        self.legend_placement = placement;
+        self
+    }
+
+    /// Compact rendering for tables and lists, e.g. 32 to 64 points: only the outer and
+    /// r = 1 circles, traces and points, without labels, readouts, zoom or dragging.
+    /// ```
+    /// # use egui_smith_chart::{SmithChart, Trace};
+    /// # use egui_smith_chart::Complex;
+    /// # fn row(ui: &mut egui::Ui, name: &str, gamma: Complex<f32>) {
+    /// ui.horizontal(|ui| {
+    ///     ui.label(name);
+    ///     SmithChart::new(name)
+    ///         .thumbnail(true)
+    ///         .size(40.0)
+    ///         .trace(Trace::new(name, vec![gamma]))
+    ///         .show(ui);
+    /// });
+    /// # }
+    /// ```
+    pub fn thumbnail(mut self, thumbnail: bool) -> Self {
+        self.thumbnail = thumbnail;
+        self
+    }
+
+    /// Show the admittance image of the cursor and the selected markers, i.e. the
+    /// point mirrored through the chart center, e.g. for designing shunt elements
+    pub fn admittance_ghost(mut self, show: bool) -> Self {
+        self.admittance_ghost = show;
+        self
+    }
+
+    /// Measure by dragging on empty chart space: shows the straight-line |ΔΓ| and the
+    /// rotation at constant |Γ| from the drag start to the pointer
+    pub fn ruler(mut self, ruler: bool) -> Self {
+        self.ruler = ruler;
+        self
+    }
+
+    /// Compare trace `measurement` against trace `reference`: corresponding points are
+    /// connected, see [`SmithChart::comparison_locus`], and the largest and mean |ΔΓ|
+    /// are shown in the readout and returned in [`SmithChartResponse::deviation`]
+    pub fn compare(mut self, reference: usize, measurement: usize) -> Self {
+        self.comparison = Some((reference, measurement));
+        self
+    }
+
+    /// Draw the comparison set with [`SmithChart::compare`] as whiskers between the
+    /// traces, the default, or as an extra trace of their difference or ratio
+    pub fn comparison_locus(mut self, locus: ComparisonLocus) -> Self {
+        self.comparison_locus = locus;
+        self
+    }
+
+    /// Characteristic impedance of the chart in ohms, 50 Ω by default. Traces with
+    /// their own reference impedance are renormalized to it.
+    pub fn z0(mut self, z0: Complex<f32>) -> Self {
+        self.Z0 = z0;
+        self.traces = std::mem::take(&mut self.traces)
+            .into_iter()
+            .map(|trace| trace.renormalize(z0))
+            .collect();
+        self
+    }
+
+    /// Write marker and cursor readouts as R + jX or as |Z| ∠ θ with |Γ| ∠ θ, until the
+    /// user switches them with [`Command::ToggleReadoutForm`] or the context menu. The
+    /// choice and markers switched on their own are kept in [`SmithChartState`].
+    pub fn readout_form(mut self, form: ComplexForm) -> Self {
+        self.readout_form = form;
+        self
+    }
+
+    /// List a readout line with the impedance of each marker, and on S-parameter
+    /// traces (see [`Trace::port`]) a line with the magnitude in dB and the phase
+    pub fn marker_readout(mut self, show: bool) -> Self {
+        self.marker_readout = show;
+        self
+    }
+
+    /// Mark the series and parallel resonances of swept traces, where the reactance
+    /// crosses zero (see [`Trace::resonances`]), with a diamond labelled with the
+    /// frequency and the resistance there. Off by default.
+    pub fn resonance_markers(mut self, show: bool) -> Self {
+        self.resonance_markers = show;
+        self
+    }
+
+    /// Report marker values normalized to the reference impedance each trace was
+    /// given in rather than to the chart Z0
+    pub fn native_reference(mut self, native: bool) -> Self {
+        self.native_reference = native;
+        self
+    }
+
+    /// Add a line over borrowed data, painted like a trace but without copying the
+    /// samples every frame
+    pub fn line(mut self, line: Line<'a>) -> Self {
+        self.lines.push(line);
+        self
+    }
+
+    /// Draw a parametric curve, e.g. a theoretical locus:
+    /// ```
+    /// # use egui_smith_chart::{Curve, Domain, SmithChart};
+    /// # use egui_smith_chart::Complex;
+    /// // varactor from 0.5 to 5 pF in series with 5 nH at 1 GHz, normalized to 50 Ω
+    /// let w = 2.0 * std::f32::consts::PI * 1e9;
+    /// let chart = SmithChart::new("tuning").curve(
+    ///     Curve::new(
+    ///         move |c_pf| Complex::new(0.1, (w * 5e-9 - 1.0 / (w * c_pf * 1e-12)) / 50.0),
+    ///         0.5..=5.0,
+    ///     )
+    ///     .domain(Domain::Impedance),
+    /// );
+    /// ```
+    pub fn curve(mut self, curve: Curve<'a>) -> Self {
+        self.curves.push(curve);
+        self
+    }
+
+    /// Draw finer grid circles when the chart is large or zoomed in
+    pub fn adaptive_grid(mut self, adaptive: bool) -> Self {
+        self.adaptive_grid = adaptive;
+        self
+    }
+
+    /// Tick ∠Γ around the rim every 10°, labelling every 30°. A lighter alternative
+    /// to printed peripheral scales.
+    pub fn angle_ticks(mut self, show: bool) -> Self {
+        self.angle_ticks = show;
+        self
+    }
+
+    /// Label |Γ| at 0.2, 0.4 … 1 along the spoke at `degrees`, e.g. 90 for straight up
+    pub fn radial_labels(mut self, degrees: f32) -> Self {
+        self.radial_labels = Some(degrees);
+        self
+    }
+
+    /// Hide, restyle or tick the real axis:
+    /// ```
+    /// # use egui_smith_chart::{RealAxis, SmithChart};
+    /// let chart = SmithChart::new("ticked").real_axis(RealAxis::default().ticks(8.0));
+    /// ```
+    pub fn real_axis(mut self, real_axis: RealAxis) -> Self {
+        self.real_axis = real_axis;
+        self
+    }
+
+    /// Draw `circles` with the strong grid stroke of [`SmithChartStyle`] instead of
+    /// the r=0 and r=1 circles, or g=0 and g=1 on the admittance plane:
+    /// ```
+    /// # use egui_smith_chart::{GridCircle, Plane, SmithChart};
+    /// let chart = SmithChart::new("matching").plane(Plane::Both).emphasize([
+    ///     GridCircle::Resistance(1.0),
+    ///     GridCircle::Conductance(1.0),
+    /// ]);
+    /// ```
+    pub fn emphasize(mut self, circles: impl IntoIterator<Item = GridCircle>) -> Self {
+        self.emphasized = Some(circles.into_iter().collect());
+        self
+    }
+
+    /// Always draw the grid at `density`, whatever the size of the chart.
+    /// Takes precedence over [`SmithChart::adaptive_grid`].
+    pub fn grid_density(mut self, density: GridDensity) -> Self {
+        self.grid_density = Some(density);
+        self
+    }
+
+    /// Label the grid circles with their normalized values. With
+    /// [`SmithChart::adaptive_grid`] the labels of finer circles only appear
+    /// once there is room for them.
+    pub fn grid_labels(mut self, show: bool) -> Self {
+        self.grid_labels = show;
+        self
+    }
+
+    /// Shade an area of the chart, e.g. a VSWR specification
+    pub fn region(mut self, region: Region) -> Self {
+        self.regions.push(region);
+        self
+    }
+
+    /// Limit the mismatch over a frequency range: the passing area is shaded, and the
+    /// swept reflection traces are colored with [`SmithChartStyle::spec_pass`] where they meet
+    /// every mask covering the frequency and [`SmithChartStyle::spec_fail`] where they
+    /// don't:
+    /// ```
+    /// # use egui_smith_chart::{SmithChart, SpecMask};
+    /// let chart = SmithChart::new("antenna")
+    ///     .spec_mask(SpecMask::vswr(2.4e9..=2.5e9, 2.0))
+    ///     .spec_mask(SpecMask::return_loss(5.15e9..=5.85e9, 10.0));
+    /// ```
+    pub fn spec_mask(mut self, mask: SpecMask) -> Self {
+        self.spec_masks.push(mask);
+        self
+    }
+
+    /// Shade where the points of a cloud, e.g. from a Monte Carlo analysis, are dense,
+    /// see [`preprocess::Density`]
+    pub fn density(mut self, density: Density) -> Self {
+        self.densities.push(density);
+        self
+    }
+
+    /// Paint layers bottom to top in `order`. Layers left out are not painted.
+    /// ```
+    /// # use egui_smith_chart::{Layer, SmithChart};
+    /// // shaded regions on top of the traces
+    /// let chart = SmithChart::new("chart").layer_order([
+    ///     Layer::Background,
+    ///     Layer::Grid,
+    ///     Layer::Traces,
+    ///     Layer::Regions,
+    ///     Layer::Markers,
+    ///     Layer::Overlays,
+    ///     Layer::Readout,
+    /// ]);
+    /// ```
+    pub fn layer_order(mut self, order: impl IntoIterator<Item = Layer>) -> Self {
+        self.layers = order.into_iter().collect();
+        self
+    }
+
+    /// Show or hide a layer
+    pub fn layer_visible(mut self, layer: Layer, visible: bool) -> Self {
+        self.hidden_layers.retain(|&hidden| hidden != layer);
+        if !visible {
+            self.hidden_layers.push(layer);
+        }
+        self
+    }
+
+    /// Draw the load and source stability circles of `stability`
+    pub fn stability_circles(self, stability: &Stability) -> Self {
+        self.curve(
+            stability
+                .load_circle
+                .curve()
+                .color(Color32::from_rgb(230, 60, 60)),
+        )
+        .curve(
+            stability
+                .source_circle
+                .curve()
+                .color(Color32::from_rgb(230, 150, 40)),
+        )
+    }
+
+    /// Draw a family of gain circles labelled with their gain, and the optimum
+    /// termination as a point
+    pub fn gain_circles(mut self, family: &GainCircles) -> Self {
+        for (i, (gain, circle)) in family.circles().into_iter().enumerate() {
+            let label = if i == 0 {
+                format!("max {}", self.format.db(gain))
+            } else {
+                self.format.db(gain)
+            };
+            let curve = if circle.radius < 1e-4 {
+                Curve::point(circle.center)
+            } else {
+                circle.curve()
+            };
+            self = self.curve(curve.color(family.color).width(1.0).label(label));
+        }
+        self
+    }
+
+    /// Draw a family of noise figure circles labelled with their noise figure, and
+    /// Γopt as a point
+    pub fn noise_circles(mut self, family: &NoiseCircles) -> Self {
+        let optimum = format!("min {}", self.format.db(family.fmin_db));
+        self = self.curve(
+            Curve::point(family.gamma_opt)
+                .color(family.color)
+                .label(optimum),
+        );
+        for (nf, circle) in family.circles() {
+            let label = self.format.db(nf);
+            self = self.curve(circle.curve().color(family.color).width(1.0).label(label));
+        }
+        self
+    }
+
+    /// Draw the 1σ and 2σ ellipses of `uncertainty` around its nominal point
+    pub fn tolerance_ellipses(mut self, uncertainty: &Uncertainty) -> Self {
+        self = self.curve(Curve::point(uncertainty.center).color(uncertainty.color));
+        for sigma in [1, 2] {
+            let curve = uncertainty.ellipse(sigma as f32);
+            self = self.curve(
+                curve
+                    .color(uncertainty.color)
+                    .width(1.0)
+                    .label(format!("{}σ", sigma)),
+            );
+        }
+        self
+    }
+
+    /// Draw labelled contours, e.g. output power from a load-pull. Sets are listed in
+    /// a legend, and the area meeting all their [`ContourSet::highlight_above`]
+    /// thresholds is shaded:
+    /// ```
+    /// # use egui_smith_chart::{contour::loadline_power_dbm, ContourSet, SmithChart};
+    /// # use egui::Color32;
+    /// let power = ContourSet::from_fn(
+    ///     "Pout",
+    ///     "dBm",
+    ///     |gamma| loadline_power_dbm(gamma, 50.0, 12.5, 40.0),
+    ///     &[39.0, 38.0],
+    /// )
+    /// .highlight_above(38.0);
+    /// // toy efficiency peaking at a different load
+    /// let efficiency = ContourSet::from_fn(
+    ///     "PAE",
+    ///     "%",
+    ///     |gamma| 70.0 - 100.0 * (gamma - egui_smith_chart::Complex::new(-0.4, 0.3)).norm(),
+    ///     &[60.0, 50.0],
+    /// )
+    /// .color(Color32::LIGHT_BLUE)
+    /// .highlight_above(50.0);
+    /// let chart = SmithChart::new("load-pull").contours(power).contours(efficiency);
+    /// ```
+    pub fn contours(mut self, contours: ContourSet) -> Self {
+        self.contours.push(contours);
+        self
+    }
+
+    /// Place a marker on trace number `trace` at `frequency` for this frame.
+    ///
+    /// Unlike the markers in [`SmithChartState`] these can't be dragged by the user,
+    /// the application moves them by passing another frequency on the next frame.
+    pub fn marker_at(mut self, trace: usize, frequency: f64) -> Self {
+        self.markers.push(Marker { trace, frequency });
+        self
+    }
+
+    /// Distance in points within which traces and markers react to the pointer
+    pub fn pick_radius(mut self, radius: f32) -> Self {
+        self.pick_radius = radius;
+        self
+    }
+
+    /// Gear icon in the top right corner opening a popup where users edit the grid,
+    /// overlays and style at runtime. Their changes are kept in
+    /// [`SmithChartState::settings`] and override this setup. Off by default.
+    pub fn settings_popup(mut self, show: bool) -> Self {
+        self.settings_popup = show;
+        self
+    }
+
+    /// Hovering a trace anywhere along its line shows its name, source file, port and
+    /// reference impedance in a tooltip, see [`Trace::source`] and [`Trace::port`]. Off
+    /// by default.
+    pub fn trace_tooltips(mut self, trace_tooltips: bool) -> Self {
+        self.trace_tooltips = trace_tooltips;
+        self
+    }
+
+    /// Called with the normalized impedance under the pointer when the chart is clicked
+    pub fn on_click(mut self, on_click: impl FnMut(Complex<f32>) + 'a) -> Self {
+        self.on_click = Some(Box::new(on_click));
+        self
+    }
+
+    /// Called with the marker index and the marker when a marker is dragged
+    pub fn on_marker_moved(mut self, on_marker_moved: impl FnMut(usize, &Marker) + 'a) -> Self {
+        self.on_marker_moved = Some(Box::new(on_marker_moved));
+        self
+    }
+
+    /// Called with the closest sample when the pointer hovers near a trace
+    pub fn on_trace_hover(mut self, on_trace_hover: impl FnMut(&TraceHit) + 'a) -> Self {
+        self.on_trace_hover = Some(Box::new(on_trace_hover));
+        self
+    }
+
+    /// Called while painting, after grid, traces and markers but before the readout,
+    /// with a painter clipped to the chart and the current gamma↔screen transform
+    pub fn on_paint(mut self, on_paint: impl Fn(&Painter, &ChartTransform) + 'a) -> Self {
+        self.on_paint = Some(Box::new(on_paint));
+        self
+    }
+
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    fn resistance_circle(&self, transform: &ChartTransform, r: f32, stroke: &Stroke) -> Shape {
+        // the r = ∞ circle shrinks to the open circuit point
+        if !r.is_finite() {
+            return Shape::Noop;
+        }
+        let rel_center = Complex::new(r / (1.0 + r), 0.0);
+        let rel_radius = 1.0 / (1.0 + r);
+        let center = transform.gamma_to_screen(&rel_center);
+        let radius = transform.scale(rel_radius);
+        Shape::circle_stroke(center, radius, *stroke)
+    }
+
+    fn reactance_arc(
+        &self,
+        transform: &ChartTransform,
+        x: f32, // normalized reactance
+        stroke: &Stroke,
+    ) -> Shape {
+        // the x = ±∞ arcs shrink to the open circuit point
+        if !x.is_finite() {
+            return Shape::Noop;
+        }
+        let arc_points: Vec<Pos2> = if x.abs() >= 1.0 {
+            let yend: f32 = (2.0 * x) / (1.0 + x.powf(2.0));
+            let n = 128; // TODO: adaptive step count based on arc size
+
+            fn x_gt_one_arc(x: f32, gi: f32) -> f32 {
+                1.0 - f32::sqrt((gi * (2.0 - x * gi)) / x)
+            }
+
+            (0..=n)
+                .map(|i| {
+                    let gi = egui::remap(i as f32, 0.0..=(n as f32), 0.0..=yend);
+                    transform.gamma_to_screen(&Complex::new(x_gt_one_arc(x, gi), gi))
+                })
+                .collect()
+        } else {
+            let xstart = (x.powf(2.0) - 1.0) / (x.powf(2.0) + 1.0);
+            let n = 128; // TODO: adaptive step count based on arc size
+
+            // 1/x ∓ sqrt(1/x² - (gr - 1)²) rearranged to stay accurate, and 0 rather
+            // than NaN, as x goes to 0
+            fn x_lt_one_arc(x: f32, gr: f32) -> f32 {
+                let d = gr - 1.0;
+                x * d * d / (1.0 + (1.0 - x * x * d * d).max(0.0).sqrt())
+            }
+
+            (0..=n)
+                .map(|i| {
+                    let gr = egui::remap(i as f32, 0.0..=(n as f32), xstart..=1.0);
+                    transform.gamma_to_screen(&Complex::new(gr, x_lt_one_arc(x, gr)))
+                })
+                .collect()
+        };
+        Shape::Path(PathShape::line(arc_points, *stroke))
+    }
+
+    /// Readout of a normalized value and its absolute counterpart in `unit`,
+    /// as selected by [`NumberFormat::value_display`]
+    fn value_pair(
+        &self,
+        normalized_label: &str,
+        normalized: f32,
+        absolute_label: &str,
+        absolute: f32,
+        unit: &str,
+    ) -> String {
+        let normalized = format!("{} = {}", normalized_label, self.format.signed(normalized));
+        let absolute = format!("{} = {}", absolute_label, self.format.eng(absolute, unit));
+        match self.format.value_display {
+            ValueDisplay::Normalized => normalized,
+            ValueDisplay::Absolute => absolute,
+            ValueDisplay::Both => format!("{}, {}", normalized, absolute),
+        }
+    }
+
+    /// Admittance image of `gamma`: a ring at −Γ joined to Γ through the center
+    fn ghost(&self, transform: &ChartTransform, gamma: Complex<f32>, stroke: Stroke) -> [Shape; 2] {
+        let image = transform.gamma_to_screen(&-gamma);
+        [
+            Shape::line_segment(
+                [transform.gamma_to_screen(&gamma), image],
+                Stroke::new(stroke.width, stroke.color.linear_multiply(0.4)),
+            ),
+            Shape::circle_stroke(image, 4.0, stroke),
+        ]
+    }
+
+    /// Visible reference and measurement traces set with [`SmithChart::compare`]
+    fn comparison_traces(&self) -> Option<(&Trace, &Trace)> {
+        let (reference, measurement) = self.comparison?;
+        let reference = self.traces.get(reference).filter(|trace| trace.visible)?;
+        let measurement = self.traces.get(measurement).filter(|trace| trace.visible)?;
+        Some((reference, measurement))
+    }
+
+    fn deviation(&self) -> Option<Deviation> {
+        let (reference, measurement) = self.comparison_traces()?;
+        measurement.deviation(reference)
+    }
+
+    /// Readout of a normalized impedance and the impedance in ohms,
+    /// as selected by [`NumberFormat::value_display`]
+    fn complex_pair(
+        &self,
+        normalized: Complex<f32>,
+        absolute: Complex<f32>,
+        form: ComplexForm,
+    ) -> String {
+        let normalized = format!(
+            "{} = {}",
+            self.locale.normalized_impedance,
+            self.format.complex_in(normalized, "", form)
+        );
+        let absolute = format!(
+            "{} = {}",
+            self.locale.absolute_impedance,
+            self.format.complex_in(absolute, "Ω", form)
+        );
+        match self.format.value_display {
+            ValueDisplay::Normalized => normalized,
+            ValueDisplay::Absolute => absolute,
+            ValueDisplay::Both => format!("{}, {}", normalized, absolute),
+        }
+    }
+
+    fn cursor(&self, gamma: Complex<f32>) -> Cursor {
+        let normalized = self.gamma_to_z(&gamma);
+        Cursor {
+            gamma,
+            normalized,
+            impedance: math::scale(normalized, self.Z0),
+        }
+    }
+
+    fn gamma_to_z(&self, gamma: &Complex<f32>) -> Complex<f32> {
+        math::gamma_to_z(*gamma)
+    }
+
+    fn z_to_gamma(&self, z: &Complex<f32>) -> Complex<f32> {
+        math::z_to_gamma(*z)
+    }
+}
+
+/// Whether `what` hasn't been reported in `ctx` before, remembering it from now on
+#[cfg(feature = "log")]
+fn first_report(ctx: &egui::Context, what: impl std::hash::Hash) -> bool {
+    let id = Id::new("egui_smith_chart_reported").with(what);
+    let mut data = ctx.data();
+    let first = data.get_temp::<()>(id).is_none();
+    data.insert_temp(id, ());
+    first
+}
+
+/// Opposite alignment, for labels of the mirrored grid
+fn flip(align: Align) -> Align {
+    match align {
+        Align::Min => Align::Max,
+        Align::Center => Align::Center,
+        Align::Max => Align::Min,
+    }
+}
+
+/// `shapes` broken up into the dashes or dots of `line_style`, with its lengths clamped
+/// since the style fields are public
+fn dash(shapes: impl IntoIterator<Item = Shape>, line_style: LineStyle) -> Vec<Shape> {
+    let line_style = line_style.clamped();
+    if line_style == LineStyle::Solid {
+        return shapes.into_iter().collect();
+    }
+    let mut dashed = Vec::new();
+    for shape in shapes {
+        let (points, stroke) = match &shape {
+            Shape::Circle(circle) => {
+                let n = (circle.radius / 2.0).clamp(32.0, 512.0) as usize;
+                let points = (0..=n)
+                    .map(|i| {
+                        let angle = i as f32 / n as f32 * std::f32::consts::TAU;
+                        circle.center + circle.radius * vec2(angle.cos(), angle.sin())
+                    })
+                    .collect();
+                (points, circle.stroke)
+            }
+            Shape::LineSegment { points, stroke } => (points.to_vec(), *stroke),
+            Shape::Path(path) => {
+                let mut points = path.points.clone();
+                if path.closed {
+                    points.extend(path.points.first().copied());
+                }
+                (points, path.stroke)
+            }
+            _ => {
+                dashed.push(shape);
+                continue;
+            }
+        };
+        match line_style {
+            LineStyle::Solid => {}
+            LineStyle::Dashed { length, gap } => {
+                Shape::dashed_line_many(&points, stroke, length, gap, &mut dashed)
+            }
+            LineStyle::Dotted { spacing } => dashed.extend(Shape::dotted_line(
+                &points,
+                stroke.color,
+                spacing,
+                stroke.width / 2.0,
+            )),
+        }
+    }
+    dashed
+}
+
+/// `shape` mirrored through `center`, with its stroke recolored to `color` if given
+fn mirror(shape: Shape, center: Pos2, color: Option<Color32>) -> Shape {
+    let flip = |pos: Pos2| center + (center - pos);
+    let recolor = |stroke: Stroke| Stroke::new(stroke.width, color.unwrap_or(stroke.color));
+    match shape {
+        Shape::Circle(mut circle) => {
+            circle.center = flip(circle.center);
+            circle.stroke = recolor(circle.stroke);
+            Shape::Circle(circle)
+        }
+        Shape::LineSegment { points, stroke } => Shape::LineSegment {
+            points: points.map(flip),
+            stroke: recolor(stroke),
+        },
+        Shape::Path(mut path) => {
+            for point in &mut path.points {
+                *point = flip(*point);
+            }
+            path.stroke = recolor(path.stroke);
+            Shape::Path(path)
+        }
+        shape => shape,
+    }
+}
+
+/// Line from `from` to `to` with a fixed size arrow head at `to`, nothing if the two
+/// coincide and there is no direction to point in
+fn arrow(from: Pos2, to: Pos2, stroke: Stroke) -> Vec<Shape> {
+    if from == to {
+        return Vec::new();
+    }
+    let dir = (to - from).normalized();
+    let rot = emath::Rot2::from_angle(std::f32::consts::TAU / 12.0);
+    let head = 8.0;
+    vec![
+        Shape::line_segment([from, to], stroke),
+        Shape::line_segment([to, to - head * (rot * dir)], stroke),
+        Shape::line_segment([to, to - head * (rot.inverse() * dir)], stroke),
+    ]
+}
+
+/// Name of a dragged file without the directory, `name` where there is no path
+fn file_name(path: Option<&std::path::Path>, name: &str) -> String {
+    match path.and_then(|path| path.file_name()) {
+        Some(file_name) => file_name.to_string_lossy().to_string(),
+        None => name.to_string(),
+    }
+}
+
+/// A Touchstone, load-pull or (with the `json` feature) JSON file by its name, or a
+/// file whose name is unknown
+fn loadable(name: &str) -> bool {
+    let extension = match name.rsplit_once('.') {
+        Some((_, extension)) => extension.to_ascii_lowercase(),
+        None => return name.is_empty(),
+    };
+    touchstone::ports(&extension).is_some()
+        || extension == "csv"
+        || (cfg!(feature = "json") && extension == "json")
+}
+
+/// Gear icon of the settings popup in the top right corner of the chart in `rect`
+fn gear_rect(rect: Rect) -> Rect {
+    Rect::from_min_size(
+        pos2(rect.right() - 22.0, rect.top() + 4.0),
+        Vec2::splat(18.0),
+    )
+}
+
+/// Number of points submitted for `shape`, for the debug overlay
+fn shape_points(shape: &Shape) -> usize {
+    match shape {
+        Shape::Vec(shapes) => shapes.iter().map(shape_points).sum(),
+        Shape::Path(path) => path.points.len(),
+        Shape::Mesh(mesh) => mesh.vertices.len(),
+        _ => 1,
     }
 }
@@ -0,0 +1,68 @@
+//! Lightweight per-frame lines over borrowed data.
+
+use egui::{Color32, Pos2, Shape, Stroke};
+
+use crate::{ChartTransform, Complex, SmithPoints};
+
+/// Polyline of reflection coefficients that borrows its data, for large or live
+/// datasets that shouldn't be copied into a [`crate::Trace`] every frame.
+///
+/// Lines are only painted: they have no markers, annotations or picking.
+pub struct Line<'a> {
+    pub(crate) name: String,
+    points: SmithPoints<'a>,
+    pub(crate) color: Option<Color32>,
+    pub(crate) width: f32,
+}
+impl<'a> Line<'a> {
+    /// Line through `gamma`, which is not copied when borrowed
+    pub fn new(name: impl Into<String>, gamma: impl Into<SmithPoints<'a>>) -> Self {
+        Self {
+            name: name.into(),
+            points: gamma.into(),
+            color: None,
+            width: 2.0,
+        }
+    }
+
+    /// Line through the items of `gamma`, iterated each time the chart is painted.
+    /// ```
+    /// # use egui_smith_chart::{Line, SmithChart};
+    /// # use egui_smith_chart::Complex;
+    /// let samples: Vec<(f64, Complex<f32>)> = Vec::new();
+    /// let chart = SmithChart::new("live")
+    ///     .line(Line::from_iter("live", samples.iter().map(|(_, gamma)| *gamma)));
+    /// ```
+    pub fn from_iter<I>(name: impl Into<String>, gamma: I) -> Self
+    where
+        I: IntoIterator<Item = Complex<f32>> + Clone + 'a,
+    {
+        Self::new(name, SmithPoints::lazy(gamma))
+    }
+
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Segments between consecutive points, straight from the points without
+    /// collecting them first
+    pub(crate) fn segments<'s>(
+        &'s self,
+        transform: &'s ChartTransform,
+        stroke: Stroke,
+    ) -> impl Iterator<Item = Shape> + 's {
+        let mut previous: Option<Pos2> = None;
+        self.points.iter().filter_map(move |gamma| {
+            let pos = transform.gamma_to_screen(&gamma);
+            previous
+                .replace(pos)
+                .map(|from| Shape::line_segment([from, pos], stroke))
+        })
+    }
+}
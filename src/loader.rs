@@ -0,0 +1,141 @@
+//! Opening Touchstone and load-pull files from a file dialog without blocking the UI.
+//!
+//! On native the dialog and the parsing run on a thread of their own, on the web the
+//! browser's file picker runs as a future on the page's event loop. Either way the
+//! result arrives through a channel that the app polls every frame:
+//! ```no_run
+//! # use egui_smith_chart::{loader::FileLoader, Trace};
+//! # fn ui(ui: &mut egui::Ui, loader: &mut FileLoader, traces: &mut Vec<Trace>) {
+//! if ui.button("Open…").clicked() {
+//!     loader.open(ui.ctx());
+//! }
+//! match loader.poll() {
+//!     Some(Ok(file)) => traces.extend(file.traces()),
+//!     Some(Err(e)) => eprintln!("{}", e),
+//!     None => {}
+//! }
+//! # }
+//! ```
+
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+
+use egui::Context;
+
+use crate::{loadpull, loadpull::LoadPull, touchstone, Network, SmithChartError, Trace};
+
+/// Contents of a file picked with [`FileLoader::open`]
+#[derive(Clone, Debug)]
+pub enum LoadedFile {
+    /// S-parameters of a Touchstone (.sNp) file
+    Touchstone { name: String, network: Network },
+    /// Load-pull measurements of a .csv file, see [`crate::loadpull`]
+    LoadPull { name: String, load_pull: LoadPull },
+}
+impl LoadedFile {
+    /// File name without the directory
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Touchstone { name, .. } | Self::LoadPull { name, .. } => name,
+        }
+    }
+
+    /// The reflection traces S11, S22, … of a Touchstone file, or the measured loads of
+    /// a load-pull file as a trace without frequencies
+    pub fn traces(&self) -> Vec<Trace> {
+        match self {
+            Self::Touchstone { name, network } => (0..network.ports)
+                .map(|i| network.trace(i, i).source(name.clone()))
+                .collect(),
+            Self::LoadPull { name, load_pull } => {
+                vec![Trace::new(name.clone(), load_pull.gamma.clone()).source(name.clone())]
+            }
+        }
+    }
+}
+
+/// File dialog for Touchstone and load-pull files, kept by the app across frames
+pub struct FileLoader {
+    /// Reference impedance of the impedance columns of load-pull files
+    z0: f32,
+    pending: Option<Receiver<Option<Result<LoadedFile, SmithChartError>>>>,
+}
+impl Default for FileLoader {
+    fn default() -> Self {
+        Self {
+            z0: 50.0,
+            pending: None,
+        }
+    }
+}
+impl FileLoader {
+    /// Reference impedance in ohms that `r`/`x` columns of load-pull files are
+    /// normalized to, 50 Ω by default
+    pub fn z0(mut self, z0: f32) -> Self {
+        self.z0 = z0;
+        self
+    }
+
+    /// Show the file dialog, unless it is already open. The chart is repainted once a
+    /// file is picked and parsed.
+    pub fn open(&mut self, ctx: &Context) {
+        if self.is_open() {
+            return;
+        }
+        let (sender, receiver) = channel();
+        self.pending = Some(receiver);
+        let (ctx, z0) = (ctx.clone(), self.z0);
+        let task = async move {
+            let file = rfd::AsyncFileDialog::new()
+                .add_filter("Touchstone", &["s1p", "s2p", "s3p", "s4p"])
+                .add_filter("Load-pull CSV", &["csv"])
+                .pick_file()
+                .await;
+            let loaded = match file {
+                Some(file) => Some(parse(file.file_name(), &file.read().await, z0)),
+                None => None,
+            };
+            // the app may have dropped the loader in the meantime
+            sender.send(loaded).ok();
+            ctx.request_repaint();
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(move || pollster::block_on(task));
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(task);
+    }
+
+    /// The dialog is open or the picked file is still being read
+    pub fn is_open(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// The loaded file, or why it couldn't be loaded, once in the frame after it is
+    /// done. `None` while nothing was picked.
+    pub fn poll(&mut self) -> Option<Result<LoadedFile, SmithChartError>> {
+        let loaded = match self.pending.as_ref()?.try_recv() {
+            Ok(loaded) => loaded,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => None,
+        };
+        self.pending = None;
+        loaded
+    }
+}
+
+/// Parse `bytes` by the extension of `name`: Touchstone files by their port count,
+/// .csv files as load-pull measurements
+fn parse(name: String, bytes: &[u8], z0: f32) -> Result<LoadedFile, SmithChartError> {
+    let text = String::from_utf8_lossy(bytes);
+    let extension = name.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("");
+    match touchstone::ports(extension) {
+        Some(ports) => Ok(LoadedFile::Touchstone {
+            network: touchstone::parse(&text, ports)?,
+            name,
+        }),
+        None if extension.eq_ignore_ascii_case("csv") => Ok(LoadedFile::LoadPull {
+            load_pull: loadpull::parse(&text, z0)?,
+            name,
+        }),
+        None => Err(SmithChartError::UnsupportedFormat(extension.to_string())),
+    }
+}
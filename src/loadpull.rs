@@ -0,0 +1,187 @@
+//! Reading load-pull measurements from CSV files.
+//!
+//! The first non-comment line names the columns, separated by commas. Files written
+//! with decimal commas separate the columns by semicolons instead.
+//! The load is given by one pair of columns:
+//!
+//! * `gamma_re`, `gamma_im`: reflection coefficient
+//! * `gamma_mag`, `gamma_ang`: reflection coefficient, angle in degrees
+//! * `r`, `x`: impedance in ohms
+//!
+//! Every other column is a measured quantity, e.g. `pout_dbm` or `pae_pct`.
+//! Lines starting with `#` or `!` are comments.
+//!
+//! ```text
+//! # 2.4 GHz load-pull
+//! gamma_mag, gamma_ang, pout_dbm, pae_pct
+//! 0.5, 170, 39.2, 61
+//! 0.6, 165, 39.6, 58
+//! ```
+
+use std::path::Path;
+
+use crate::{Complex, ContourSet, SmithChartError};
+
+/// Measured quantities at a set of loads
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoadPull {
+    /// Names of the measured quantities
+    pub columns: Vec<String>,
+    /// Load reflection coefficient of each point
+    pub gamma: Vec<Complex<f32>>,
+    /// Measured quantities of each point, in the order of `columns`
+    pub values: Vec<Vec<f32>>,
+}
+impl LoadPull {
+    /// Values of the quantity named `name` at each point
+    pub fn column(&self, name: &str) -> Option<Vec<f32>> {
+        let index = self.columns.iter().position(|column| column == name)?;
+        Some(self.values.iter().map(|values| values[index]).collect())
+    }
+
+    /// Contours of the quantity named `name`, interpolated between the measured loads
+    pub fn contours(&self, name: &str, unit: &str, levels: &[f32]) -> Option<ContourSet> {
+        let values = self.column(name)?;
+        let samples: Vec<_> = self.gamma.iter().copied().zip(values).collect();
+        Some(ContourSet::from_samples(name, unit, &samples, levels))
+    }
+}
+
+/// Read a load-pull CSV file, impedance columns are normalized to `z0`
+pub fn load(path: impl AsRef<Path>, z0: f32) -> Result<LoadPull, SmithChartError> {
+    parse(&std::fs::read_to_string(path)?, z0)
+}
+
+/// Parse load-pull CSV text, impedance columns are normalized to `z0`
+/// ```
+/// let data = egui_smith_chart::loadpull::parse("r, x, pout_dbm\n50, 0, 38\n25, 5, 40", 50.0)
+///     .unwrap();
+/// assert_eq!(data.columns, ["pout_dbm"]);
+/// assert_eq!(data.column("pout_dbm").unwrap(), [38.0, 40.0]);
+/// assert_eq!(data.gamma[0], egui_smith_chart::Complex::new(0.0, 0.0));
+/// ```
+pub fn parse(text: &str, z0: f32) -> Result<LoadPull, SmithChartError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'));
+    let header = lines.next().map(|(_, line)| line).unwrap_or_default();
+    // a semicolon separated header means the numbers have decimal commas
+    let delimiter = if header.contains(';') { ';' } else { ',' };
+    let split = |line: &str| -> Vec<String> {
+        line.split(delimiter)
+            .map(|field| field.trim().to_ascii_lowercase())
+            .collect()
+    };
+
+    let header = split(header);
+    let find = |name: &str| header.iter().position(|column| column == name);
+    let (load, a, b) = [
+        ("gamma_re", "gamma_im"),
+        ("gamma_mag", "gamma_ang"),
+        ("r", "x"),
+    ]
+    .into_iter()
+    .enumerate()
+    .find_map(|(load, (a, b))| Some((load, find(a)?, find(b)?)))
+    .ok_or(SmithChartError::MissingLoad)?;
+    let measured: Vec<usize> = (0..header.len()).filter(|&i| i != a && i != b).collect();
+
+    let mut load_pull = LoadPull {
+        columns: measured.iter().map(|&i| header[i].clone()).collect(),
+        gamma: Vec::new(),
+        values: Vec::new(),
+    };
+    for (line, text) in lines {
+        let fields = split(text);
+        if fields.len() != header.len() {
+            return Err(SmithChartError::ColumnCount { line });
+        }
+        let numbers = fields
+            .iter()
+            .map(|field| {
+                field
+                    .replace(',', ".")
+                    .parse::<f32>()
+                    .map_err(|_| SmithChartError::InvalidNumber {
+                        line,
+                        text: field.clone(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let gamma = match load {
+            0 => Complex::new(numbers[a], numbers[b]),
+            1 => Complex::from_polar(numbers[a], numbers[b].to_radians()),
+            _ => {
+                let z = Complex::new(numbers[a], numbers[b]) / z0;
+                (z - 1.0) / (z + 1.0)
+            }
+        };
+        load_pull.gamma.push(gamma);
+        load_pull
+            .values
+            .push(measured.iter().map(|&i| numbers[i]).collect());
+    }
+    if load_pull.gamma.is_empty() {
+        diagnostic!(warn, "load-pull: no data below the header");
+    }
+    diagnostic!(
+        debug,
+        "load-pull: read {} loads with {} quantities",
+        load_pull.gamma.len(),
+        load_pull.columns.len()
+    );
+    Ok(load_pull)
+}
+
+impl ContourSet {
+    /// Contours of scattered samples `(Γ, value)`, interpolated by inverse distance
+    /// weighting. Away from the samples the field is left empty.
+    pub fn from_samples(
+        name: impl Into<String>,
+        unit: impl Into<String>,
+        samples: &[(Complex<f32>, f32)],
+        levels: &[f32],
+    ) -> Self {
+        // leave the field empty further than a few sample spacings from any sample
+        let spacing = samples
+            .iter()
+            .map(|(a, _)| {
+                samples
+                    .iter()
+                    .filter(|(b, _)| b != a)
+                    .map(|(b, _)| (a - b).norm())
+                    .fold(f32::INFINITY, f32::min)
+            })
+            .filter(|d| d.is_finite())
+            .sum::<f32>()
+            / samples.len().max(1) as f32;
+        let reach = 2.0 * spacing.max(1e-3);
+
+        Self::from_fn(
+            name,
+            unit,
+            |gamma| {
+                let mut nearest = f32::INFINITY;
+                let (mut sum, mut weights) = (0.0, 0.0);
+                for (at, value) in samples {
+                    let distance = (gamma - at).norm();
+                    nearest = nearest.min(distance);
+                    if distance < 1e-6 {
+                        return *value;
+                    }
+                    let weight = 1.0 / (distance * distance);
+                    sum += weight * value;
+                    weights += weight;
+                }
+                if nearest > reach {
+                    f32::NAN
+                } else {
+                    sum / weights
+                }
+            },
+            levels,
+        )
+    }
+}
@@ -0,0 +1,196 @@
+//! Translatable built-in strings.
+
+/// Built-in strings painted by the chart.
+///
+/// The default is English, replace the fields to translate readouts and labels:
+/// ```
+/// let locale = egui_smith_chart::Locale {
+///     toward_generator: "vers le générateur".to_string(),
+///     toward_load: "vers la charge".to_string(),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Locale {
+    /// Label of the wavelength scale running clockwise
+    pub toward_generator: String,
+    /// Label of the wavelength scale running counter-clockwise
+    pub toward_load: String,
+
+    /// Name of the impedance grid
+    pub impedance: String,
+    /// Name of the admittance grid
+    pub admittance: String,
+
+    /// Readout prefix of the characteristic impedance
+    pub characteristic_impedance: String,
+    /// Readout prefix of normalized impedance
+    pub normalized_impedance: String,
+    /// Readout prefix of impedance
+    pub absolute_impedance: String,
+    /// Readout prefix of normalized resistance
+    pub normalized_resistance: String,
+    /// Readout prefix of resistance
+    pub resistance: String,
+    /// Readout prefix of normalized reactance
+    pub normalized_reactance: String,
+    /// Readout prefix of reactance
+    pub reactance: String,
+    /// Readout prefix of normalized conductance
+    pub normalized_conductance: String,
+    /// Readout prefix of conductance
+    pub conductance: String,
+    /// Readout prefix of normalized susceptance
+    pub normalized_susceptance: String,
+    /// Readout prefix of susceptance
+    pub susceptance: String,
+    /// Readout prefix of the reflection coefficient
+    pub reflection_coefficient: String,
+    /// Readout prefix of the voltage standing wave ratio
+    pub vswr: String,
+    /// Readout prefix of the return loss
+    pub return_loss: String,
+    /// Readout prefix of the transmission coefficient
+    pub transmission_coefficient: String,
+    /// Readout prefix of the stub matching assist
+    pub stub: String,
+    /// Name of an open-circuited stub
+    pub open_stub: String,
+    /// Name of a short-circuited stub
+    pub short_stub: String,
+    /// Readout prefix of the deviation from a reference trace
+    pub deviation: String,
+    /// Prompt shown while a file is dragged over the chart, followed by its name
+    pub drop_to_load: String,
+
+    /// Placeholder of [`crate::TraceDetails`] without a trace
+    pub no_trace_selected: String,
+    /// Trace details label of the trace name
+    pub trace_name: String,
+    /// Trace details label of the number of samples
+    pub samples: String,
+    /// Prefix of the index of a sample without frequency
+    pub sample: String,
+    /// Trace details label of the sweep range
+    pub frequency: String,
+    /// Trace details label of the lowest VSWR
+    pub min_vswr: String,
+    /// Trace details label of the highest VSWR
+    pub max_vswr: String,
+    /// Trace details label of the sample with the lowest VSWR
+    pub best_match: String,
+    /// Reference impedance of traces normalized to the chart
+    pub chart_z0: String,
+    /// Trace details label of the file a trace was loaded from
+    pub source: String,
+    /// Trace details label of the ports of an S-parameter
+    pub port: String,
+    /// Context menu entry switching readouts to polar form
+    pub polar_readouts: String,
+
+    /// Settings popup heading of the grid options
+    pub settings_grid: String,
+    /// Settings popup label of the grid plane
+    pub plane: String,
+    /// Settings popup label of the grid density
+    pub density: String,
+    /// Grid density chosen from the chart size
+    pub density_by_size: String,
+    /// Settings popup option adding grid circles when zoomed in
+    pub adaptive_grid: String,
+    /// Settings popup option labelling the grid circles
+    pub grid_labels: String,
+    /// Settings popup option of the ∠Γ ticks around the rim
+    pub angle_ticks: String,
+    /// Settings popup heading of the layer switches
+    pub settings_overlays: String,
+    /// Settings popup option showing the legend
+    pub legend: String,
+    /// Settings popup heading of the style options
+    pub settings_style: String,
+    /// Settings popup label of the style presets
+    pub preset: String,
+    /// Name of [`crate::SmithChartStyle::default`]
+    pub preset_default: String,
+    /// Name of [`crate::SmithChartStyle::classic_paper`]
+    pub preset_paper: String,
+    /// Name of [`crate::SmithChartStyle::dark`]
+    pub preset_dark: String,
+    /// Name of [`crate::SmithChartStyle::print`]
+    pub preset_print: String,
+    /// Settings popup label of the trace palette
+    pub palette: String,
+    /// Settings popup label of the grid line width
+    pub grid_width: String,
+    /// Settings popup label of the emphasized grid line width
+    pub strong_grid_width: String,
+    /// Settings popup label of the font size
+    pub font_size: String,
+    /// Settings popup label of the opacity of unselected traces
+    pub unselected_opacity: String,
+    /// Settings popup label of the chart background
+    pub background: String,
+}
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            toward_generator: "wavelengths toward generator".to_string(),
+            toward_load: "wavelengths toward load".to_string(),
+            impedance: "impedance".to_string(),
+            admittance: "admittance".to_string(),
+            characteristic_impedance: "Z0".to_string(),
+            normalized_impedance: "z".to_string(),
+            absolute_impedance: "Z".to_string(),
+            normalized_resistance: "r".to_string(),
+            resistance: "R".to_string(),
+            normalized_reactance: "x".to_string(),
+            reactance: "X".to_string(),
+            normalized_conductance: "g".to_string(),
+            conductance: "G".to_string(),
+            normalized_susceptance: "b".to_string(),
+            susceptance: "B".to_string(),
+            reflection_coefficient: "Γ".to_string(),
+            vswr: "VSWR".to_string(),
+            return_loss: "RL".to_string(),
+            transmission_coefficient: "T".to_string(),
+            stub: "stub".to_string(),
+            open_stub: "open".to_string(),
+            short_stub: "short".to_string(),
+            deviation: "|ΔΓ|".to_string(),
+            drop_to_load: "drop to load".to_string(),
+            no_trace_selected: "no trace selected".to_string(),
+            trace_name: "name".to_string(),
+            samples: "samples".to_string(),
+            sample: "sample".to_string(),
+            frequency: "frequency".to_string(),
+            min_vswr: "min VSWR".to_string(),
+            max_vswr: "max VSWR".to_string(),
+            best_match: "best match".to_string(),
+            chart_z0: "chart Z0".to_string(),
+            source: "source".to_string(),
+            port: "port".to_string(),
+            polar_readouts: "Polar readouts".to_string(),
+            settings_grid: "Grid".to_string(),
+            plane: "plane".to_string(),
+            density: "density".to_string(),
+            density_by_size: "by size".to_string(),
+            adaptive_grid: "finer grid when zoomed".to_string(),
+            grid_labels: "grid labels".to_string(),
+            angle_ticks: "angle ticks".to_string(),
+            settings_overlays: "Overlays".to_string(),
+            legend: "legend".to_string(),
+            settings_style: "Style".to_string(),
+            preset: "preset".to_string(),
+            preset_default: "default".to_string(),
+            preset_paper: "paper".to_string(),
+            preset_dark: "dark".to_string(),
+            preset_print: "print".to_string(),
+            palette: "palette".to_string(),
+            grid_width: "grid width".to_string(),
+            strong_grid_width: "strong grid width".to_string(),
+            font_size: "font size".to_string(),
+            unselected_opacity: "unselected opacity".to_string(),
+            background: "background".to_string(),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Transmission magnitude plot to show next to the chart.
+
+use egui::plot::{Legend, Line, Plot, PlotPoints, VLine};
+use egui::{Response, Ui};
+
+use crate::{format::format_eng, Network};
+
+/// |S<sub>ij</sub>| in dB over frequency of the transmission parameters (i ≠ j)
+/// of a [`Network`], with an optional frequency cursor linking it to the chart
+pub struct MagnitudePlot<'a> {
+    id_source: egui::Id,
+    network: &'a Network,
+    frequency: Option<f64>,
+    height: f32,
+}
+impl<'a> MagnitudePlot<'a> {
+    pub fn new(id_source: impl std::hash::Hash, network: &'a Network) -> Self {
+        Self {
+            id_source: egui::Id::new(id_source),
+            network,
+            frequency: None,
+            height: 160.0,
+        }
+    }
+
+    /// Frequency in Hz marked with a vertical line, e.g. of the hovered trace sample
+    pub fn frequency(mut self, frequency: Option<f64>) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn show(self, ui: &mut Ui) -> Response {
+        let network = self.network;
+        Plot::new(self.id_source)
+            .height(self.height)
+            .legend(Legend::default())
+            .x_axis_formatter(|x, _| format_eng(x, "Hz", 3))
+            .show(ui, |plot_ui| {
+                for i in 0..network.ports {
+                    for j in (0..network.ports).filter(|&j| j != i) {
+                        let points: PlotPoints = network
+                            .frequencies
+                            .iter()
+                            .zip(network.parameter(i, j))
+                            .map(|(&f, s)| [f, 20.0 * (s.norm() as f64).log10()])
+                            // no transmission at all is −∞ dB, off the plot
+                            .filter(|[_, db]| db.is_finite())
+                            .collect();
+                        plot_ui.line(Line::new(points).name(format!("S{}{}", i + 1, j + 1)));
+                    }
+                }
+                if let Some(frequency) = self.frequency {
+                    plot_ui.vline(VLine::new(frequency));
+                }
+            })
+            .response
+    }
+}
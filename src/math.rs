@@ -0,0 +1,130 @@
+//! Operations deriving new traces from existing ones.
+
+use std::f64::consts::PI;
+
+use crate::{Complex, SmithChartError, Trace};
+
+impl Trace {
+    /// Complex conjugate of every sample, e.g. the conjugate match of a load
+    pub fn conjugate(self) -> Self {
+        self.map_gamma(|gamma| gamma.conj())
+    }
+
+    /// Move the reference plane `wavelengths` toward the generator along lossless line,
+    /// the same rotation at every frequency
+    pub fn rotate(self, wavelengths: f32) -> Self {
+        let turn = Complex::from_polar(1.0, -4.0 * std::f32::consts::PI * wavelengths);
+        self.map_gamma(|gamma| gamma * turn)
+    }
+
+    /// Move the reference plane toward the generator along lossless line with a
+    /// one-way delay of `seconds`, rotating each sample by its frequency.
+    /// Traces without frequencies are returned unchanged.
+    pub fn delay(mut self, seconds: f64) -> Self {
+        if self.is_swept() {
+            for (gamma, f) in self.gamma.iter_mut().zip(&self.frequencies) {
+                *gamma *= Complex::from_polar(1.0, (-4.0 * PI * f * seconds) as f32);
+            }
+        }
+        self
+    }
+
+    /// Impedance to admittance: the same normalized values on the admittance grid
+    pub fn invert(self) -> Self {
+        self.map_gamma(|gamma| -gamma)
+    }
+
+    /// Combine with `other` sample by sample, at this trace's sweep coordinates with
+    /// `other` interpolated. Samples outside the sweep of `other` are dropped.
+    pub fn zip_with(
+        &self,
+        other: &Trace,
+        f: impl Fn(Complex<f32>, Complex<f32>) -> Complex<f32>,
+    ) -> Trace {
+        let mut result = Trace::new(self.name.clone(), Vec::new());
+        result.z0 = self.z0;
+        result.markers = self.markers.clone();
+        for (i, gamma) in self.gamma.iter().enumerate() {
+            let sweep = self.sweep_at(i);
+            if let Some(other) = other.gamma_at(sweep) {
+                result.gamma.push(f(*gamma, other));
+                if self.is_swept() {
+                    result.frequencies.push(sweep);
+                }
+            }
+        }
+        result
+    }
+
+    /// This load in series with `other`. Both must be normalized to the same reference,
+    /// see [`Trace::z0`].
+    pub fn series(&self, other: &Trace) -> Result<Trace, SmithChartError> {
+        self.same_reference(other)?;
+        Ok(self.zip_with(other, |a, b| z_to_gamma(gamma_to_z(a) + gamma_to_z(b))))
+    }
+
+    /// This load in parallel with `other`. Both must be normalized to the same reference,
+    /// see [`Trace::z0`].
+    pub fn shunt(&self, other: &Trace) -> Result<Trace, SmithChartError> {
+        self.same_reference(other)?;
+        // admittance is the impedance of the inverted reflection coefficient
+        Ok(self.zip_with(other, |a, b| -z_to_gamma(gamma_to_z(-a) + gamma_to_z(-b))))
+    }
+
+    fn same_reference(&self, other: &Trace) -> Result<(), SmithChartError> {
+        if self.z0 == other.z0 {
+            Ok(())
+        } else {
+            Err(SmithChartError::ReferenceMismatch(
+                self.name.clone(),
+                other.name.clone(),
+            ))
+        }
+    }
+
+    fn map_gamma(mut self, f: impl Fn(Complex<f32>) -> Complex<f32>) -> Self {
+        for gamma in &mut self.gamma {
+            *gamma = f(*gamma);
+        }
+        self
+    }
+}
+
+/// Distance from Γ = 1 within which a reflection coefficient is an open circuit
+const OPEN: f32 = 1e-6;
+
+/// Normalized impedance of `gamma`, infinite rather than NaN at the open circuit
+pub(crate) fn gamma_to_z(gamma: Complex<f32>) -> Complex<f32> {
+    if (1.0 - gamma).norm() < OPEN {
+        return Complex::new(f32::INFINITY, 0.0);
+    }
+    (1.0 + gamma) / (1.0 - gamma)
+}
+
+/// Reflection coefficient of the normalized impedance `z`, 1 for infinite impedances
+pub(crate) fn z_to_gamma(z: Complex<f32>) -> Complex<f32> {
+    if z.is_infinite() {
+        return Complex::new(1.0, 0.0);
+    }
+    (z - 1.0) / (z + 1.0)
+}
+
+/// `z` times `factor`, keeping an infinite `z` infinite where complex multiplication
+/// gives NaN
+pub(crate) fn scale(z: Complex<f32>, factor: Complex<f32>) -> Complex<f32> {
+    if z.is_infinite() {
+        return Complex::new(f32::INFINITY, 0.0);
+    }
+    z * factor
+}
+
+/// Admittance of the impedance `z`, or the other way around, with 0 and ∞ swapped
+pub(crate) fn invert(z: Complex<f32>) -> Complex<f32> {
+    if z.is_infinite() {
+        Complex::new(0.0, 0.0)
+    } else if z.norm() == 0.0 {
+        Complex::new(f32::INFINITY, 0.0)
+    } else {
+        1.0 / z
+    }
+}
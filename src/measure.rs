@@ -0,0 +1,75 @@
+//! Geometric measurements between points of the chart.
+
+use crate::Complex;
+
+/// Rotation between two reflection coefficients around the chart center
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rotation {
+    /// Clockwise angle in degrees, 0 to 360
+    pub degrees: f32,
+    /// Length of lossless line toward the generator that rotates the first point
+    /// onto the angle of the second, in wavelengths, 0 to 0.5
+    pub wavelengths: f32,
+}
+
+/// Clockwise rotation from `from` to `to`, i.e. the electrical length of line
+/// between the two reference planes.
+/// ```
+/// # use egui_smith_chart::measure::rotation;
+/// # use egui_smith_chart::Complex;
+/// // open to short is a quarter wave
+/// let quarter = rotation(Complex::new(1.0, 0.0), Complex::new(-1.0, 0.0));
+/// assert!((quarter.wavelengths - 0.25).abs() < 1e-6);
+/// ```
+pub fn rotation(from: Complex<f32>, to: Complex<f32>) -> Rotation {
+    let degrees = (from.arg() - to.arg()).to_degrees().rem_euclid(360.0);
+    Rotation {
+        degrees,
+        wavelengths: degrees / 720.0,
+    }
+}
+
+/// Position of `gamma` on the "wavelengths toward generator" scale printed around
+/// the chart, 0 at the short circuit and growing clockwise to 0.5.
+/// ```
+/// # use egui_smith_chart::measure::wavelengths_toward_generator;
+/// # use egui_smith_chart::Complex;
+/// let open = wavelengths_toward_generator(Complex::new(1.0, 0.0));
+/// assert!((open - 0.25).abs() < 1e-6);
+/// ```
+pub fn wavelengths_toward_generator(gamma: Complex<f32>) -> f32 {
+    rotation(Complex::new(-1.0, 0.0), gamma).wavelengths
+}
+
+/// Shunt stub cancelling the susceptance of a point on the unit-conductance circle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stub {
+    /// Normalized susceptance the stub must add
+    pub susceptance: f32,
+    /// Length of an open-circuited stub in wavelengths, 0 to 0.5
+    pub open: f32,
+    /// Length of a short-circuited stub in wavelengths, 0 to 0.5
+    pub short: f32,
+}
+
+/// Shunt stub that moves the normalized admittance `y` onto the real axis, which
+/// matches it once `y` has been brought onto the g = 1 circle.
+/// ```
+/// # use egui_smith_chart::measure::shunt_stub;
+/// # use egui_smith_chart::Complex;
+/// // a capacitive susceptance of 1 needs an inductive stub: an eighth-wave short
+/// let stub = shunt_stub(Complex::new(1.0, 1.0));
+/// assert!((stub.susceptance + 1.0).abs() < 1e-6);
+/// assert!((stub.short - 0.125).abs() < 1e-6);
+/// assert!((stub.open - 0.375).abs() < 1e-6);
+/// ```
+pub fn shunt_stub(y: Complex<f32>) -> Stub {
+    use std::f32::consts::{PI, TAU};
+    let susceptance = -y.im;
+    Stub {
+        susceptance,
+        // open stub: j tan(βl), short stub: -j cot(βl)
+        open: susceptance.atan().rem_euclid(PI) / TAU,
+        short: (-1.0f32).atan2(susceptance).rem_euclid(PI) / TAU,
+    }
+}
@@ -0,0 +1,185 @@
+//! Network parameters: conversion to scattering parameters, terminated two-ports
+//! and multi-port data.
+
+use crate::{Complex, SmithChartError, Trace, TraceGroup};
+
+/// 2×2 network parameters, `m[row][column]`, e.g. `m[1][0]` is S21
+pub type Matrix2 = [[Complex<f32>; 2]; 2];
+
+/// Kind of two-port parameters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Parameters {
+    /// Scattering parameters
+    S,
+    /// Impedance parameters in ohms
+    Z,
+    /// Admittance parameters in siemens
+    Y,
+    /// Chain (transmission) parameters
+    Abcd,
+}
+impl std::fmt::Display for Parameters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::S => "S",
+            Self::Z => "Z",
+            Self::Y => "Y",
+            Self::Abcd => "ABCD",
+        })
+    }
+}
+
+/// Scattering parameters of `m`, given as `kind` parameters, with both ports
+/// referenced to `z0`.
+/// ```
+/// # use egui_smith_chart::network::{to_s, Parameters};
+/// # use egui_smith_chart::Complex;
+/// // 50 Ω in series
+/// let one = Complex::new(1.0, 0.0);
+/// let zero = Complex::new(0.0, 0.0);
+/// let s = to_s(&[[one, Complex::new(50.0, 0.0)], [zero, one]], Parameters::Abcd, 50.0.into());
+/// assert!((s[0][0] - 1.0 / 3.0).norm() < 1e-6);
+/// ```
+pub fn to_s(m: &Matrix2, kind: Parameters, z0: Complex<f32>) -> Matrix2 {
+    let one = Complex::new(1.0, 0.0);
+    let two = Complex::new(2.0, 0.0);
+    match kind {
+        Parameters::S => *m,
+        Parameters::Z => {
+            let [[z11, z12], [z21, z22]] = *m;
+            let d = (z11 + z0) * (z22 + z0) - z12 * z21;
+            [
+                [
+                    ((z11 - z0) * (z22 + z0) - z12 * z21) / d,
+                    two * z12 * z0 / d,
+                ],
+                [
+                    two * z21 * z0 / d,
+                    ((z11 + z0) * (z22 - z0) - z12 * z21) / d,
+                ],
+            ]
+        }
+        Parameters::Y => {
+            let [[y11, y12], [y21, y22]] = *m;
+            let c = y12 * y21 * z0 * z0;
+            let d = (one + y11 * z0) * (one + y22 * z0) - c;
+            [
+                [
+                    ((one - y11 * z0) * (one + y22 * z0) + c) / d,
+                    -two * y12 * z0 / d,
+                ],
+                [
+                    -two * y21 * z0 / d,
+                    ((one + y11 * z0) * (one - y22 * z0) + c) / d,
+                ],
+            ]
+        }
+        Parameters::Abcd => {
+            let [[a, b], [c, d]] = *m;
+            let delta = a + b / z0 + c * z0 + d;
+            [
+                [
+                    (a + b / z0 - c * z0 - d) / delta,
+                    two * (a * d - b * c) / delta,
+                ],
+                [two / delta, (-a + b / z0 - c * z0 + d) / delta],
+            ]
+        }
+    }
+}
+
+/// Input reflection coefficient of two-port `s` with port 2 terminated in `gamma_load`
+pub fn gamma_in(s: &Matrix2, gamma_load: Complex<f32>) -> Complex<f32> {
+    s[0][0] + s[0][1] * s[1][0] * gamma_load / (1.0 - s[1][1] * gamma_load)
+}
+
+/// Output reflection coefficient of two-port `s` with port 1 driven from `gamma_source`
+pub fn gamma_out(s: &Matrix2, gamma_source: Complex<f32>) -> Complex<f32> {
+    s[1][1] + s[0][1] * s[1][0] * gamma_source / (1.0 - s[0][0] * gamma_source)
+}
+
+/// S-parameters of a multi-port over frequency, e.g. loaded with
+/// [`crate::touchstone::load`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Network {
+    /// Number of ports
+    pub ports: usize,
+
+    /// Reference impedance of all ports in ohms
+    pub z0: Complex<f32>,
+
+    /// Frequency of each point in Hz
+    pub frequencies: Vec<f64>,
+
+    /// S-parameters at each frequency, row-major: `s[k][i * ports + j]` is
+    /// S<sub>i+1,j+1</sub> at `frequencies[k]`
+    pub s: Vec<Vec<Complex<f32>>>,
+}
+impl Network {
+    /// S<sub>i+1,j+1</sub> over frequency, ports counted from zero
+    pub fn parameter(&self, i: usize, j: usize) -> Vec<Complex<f32>> {
+        self.s.iter().map(|s| s[i * self.ports + j]).collect()
+    }
+
+    /// Trace of S<sub>i+1,j+1</sub>, named e.g. "S21"
+    pub fn trace(&self, i: usize, j: usize) -> Trace {
+        Trace::new(format!("S{}{}", i + 1, j + 1), self.parameter(i, j))
+            .frequencies(self.frequencies.clone())
+            .z0(self.z0)
+            .port(i, j)
+    }
+
+    /// Group of the reflection traces S11, S22, … of all ports
+    pub fn group(&self, name: impl Into<String>) -> TraceGroup {
+        let traces = (0..self.ports).map(|i| self.trace(i, i)).collect();
+        TraceGroup::new(name, traces)
+    }
+
+    /// Two-port S-parameters at point `k`, `None` unless the network has two ports
+    pub fn two_port(&self, k: usize) -> Option<Matrix2> {
+        let s = self.s.get(k).filter(|_| self.ports == 2)?;
+        Some([[s[0], s[1]], [s[2], s[3]]])
+    }
+
+    /// Input reflection coefficient with port 2 terminated in `gamma_load(frequency)`,
+    /// an error unless the network has two ports
+    pub fn gamma_in(
+        &self,
+        name: impl Into<String>,
+        gamma_load: impl Fn(f64) -> Complex<f32>,
+    ) -> Result<Trace, SmithChartError> {
+        self.terminated(name, |s, f| gamma_in(s, gamma_load(f)))
+    }
+
+    /// Output reflection coefficient with port 1 driven from `gamma_source(frequency)`,
+    /// an error unless the network has two ports
+    pub fn gamma_out(
+        &self,
+        name: impl Into<String>,
+        gamma_source: impl Fn(f64) -> Complex<f32>,
+    ) -> Result<Trace, SmithChartError> {
+        self.terminated(name, |s, f| gamma_out(s, gamma_source(f)))
+    }
+
+    fn terminated(
+        &self,
+        name: impl Into<String>,
+        gamma: impl Fn(&Matrix2, f64) -> Complex<f32>,
+    ) -> Result<Trace, SmithChartError> {
+        if self.ports != 2 {
+            return Err(SmithChartError::PortCount {
+                expected: 2,
+                found: self.ports,
+            });
+        }
+        let gamma: Vec<_> = self
+            .frequencies
+            .iter()
+            .enumerate()
+            .filter_map(|(k, &f)| Some(gamma(&self.two_port(k)?, f)))
+            .collect();
+        Ok(Trace::new(name, gamma)
+            .frequencies(self.frequencies.clone())
+            .z0(self.z0))
+    }
+}
@@ -0,0 +1,79 @@
+//! Constant noise figure circles of two-port amplifiers.
+
+use egui::Color32;
+
+use crate::{Circle, Complex};
+
+/// Family of constant noise figure circles in steps above the minimum noise figure,
+/// from the noise parameters of a two-port
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoiseCircles {
+    /// Minimum noise figure in dB
+    pub fmin_db: f32,
+    /// Equivalent noise resistance in ohms
+    pub rn: f32,
+    /// Source reflection coefficient giving the minimum noise figure
+    pub gamma_opt: Complex<f32>,
+    /// Reference impedance of `gamma_opt` in ohms
+    pub z0: f32,
+    /// Noise figure difference between neighbouring circles in dB
+    pub step_db: f32,
+    /// Number of circles above the minimum
+    pub count: usize,
+    pub color: Color32,
+}
+impl NoiseCircles {
+    pub fn new(fmin_db: f32, rn: f32, gamma_opt: Complex<f32>) -> Self {
+        Self {
+            fmin_db,
+            rn,
+            gamma_opt,
+            z0: 50.0,
+            step_db: 0.5,
+            count: 4,
+            color: Color32::from_rgb(200, 90, 200),
+        }
+    }
+
+    pub fn z0(mut self, z0: f32) -> Self {
+        self.z0 = z0;
+        self
+    }
+
+    pub fn step(mut self, step_db: f32) -> Self {
+        self.step_db = step_db;
+        self
+    }
+
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Circle of source terminations giving noise figure `nf_db`,
+    /// `None` below the minimum
+    pub fn circle(&self, nf_db: f32) -> Option<Circle> {
+        let f = 10f32.powf(nf_db / 10.0);
+        let fmin = 10f32.powf(self.fmin_db / 10.0);
+        let n = (f - fmin) / (4.0 * self.rn / self.z0) * (1.0 + self.gamma_opt).norm_sqr();
+        (n >= 0.0).then(|| Circle {
+            center: self.gamma_opt / (n + 1.0),
+            radius: (n * (n + 1.0 - self.gamma_opt.norm_sqr())).sqrt() / (n + 1.0),
+        })
+    }
+
+    /// Circles from `step_db` above the minimum upwards, with their noise figure in dB
+    pub fn circles(&self) -> Vec<(f32, Circle)> {
+        (1..=self.count)
+            .filter_map(|n| {
+                let nf = self.fmin_db + n as f32 * self.step_db;
+                Some((nf, self.circle(nf)?))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,311 @@
+//! Single-page PDF documents of painted shapes, see [`crate::SmithChart::export_pdf`].
+//!
+//! Strokes and fills stay vectors and text stays text, set in the PDF base fonts
+//! Helvetica and Symbol which every reader provides, so nothing needs embedding.
+//! Characters neither font has are written as '?'.
+
+use std::fmt::Write;
+
+use egui::epaint::{ClippedShape, TextShape};
+use egui::{Color32, Pos2, Rect, Stroke};
+
+use crate::vector::{self, num, Backend};
+
+/// Magic number of cubic Bézier quarter circles
+const KAPPA: f32 = 0.552_284_8;
+
+/// PDF document of `shapes` on a page covering `page` in points
+pub(crate) fn document(shapes: &[ClippedShape], page: Rect) -> Vec<u8> {
+    let mut content = Content::default();
+    // y grows downwards like in egui
+    writeln!(
+        content.ops,
+        "1 0 0 -1 {} {} cm 1 J 1 j",
+        num(-page.min.x),
+        num(page.max.y)
+    )
+    .ok();
+    vector::walk(&mut content, shapes, page);
+
+    let states: String = content
+        .alphas
+        .iter()
+        .map(|a| {
+            format!(
+                "/A{} << /ca {} /CA {} >> ",
+                a,
+                num(*a as f32 / 255.0),
+                num(*a as f32 / 255.0)
+            )
+        })
+        .collect();
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents 4 0 R \
+             /Resources << /Font << /F1 5 0 R /F2 6 0 R >> /ExtGState << {}>> >> >>",
+            num(page.width()),
+            num(page.height()),
+            states
+        ),
+        format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.ops.len(),
+            content.ops
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Symbol >>".to_string(),
+    ];
+
+    let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", i + 1).bytes());
+        pdf.extend(object.bytes());
+        pdf.extend(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        writeln!(trailer, "{:010} 00000 n ", offset).ok();
+    }
+    write!(
+        trailer,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    )
+    .ok();
+    pdf.extend(trailer.bytes());
+    pdf
+}
+
+/// Content stream of the page and the opacities it uses
+#[derive(Default)]
+struct Content {
+    ops: String,
+    alphas: Vec<u8>,
+}
+impl Backend for Content {
+    fn path(&mut self, points: &[Pos2], closed: bool, fill: Color32, stroke: Stroke) {
+        for (i, p) in points.iter().enumerate() {
+            let op = if i == 0 { "m" } else { "l" };
+            writeln!(self.ops, "{} {} {}", num(p.x), num(p.y), op).ok();
+        }
+        if closed {
+            self.ops.push_str("h\n");
+        }
+        self.paint(fill, stroke);
+    }
+
+    fn circle(&mut self, c: Pos2, r: f32, fill: Color32, stroke: Stroke) {
+        let k = r * KAPPA;
+        writeln!(self.ops, "{} {} m", num(c.x + r), num(c.y)).ok();
+        for [(x1, y1), (x2, y2), (x, y)] in [
+            [(r, k), (k, r), (0.0, r)],
+            [(-k, r), (-r, k), (-r, 0.0)],
+            [(-r, -k), (-k, -r), (0.0, -r)],
+            [(k, -r), (r, -k), (r, 0.0)],
+        ] {
+            writeln!(
+                self.ops,
+                "{} {} {} {} {} {} c",
+                num(c.x + x1),
+                num(c.y + y1),
+                num(c.x + x2),
+                num(c.y + y2),
+                num(c.x + x),
+                num(c.y + y)
+            )
+            .ok();
+        }
+        self.paint(fill, stroke);
+    }
+
+    fn rect(&mut self, r: Rect, _rounding: f32, fill: Color32, stroke: Stroke) {
+        writeln!(
+            self.ops,
+            "{} {} {} {} re",
+            num(r.min.x),
+            num(r.min.y),
+            num(r.width()),
+            num(r.height())
+        )
+        .ok();
+        self.paint(fill, stroke);
+    }
+
+    fn cubic(&mut self, [p0, p1, p2, p3]: [Pos2; 4], closed: bool, fill: Color32, stroke: Stroke) {
+        writeln!(self.ops, "{} {} m", num(p0.x), num(p0.y)).ok();
+        writeln!(
+            self.ops,
+            "{} {} {} {} {} {} c",
+            num(p1.x),
+            num(p1.y),
+            num(p2.x),
+            num(p2.y),
+            num(p3.x),
+            num(p3.y)
+        )
+        .ok();
+        if closed {
+            self.ops.push_str("h\n");
+        }
+        self.paint(fill, stroke);
+    }
+
+    /// Each row as runs of Helvetica and Symbol characters, placed where egui laid out
+    /// their first glyph
+    fn text(&mut self, text: &TextShape) {
+        let galley = &text.galley;
+        let (sin, cos) = text.angle.sin_cos();
+        for row in &galley.rows {
+            let mut runs: Vec<(Font, usize, Vec<u8>)> = Vec::new();
+            for (i, glyph) in row.glyphs.iter().enumerate() {
+                let (font, byte) = encode(glyph.chr);
+                match runs.last_mut() {
+                    Some((last, _, bytes)) if *last == font => bytes.push(byte),
+                    _ => runs.push((font, i, vec![byte])),
+                }
+            }
+            for (font, first, bytes) in runs {
+                let glyph = &row.glyphs[first];
+                let format = &galley.job.sections[glyph.section_index as usize].format;
+                let size = format.font_id.size;
+                self.color(text.override_text_color.unwrap_or(format.color), "rg");
+                // baseline of the row, rotated about the text position
+                let offset = glyph.pos.to_vec2()
+                    + egui::vec2(0.0, row.rect.min.y - glyph.pos.y + 0.8 * size);
+                let x = text.pos.x + offset.x * cos - offset.y * sin;
+                let y = text.pos.y + offset.x * sin + offset.y * cos;
+                let literal: String = bytes
+                    .iter()
+                    .map(|&byte| match byte {
+                        b'(' | b')' | b'\\' => format!("\\{}", byte as char),
+                        0x20..=0x7e => (byte as char).to_string(),
+                        _ => format!("\\{:03o}", byte),
+                    })
+                    .collect();
+                writeln!(
+                    self.ops,
+                    "BT /{} {} Tf {} {} {} {} {} {} Tm ({}) Tj ET",
+                    font.resource(),
+                    num(size),
+                    num(cos),
+                    num(sin),
+                    num(sin),
+                    num(-cos),
+                    num(x),
+                    num(y),
+                    literal
+                )
+                .ok();
+            }
+        }
+    }
+
+    fn begin_clip(&mut self, clip: Rect) {
+        writeln!(
+            self.ops,
+            "q {} {} {} {} re W n",
+            num(clip.min.x),
+            num(clip.min.y),
+            num(clip.width()),
+            num(clip.height())
+        )
+        .ok();
+    }
+
+    fn end_clip(&mut self) {
+        self.ops.push_str("Q\n");
+    }
+}
+impl Content {
+    /// Fill and stroke the current path, skipping invisible parts
+    fn paint(&mut self, fill: Color32, stroke: Stroke) {
+        let filled = fill.a() > 0;
+        let stroked = stroke.width > 0.0 && stroke.color.a() > 0;
+        if filled {
+            self.color(fill, "rg");
+        }
+        if stroked {
+            self.color(stroke.color, "RG");
+            writeln!(self.ops, "{} w", num(stroke.width)).ok();
+        }
+        let op = match (filled, stroked) {
+            (true, true) => "B",
+            (true, false) => "f",
+            (false, true) => "S",
+            (false, false) => "n",
+        };
+        self.ops.push_str(op);
+        self.ops.push('\n');
+    }
+
+    /// Set the fill (`rg`) or stroke (`RG`) color with its opacity
+    fn color(&mut self, color: Color32, op: &str) {
+        let [r, g, b, a] = color.to_srgba_unmultiplied();
+        if !self.alphas.contains(&a) {
+            self.alphas.push(a);
+        }
+        writeln!(
+            self.ops,
+            "/A{} gs {} {} {} {}",
+            a,
+            num(r as f32 / 255.0),
+            num(g as f32 / 255.0),
+            num(b as f32 / 255.0),
+            op
+        )
+        .ok();
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Font {
+    Helvetica,
+    Symbol,
+}
+impl Font {
+    fn resource(&self) -> &'static str {
+        match self {
+            Self::Helvetica => "F1",
+            Self::Symbol => "F2",
+        }
+    }
+}
+
+/// Font and code of `c`: WinAnsi for Latin-1, the Symbol font for Greek letters and
+/// the math signs the chart uses
+fn encode(c: char) -> (Font, u8) {
+    let symbol = match c {
+        'Γ' => Some(b'G'),
+        'Δ' => Some(b'D'),
+        'Ω' => Some(b'W'),
+        'θ' => Some(b'q'),
+        'λ' => Some(b'l'),
+        'π' => Some(b'p'),
+        '−' => Some(b'-'),
+        '∠' => Some(0xd0),
+        '∞' => Some(0xa5),
+        '→' => Some(0xae),
+        '≤' => Some(0xa3),
+        '≥' => Some(0xb3),
+        '≈' => Some(0xbb),
+        _ => None,
+    };
+    match (symbol, c as u32) {
+        (Some(code), _) => (Font::Symbol, code),
+        (None, code @ (0x20..=0x7e | 0xa0..=0xff)) => (Font::Helvetica, code as u8),
+        (None, _) => match c {
+            '–' => (Font::Helvetica, 0x96),
+            '—' => (Font::Helvetica, 0x97),
+            '•' => (Font::Helvetica, 0x95),
+            '…' => (Font::Helvetica, 0x85),
+            _ => (Font::Helvetica, b'?'),
+        },
+    }
+}
@@ -0,0 +1,58 @@
+//! Button editing an impedance with a Smith chart popup.
+
+use egui::{Key, Order, Response, Ui};
+
+use crate::{Complex, NumberFormat, SmithChart, Trace};
+
+/// Reference impedance of [`impedance_picker`]
+const Z0: f32 = 50.0;
+
+/// Button showing `impedance` in ohms which pops up a Smith chart to click or drag a
+/// new value on, like egui's color picker buttons. Values are referenced to 50 Ω.
+/// ```
+/// # fn ui(ui: &mut egui::Ui, load: &mut egui_smith_chart::Complex<f32>) {
+/// if egui_smith_chart::impedance_picker(ui, load).changed() {
+///     println!("new load {}", load);
+/// }
+/// # }
+/// ```
+pub fn impedance_picker(ui: &mut Ui, impedance: &mut Complex<f32>) -> Response {
+    let text = NumberFormat::default().complex(*impedance, "Ω");
+    let mut button = ui.button(text);
+    let popup_id = button.id.with("smith_chart_popup");
+    if button.clicked() {
+        ui.memory().toggle_popup(popup_id);
+    }
+
+    if ui.memory().is_popup_open(popup_id) {
+        let z0 = Complex::new(Z0, 0.0);
+        let gamma = (*impedance - z0) / (*impedance + z0);
+        let area = egui::Area::new(popup_id)
+            .order(Order::Foreground)
+            .fixed_pos(button.rect.left_bottom())
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    let chart = SmithChart::new(popup_id)
+                        .size(240.0)
+                        .trace(Trace::new("value", vec![gamma]))
+                        .show(ui);
+                    let pointer = chart
+                        .response
+                        .interact_pointer_pos()
+                        .filter(|_| chart.response.is_pointer_button_down_on())
+                        .map(|pos| chart.transform.screen_to_gamma(pos))
+                        .filter(|gamma| gamma.norm() < 1.0);
+                    if let Some(gamma) = pointer {
+                        *impedance = z0 * (1.0 + gamma) / (1.0 - gamma);
+                        button.mark_changed();
+                    }
+                });
+            })
+            .response;
+
+        if !button.clicked() && (ui.input().key_pressed(Key::Escape) || area.clicked_elsewhere()) {
+            ui.memory().close_popup();
+        }
+    }
+    button
+}
@@ -0,0 +1,117 @@
+//! Input points accepted by the plotting APIs.
+
+use std::ops::RangeInclusive;
+
+use crate::Complex;
+
+/// Reflection coefficients to plot, analogous to egui's `PlotPoints`.
+///
+/// Anything taking `impl Into<SmithPoints>`, like [`crate::Line::new`] or
+/// [`crate::Trace::from_points`], accepts a `Vec` or a slice directly:
+/// ```
+/// # use egui_smith_chart::{Line, SmithPoints, Trace};
+/// # use egui_smith_chart::Complex;
+/// let gamma = vec![Complex::new(0.0, 0.0), Complex::new(0.5, 0.5)];
+/// let line = Line::new("borrowed", &gamma[..]);
+/// let trace = Trace::from_points("owned", gamma.clone());
+/// // a quarter-wave line shorted at 1 GHz, sampled from 0.5 to 1.5 GHz
+/// let stub = Trace::from_points(
+///     "stub",
+///     SmithPoints::from_fn(
+///         |f| Complex::from_polar(1.0, (std::f64::consts::PI * (1.0 - f / 1e9)) as f32),
+///         0.5e9..=1.5e9,
+///         101,
+///     ),
+/// );
+/// ```
+pub enum SmithPoints<'a> {
+    /// Points owned by the caller's value
+    Owned(Vec<Complex<f32>>),
+    /// Points borrowed without copying
+    Borrowed(&'a [Complex<f32>]),
+    /// Function of frequency in Hz sampled evenly over `frequencies`
+    Generator {
+        function: Box<dyn Fn(f64) -> Complex<f32> + 'a>,
+        frequencies: RangeInclusive<f64>,
+        points: usize,
+    },
+    /// Iterator recreated each time the points are read
+    Iter(Box<dyn Fn() -> Box<dyn Iterator<Item = Complex<f32>> + 'a> + 'a>),
+}
+impl<'a> SmithPoints<'a> {
+    /// Sample `function` at `points` frequencies spread evenly over `frequencies`
+    pub fn from_fn(
+        function: impl Fn(f64) -> Complex<f32> + 'a,
+        frequencies: RangeInclusive<f64>,
+        points: usize,
+    ) -> Self {
+        Self::Generator {
+            function: Box::new(function),
+            frequencies,
+            points,
+        }
+    }
+
+    /// Items of `gamma`, iterated each time the points are read
+    pub fn lazy<I>(gamma: I) -> Self
+    where
+        I: IntoIterator<Item = Complex<f32>> + Clone + 'a,
+    {
+        Self::Iter(Box::new(move || Box::new(gamma.clone().into_iter())))
+    }
+
+    /// Frequencies of the points, empty unless they come from a generator
+    pub fn frequencies(&self) -> Vec<f64> {
+        match self {
+            Self::Generator {
+                frequencies,
+                points,
+                ..
+            } => sample(frequencies, *points).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Iterate the points without copying borrowed or owned data
+    pub fn iter(&self) -> Box<dyn Iterator<Item = Complex<f32>> + '_> {
+        match self {
+            Self::Owned(gamma) => Box::new(gamma.iter().copied()),
+            Self::Borrowed(gamma) => Box::new(gamma.iter().copied()),
+            Self::Generator {
+                function,
+                frequencies,
+                points,
+            } => Box::new(sample(frequencies, *points).map(function)),
+            Self::Iter(gamma) => gamma(),
+        }
+    }
+
+    /// Owned points, copied unless they already are
+    pub fn into_vec(self) -> Vec<Complex<f32>> {
+        match self {
+            Self::Owned(gamma) => gamma,
+            points => points.iter().collect(),
+        }
+    }
+}
+impl From<Vec<Complex<f32>>> for SmithPoints<'_> {
+    fn from(gamma: Vec<Complex<f32>>) -> Self {
+        Self::Owned(gamma)
+    }
+}
+impl<'a> From<&'a [Complex<f32>]> for SmithPoints<'a> {
+    fn from(gamma: &'a [Complex<f32>]) -> Self {
+        Self::Borrowed(gamma)
+    }
+}
+impl<'a> From<&'a Vec<Complex<f32>>> for SmithPoints<'a> {
+    fn from(gamma: &'a Vec<Complex<f32>>) -> Self {
+        Self::Borrowed(gamma)
+    }
+}
+
+/// `points` values spread evenly over `range`, both ends included
+fn sample(range: &RangeInclusive<f64>, points: usize) -> impl Iterator<Item = f64> + '_ {
+    let step = (range.end() - range.start()) / points.saturating_sub(1).max(1) as f64;
+    (0..points).map(move |i| range.start() + i as f64 * step)
+}
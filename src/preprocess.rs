@@ -0,0 +1,138 @@
+//! Reduction of huge point sets, such as long sweeps or Monte Carlo clouds, to what the
+//! chart can draw every frame.
+//!
+//! Both functions are meant to run off the UI thread, e.g. when a file is loaded, so
+//! the chart only receives the reduced geometry. With the `rayon` feature they spread
+//! the work over all cores.
+
+use egui::{Color32, Mesh, Rect, Shape};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{ChartTransform, Complex};
+
+/// Points handled per task when running in parallel
+const CHUNK: usize = 4096;
+
+/// `points` without those closer than `tolerance` to the last point kept, which keeps
+/// the shape of a trace while dropping the samples that would land on the same pixel:
+/// ```
+/// # use egui_smith_chart::preprocess;
+/// # use egui_smith_chart::Complex;
+/// let sweep: Vec<_> = (0..100_000)
+///     .map(|i| Complex::from_polar(0.5, i as f32 * 1e-4))
+///     .collect();
+/// // about one point per pixel on a 400 points wide chart
+/// let decimated = preprocess::decimate(&sweep, 2.0 / 400.0);
+/// assert!(decimated.len() < 5_000);
+/// ```
+/// The first point of every chunk of the input is kept, the points at the seams are
+/// the only ones that may be closer.
+pub fn decimate(points: &[Complex<f32>], tolerance: f32) -> Vec<Complex<f32>> {
+    let decimate_chunk = |chunk: &[Complex<f32>]| {
+        let mut kept: Vec<Complex<f32>> = Vec::new();
+        for (i, &point) in chunk.iter().enumerate() {
+            let far = match kept.last() {
+                Some(last) => (point - last).norm() >= tolerance,
+                None => true,
+            };
+            // the very last point stays so the trace ends where it should
+            if far || i + 1 == chunk.len() {
+                kept.push(point);
+            }
+        }
+        kept
+    };
+    #[cfg(feature = "rayon")]
+    let chunks: Vec<_> = points.par_chunks(CHUNK).map(decimate_chunk).collect();
+    #[cfg(not(feature = "rayon"))]
+    let chunks: Vec<_> = points.chunks(CHUNK).map(decimate_chunk).collect();
+    let decimated = chunks.concat();
+    diagnostic!(
+        debug,
+        "decimated {} points to {}",
+        points.len(),
+        decimated.len()
+    );
+    decimated
+}
+
+/// Number of points in each cell of a square grid over the unit circle, drawn as a
+/// heat map where single points would be too many to draw
+#[derive(Clone, Debug, PartialEq)]
+pub struct Density {
+    /// Cells along each axis
+    pub resolution: usize,
+    /// Points per cell, row by row from Γ = -1 - j
+    pub counts: Vec<u32>,
+    /// Color of the densest cell, the others fade out with their count
+    pub color: Color32,
+}
+impl Density {
+    /// Bin `points` into `resolution` × `resolution` cells. Points outside the unit
+    /// square are left out.
+    pub fn new(points: &[Complex<f32>], resolution: usize) -> Self {
+        let cells = resolution * resolution;
+        let cell = |v: f32| ((v + 1.0) / 2.0 * resolution as f32).floor();
+        let inside = 0.0..resolution as f32;
+        let count = |chunk: &[Complex<f32>]| {
+            let mut counts = vec![0; cells];
+            for point in chunk {
+                let (column, row) = (cell(point.re), cell(point.im));
+                if inside.contains(&column) && inside.contains(&row) {
+                    counts[row as usize * resolution + column as usize] += 1;
+                }
+            }
+            counts
+        };
+        let add = |mut a: Vec<u32>, b: Vec<u32>| {
+            a.iter_mut().zip(b).for_each(|(a, b)| *a += b);
+            a
+        };
+        #[cfg(feature = "rayon")]
+        let counts = points
+            .par_chunks(CHUNK)
+            .map(count)
+            .reduce(|| vec![0; cells], add);
+        #[cfg(not(feature = "rayon"))]
+        let counts = points.chunks(CHUNK).map(count).fold(vec![0; cells], add);
+        let binned: u32 = counts.iter().sum();
+        if (binned as usize) < points.len() {
+            diagnostic!(
+                debug,
+                "density: {} points outside the unit square left out",
+                points.len() - binned as usize
+            );
+        }
+        Self {
+            resolution,
+            counts,
+            color: Color32::LIGHT_BLUE,
+        }
+    }
+
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Filled cells as a single mesh
+    pub(crate) fn shape(&self, transform: &ChartTransform) -> Shape {
+        let max = self.counts.iter().copied().max().unwrap_or(0).max(1) as f32;
+        let size = 2.0 / self.resolution as f32;
+        let mut mesh = Mesh::default();
+        for (i, &count) in self.counts.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let (row, column) = (i / self.resolution, i % self.resolution);
+            let min = Complex::new(column as f32 * size - 1.0, row as f32 * size - 1.0);
+            let rect = Rect::from_two_pos(
+                transform.gamma_to_screen(&min),
+                transform.gamma_to_screen(&(min + Complex::new(size, size))),
+            );
+            mesh.add_colored_rect(rect, self.color.linear_multiply(count as f32 / max));
+        }
+        Shape::mesh(mesh)
+    }
+}
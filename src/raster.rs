@@ -0,0 +1,145 @@
+//! Software rendering of tessellated shapes, for images of the chart outside the GPU
+//! frame, see [`crate::SmithChart::export_image`].
+
+use egui::epaint::{ClippedPrimitive, FontImage, ImageData, Primitive, Vertex};
+use egui::{Color32, ColorImage, Pos2, Rect, TextureId, TexturesDelta};
+
+/// Image `size` pixels large of `primitives` in points, blended like egui's own
+/// backends: premultiplied and in gamma space. Meshes sample the font atlas of
+/// `textures`, other textures are drawn as plain vertex colors.
+pub(crate) fn image(
+    primitives: &[ClippedPrimitive],
+    textures: &TexturesDelta,
+    size: [usize; 2],
+    pixels_per_point: f32,
+) -> ColorImage {
+    let font = font_atlas(textures);
+    let mut pixels = vec![[0.0f32; 4]; size[0] * size[1]];
+    let bounds = Rect::from_min_size(Pos2::ZERO, egui::vec2(size[0] as f32, size[1] as f32));
+    for primitive in primitives {
+        let mesh = match &primitive.primitive {
+            Primitive::Mesh(mesh) => mesh,
+            Primitive::Callback(_) => continue,
+        };
+        let clip = Rect::from_min_max(
+            (primitive.clip_rect.min.to_vec2() * pixels_per_point).to_pos2(),
+            (primitive.clip_rect.max.to_vec2() * pixels_per_point).to_pos2(),
+        )
+        .intersect(bounds);
+        let texture = font
+            .as_ref()
+            .filter(|_| mesh.texture_id == TextureId::default());
+        for triangle in mesh.indices.chunks_exact(3) {
+            let vertices = [0, 1, 2].map(|i| {
+                let mut vertex = mesh.vertices[triangle[i] as usize];
+                vertex.pos = (vertex.pos.to_vec2() * pixels_per_point).to_pos2();
+                vertex
+            });
+            fill_triangle(&mut pixels, size[0], clip, vertices, texture);
+        }
+    }
+    ColorImage {
+        size,
+        pixels: pixels
+            .iter()
+            .map(|[r, g, b, a]| {
+                let byte = |value: f32| (value * 255.0).round().clamp(0.0, 255.0) as u8;
+                Color32::from_rgba_premultiplied(byte(*r), byte(*g), byte(*b), byte(*a))
+            })
+            .collect(),
+    }
+}
+
+/// The font atlas after applying `textures`, `None` if it wasn't sent
+fn font_atlas(textures: &TexturesDelta) -> Option<FontImage> {
+    let mut atlas: Option<FontImage> = None;
+    for (id, delta) in &textures.set {
+        let image = match (&delta.image, *id == TextureId::default()) {
+            (ImageData::Font(image), true) => image,
+            _ => continue,
+        };
+        match (&mut atlas, delta.pos) {
+            (_, None) => atlas = Some(image.clone()),
+            (Some(atlas), Some([x, y])) => {
+                for row in 0..image.size[1] {
+                    let start = (y + row) * atlas.size[0] + x;
+                    atlas.pixels[start..start + image.size[0]].copy_from_slice(
+                        &image.pixels[row * image.size[0]..(row + 1) * image.size[0]],
+                    );
+                }
+            }
+            (None, Some(_)) => {}
+        }
+    }
+    atlas
+}
+
+/// Blend the triangle through the pixel centers inside `clip`, interpolating the
+/// vertex colors and texture coordinates
+fn fill_triangle(
+    pixels: &mut [[f32; 4]],
+    width: usize,
+    clip: Rect,
+    [a, b, c]: [Vertex; 3],
+    texture: Option<&FontImage>,
+) {
+    let area = edge(a.pos, b.pos, c.pos);
+    if area == 0.0 || clip.width() <= 0.0 || clip.height() <= 0.0 {
+        return;
+    }
+    let min = a.pos.min(b.pos).min(c.pos).max(clip.min);
+    let max = a.pos.max(b.pos).max(c.pos).min(clip.max);
+    for y in min.y.floor() as usize..max.y.ceil() as usize {
+        for x in min.x.floor() as usize..max.x.ceil() as usize {
+            let p = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+            if !clip.contains(p) {
+                continue;
+            }
+            // barycentric weights, all of the area's sign inside the triangle
+            let weights = [
+                edge(b.pos, c.pos, p) / area,
+                edge(c.pos, a.pos, p) / area,
+                edge(a.pos, b.pos, p) / area,
+            ];
+            if weights.iter().any(|&w| w < 0.0) {
+                continue;
+            }
+            let mut color = [0.0; 4];
+            for (vertex, weight) in [a, b, c].iter().zip(weights) {
+                for (channel, value) in color.iter_mut().zip(vertex.color.to_array()) {
+                    *channel += weight * value as f32 / 255.0;
+                }
+            }
+            if let Some(texture) = texture {
+                let uv = a.uv.to_vec2() * weights[0]
+                    + b.uv.to_vec2() * weights[1]
+                    + c.uv.to_vec2() * weights[2];
+                let coverage = sample(texture, uv.x, uv.y).powf(1.0 / 2.2);
+                color.iter_mut().for_each(|channel| *channel *= coverage);
+            }
+            let pixel = &mut pixels[y * width + x];
+            for (dst, src) in pixel.iter_mut().zip(color) {
+                *dst = src + *dst * (1.0 - color[3]);
+            }
+        }
+    }
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `p`
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Bilinearly filtered coverage of the atlas at normalized coordinates
+fn sample(texture: &FontImage, u: f32, v: f32) -> f32 {
+    let [width, height] = texture.size;
+    let x = (u * width as f32 - 0.5).clamp(0.0, (width - 1) as f32);
+    let y = (v * height as f32 - 0.5).clamp(0.0, (height - 1) as f32);
+    let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let (fx, fy) = (x.fract(), y.fract());
+    let at = |x: usize, y: usize| texture.pixels[y * width + x];
+    let top = at(x0, y0) * (1.0 - fx) + at(x1, y0) * fx;
+    let bottom = at(x0, y1) * (1.0 - fx) + at(x1, y1) * fx;
+    top * (1.0 - fy) + bottom * fy
+}
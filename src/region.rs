@@ -0,0 +1,147 @@
+//! Shaded areas of the chart, e.g. specification limits.
+
+use std::ops::RangeInclusive;
+
+use egui::{Color32, Stroke};
+
+use crate::{ChartTransform, Complex, SmithPoints, Trace};
+
+/// Filled area given by its outline in reflection coefficients
+#[derive(Clone, Debug, PartialEq)]
+pub struct Region {
+    /// Outline of the area, filled as a convex polygon
+    pub outline: Vec<Complex<f32>>,
+    pub fill: Color32,
+    pub stroke: Stroke,
+}
+impl Region {
+    /// Area enclosed by `outline`, which must be convex
+    pub fn polygon(outline: Vec<Complex<f32>>, fill: Color32) -> Self {
+        Self::from_points(outline, fill)
+    }
+
+    /// Area enclosed by any convex [`SmithPoints`] outline
+    pub fn from_points<'a>(outline: impl Into<SmithPoints<'a>>, fill: Color32) -> Self {
+        Self {
+            outline: outline.into().into_vec(),
+            fill,
+            stroke: Stroke::none(),
+        }
+    }
+
+    /// Area where the VSWR is at most `vswr`
+    pub fn vswr(vswr: f32, fill: Color32) -> Self {
+        let radius = (vswr - 1.0) / (vswr + 1.0);
+        let outline = (0..128)
+            .map(|i| Complex::from_polar(radius, i as f32 / 128.0 * std::f32::consts::TAU))
+            .collect();
+        Self::polygon(outline, fill)
+    }
+
+    pub fn stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = stroke;
+        self
+    }
+
+    pub(crate) fn shape(&self, transform: &ChartTransform) -> egui::Shape {
+        let points = self
+            .outline
+            .iter()
+            .map(|gamma| transform.gamma_to_screen(gamma))
+            .collect();
+        egui::Shape::convex_polygon(points, self.fill, self.stroke)
+    }
+}
+
+/// Limit of a [`SpecMask`], in the unit it was given in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpecLimit {
+    /// Largest VSWR that passes, at least 1
+    Vswr(f32),
+    /// Smallest return loss in dB that passes, at least 0
+    ReturnLoss(f32),
+}
+
+/// Largest mismatch allowed over a frequency range, see [`crate::SmithChart::spec_mask`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpecMask {
+    /// Frequencies in Hz the limit applies to
+    pub frequencies: RangeInclusive<f64>,
+    pub limit: SpecLimit,
+}
+impl SpecMask {
+    /// VSWR of at most `max_vswr` over `frequencies`, a limit below 1 is taken as 1
+    pub fn vswr(frequencies: RangeInclusive<f64>, max_vswr: f32) -> Self {
+        Self {
+            frequencies,
+            limit: SpecLimit::Vswr(max_vswr.max(1.0)),
+        }
+    }
+
+    /// Return loss of at least `min_return_loss` dB over `frequencies`, a negative
+    /// limit is taken as 0 dB
+    pub fn return_loss(frequencies: RangeInclusive<f64>, min_return_loss: f32) -> Self {
+        Self {
+            frequencies,
+            limit: SpecLimit::ReturnLoss(min_return_loss.max(0.0)),
+        }
+    }
+
+    /// Largest |Γ| that passes
+    pub fn max_gamma(&self) -> f32 {
+        let gamma = match self.limit {
+            SpecLimit::Vswr(vswr) => (vswr - 1.0) / (vswr + 1.0),
+            SpecLimit::ReturnLoss(rl) => 10f32.powf(-rl / 20.0),
+        };
+        gamma.clamp(0.0, 1.0)
+    }
+
+    /// Whether `gamma` at `frequency` meets the limit, `None` outside the mask
+    pub fn passes(&self, frequency: f64, gamma: Complex<f32>) -> Option<bool> {
+        self.frequencies
+            .contains(&frequency)
+            .then(|| gamma.norm() <= self.max_gamma())
+    }
+
+    /// Whether every sample of `trace` inside the mask meets the limit, `None` if the
+    /// trace has no frequencies there or isn't a reflection coefficient, such as a
+    /// transmission S-parameter or a derived trace:
+    /// ```
+    /// # use egui_smith_chart::{Complex, SpecMask, Trace};
+    /// let trace = Trace::new("s11", vec![Complex::new(0.5, 0.0), Complex::new(0.1, 0.0)])
+    ///     .frequencies(vec![1e9, 2e9]);
+    /// assert_eq!(SpecMask::vswr(1.5e9..=2.5e9, 2.0).check(&trace), Some(true));
+    /// assert_eq!(SpecMask::return_loss(0.5e9..=2.5e9, 10.0).check(&trace), Some(false));
+    /// assert_eq!(SpecMask::vswr(3e9..=4e9, 2.0).check(&trace), None);
+    /// assert_eq!(SpecMask::vswr(1.5e9..=2.5e9, 2.0).check(&trace.port(1, 0)), None);
+    /// ```
+    pub fn check(&self, trace: &Trace) -> Option<bool> {
+        if !trace.is_reflection() {
+            return None;
+        }
+        trace
+            .frequencies
+            .iter()
+            .zip(&trace.gamma)
+            .filter_map(|(&frequency, &gamma)| self.passes(frequency, gamma))
+            .reduce(|all, pass| all && pass)
+    }
+
+    /// Circle of the passing reflection coefficients
+    pub(crate) fn shape(&self, transform: &ChartTransform, color: Color32) -> egui::Shape {
+        egui::Shape::Circle(egui::epaint::CircleShape {
+            center: transform.gamma_to_screen(&Complex::new(0.0, 0.0)),
+            radius: transform.scale(self.max_gamma()),
+            fill: color.linear_multiply(0.1),
+            stroke: Stroke::new(1.0, color.linear_multiply(0.6)),
+        })
+    }
+}
+
+/// Verdict of all `masks` covering `frequency`, `None` if there are none
+pub(crate) fn compliance(masks: &[SpecMask], frequency: f64, gamma: Complex<f32>) -> Option<bool> {
+    masks
+        .iter()
+        .filter_map(|mask| mask.passes(frequency, gamma))
+        .reduce(|all, pass| all && pass)
+}
@@ -0,0 +1,173 @@
+//! Standalone HTML reports of a chart, for sharing tuning results with people who
+//! don't run the app.
+//!
+//! The page has no scripts or external resources: the chart is inline SVG, followed
+//! by a table of the markers and one of trace statistics.
+//! ```no_run
+//! # let ctx = egui::Context::default();
+//! # let mut chart = egui_smith_chart::SmithChart::new("chart");
+//! use egui_smith_chart::report::Report;
+//!
+//! let html = Report::new("Antenna match, rev B").html(&mut chart, &ctx);
+//! std::fs::write("report.html", html).unwrap();
+//! ```
+
+use std::fmt::Write;
+
+use egui::Context;
+
+use crate::svg::escape;
+use crate::trace::vswr;
+use crate::{math, SmithChart, SmithChartState};
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin: 1em 0 2em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: left; }
+th { background: #f0f0f0; }
+td.color { width: 1em; }";
+
+/// HTML report of a [`SmithChart`]
+pub struct Report {
+    title: String,
+    chart_size: f32,
+}
+impl Report {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            chart_size: 480.0,
+        }
+    }
+
+    /// Width and height of the chart image in CSS pixels, 480 by default
+    pub fn chart_size(mut self, size: f32) -> Self {
+        self.chart_size = size;
+        self
+    }
+
+    /// Page of `chart` with the markers and view it was last shown with by `ctx`.
+    /// Values are written in the number format of the chart.
+    pub fn html(&self, chart: &mut SmithChart, ctx: &Context) -> String {
+        let state = SmithChartState::load(ctx, chart.id_source).unwrap_or_default();
+        let (_, output, rect) = chart.paint_page(ctx, self.chart_size, 1.0);
+        let title = escape(&self.title);
+
+        let mut html = String::new();
+        writeln!(
+            html,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n<h1>{}</h1>",
+            title, STYLE, title
+        )
+        .ok();
+        html += &crate::svg::document(&output.shapes, rect);
+        html += &markers(chart, &state);
+        html += &traces(chart);
+        html += "</body>\n</html>\n";
+        html
+    }
+}
+
+/// Table of the markers on traces and the free markers, numbered like on the chart
+fn markers(chart: &SmithChart, state: &SmithChartState) -> String {
+    let format = &chart.format;
+    let mut rows = Vec::new();
+    for (i, marker) in chart.all_markers(state).enumerate() {
+        let trace = match chart.traces.get(marker.trace) {
+            Some(trace) => trace,
+            None => continue,
+        };
+        let gamma = match trace.gamma_at(marker.frequency) {
+            Some(gamma) => gamma,
+            None => continue,
+        };
+        let sweep = if trace.is_swept() {
+            format.frequency(marker.frequency)
+        } else {
+            format!("#{}", marker.frequency)
+        };
+        rows.push((format!("M{}", i + 1), trace.name.clone(), sweep, gamma));
+    }
+    for (i, gamma) in state.free_markers.iter().enumerate() {
+        rows.push((format!("P{}", i + 1), String::new(), String::new(), *gamma));
+    }
+    if rows.is_empty() {
+        return String::new();
+    }
+
+    let mut html = "<h2>Markers</h2>\n<table>\n<tr><th>marker</th><th>trace</th>\
+                    <th>frequency</th><th>Z</th><th>Γ</th><th>VSWR</th><th>RL</th></tr>\n"
+        .to_string();
+    for (label, trace, sweep, gamma) in rows {
+        let z = math::scale(chart.gamma_to_z(&gamma), chart.Z0);
+        writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            label,
+            escape(&trace),
+            sweep,
+            format.complex(z, "Ω"),
+            format.polar(gamma),
+            format.number(vswr(gamma.norm())),
+            format.db(-20.0 * gamma.norm().log10())
+        )
+        .ok();
+    }
+    html += "</table>\n";
+    html
+}
+
+/// Table of the sweep, VSWR extremes and best match of every trace
+fn traces(chart: &SmithChart) -> String {
+    if chart.traces.is_empty() {
+        return String::new();
+    }
+    let format = &chart.format;
+    let mut html = "<h2>Traces</h2>\n<table>\n<tr><th></th><th>trace</th><th>source</th>\
+                    <th>samples</th><th>frequency</th><th>min VSWR</th><th>max VSWR</th>\
+                    <th>best match</th><th>RL</th></tr>\n"
+        .to_string();
+    for (i, trace) in chart.traces.iter().enumerate() {
+        let color = trace.color.unwrap_or(chart.style.palette.color(i));
+        let range = match (trace.sweep_range(), trace.is_swept()) {
+            (Some(range), true) => format!(
+                "{} – {}",
+                format.frequency(*range.start()),
+                format.frequency(*range.end())
+            ),
+            _ => String::new(),
+        };
+        let (min_vswr, max_vswr, best_match, return_loss) = match trace.stats() {
+            Some(stats) => (
+                format.number(stats.min_vswr),
+                format.number(stats.max_vswr),
+                if trace.is_swept() {
+                    format.frequency(stats.best_match_sweep)
+                } else {
+                    format!("#{}", stats.best_match_index)
+                },
+                format.db(stats.return_loss()),
+            ),
+            None => Default::default(),
+        };
+        writeln!(
+            html,
+            "<tr><td class=\"color\" style=\"background: #{:02x}{:02x}{:02x}\"></td>\
+             <td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            color.r(),
+            color.g(),
+            color.b(),
+            escape(&trace.name),
+            escape(trace.source.as_deref().unwrap_or_default()),
+            trace.gamma.len(),
+            range,
+            min_vswr,
+            max_vswr,
+            best_match,
+            return_loss
+        )
+        .ok();
+    }
+    html += "</table>\n";
+    html
+}
@@ -0,0 +1,124 @@
+//! What [`crate::SmithChart::show`] returns.
+
+use std::time::Duration;
+
+use egui::Pos2;
+
+#[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+use crate::SmithChartError;
+use crate::{ChartTransform, Command, Complex, Deviation, LegendEntry, Marker, Trace, TraceHit};
+
+/// Result of showing a [`crate::SmithChart`]
+pub struct SmithChartResponse {
+    /// Response of the chart area
+    pub response: egui::Response,
+
+    /// Mapping between reflection coefficients and the screen in the last frame
+    pub transform: ChartTransform,
+
+    /// Trace sample within the pick radius of the pointer
+    pub hovered: Option<TraceHit>,
+
+    /// Trace selected by clicking it
+    pub selected_trace: Option<usize>,
+
+    /// Indices of the selected markers
+    pub selected_markers: Vec<usize>,
+
+    /// Deviation of the measurement from the reference set with
+    /// [`crate::SmithChart::compare`]
+    pub deviation: Option<Deviation>,
+
+    /// Chart values at the frozen cursor, or else under the pointer while it is inside
+    /// the unit circle
+    pub cursor: Option<Cursor>,
+
+    /// Chart values at the pinned A and B cursors
+    pub pinned_cursors: [Option<Cursor>; 2],
+
+    /// Commands run by keyboard shortcuts this frame
+    pub commands: Vec<Command>,
+
+    /// Markers deleted this frame with a shortcut, their close button or by dragging
+    /// them off the chart
+    pub deleted_markers: Vec<Marker>,
+
+    /// Free markers deleted this frame with their close button
+    pub deleted_free_markers: Vec<Complex<f32>>,
+
+    /// Touchstone, load-pull and JSON files dropped on the chart this frame, with
+    /// [`crate::SmithChart::file_drop`] on
+    pub dropped_files: Vec<egui::DroppedFile>,
+
+    /// Why copying the chart image from the right click menu failed this frame
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    pub copy_error: Option<SmithChartError>,
+
+    /// Entries of the legend while it is shown, e.g. to render them outside the chart
+    /// with [`crate::LegendPlacement::External`]
+    pub legend: Vec<LegendEntry>,
+
+    /// What painting the chart cost this frame
+    pub stats: PaintStats,
+
+    pub(crate) traces: Vec<Trace>,
+}
+impl SmithChartResponse {
+    /// The trace selected by clicking it, e.g. for a [`crate::TraceDetails`] panel
+    pub fn selected(&self) -> Option<&Trace> {
+        self.traces.get(self.selected_trace?)
+    }
+
+    /// Trace sample closest to `screen_pos`, e.g. for custom picking and tooltips
+    pub fn nearest_trace_point(&self, screen_pos: Pos2) -> Option<TraceHit> {
+        self.transform.nearest_trace_point(&self.traces, screen_pos)
+    }
+}
+
+/// Cost of painting a chart, for fitting it into the frame budget of an app
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PaintStats {
+    /// Shapes handed to the painter
+    pub shapes: usize,
+    /// Points of the paths and vertices of the meshes among the shapes
+    pub points: usize,
+    /// The grid was built and tessellated rather than taken from the shared cache
+    pub grid_rebuilt: bool,
+    /// Time spent building the shapes, including the grid. `None` on the web, where
+    /// there is no clock.
+    pub paint_time: Option<Duration>,
+}
+
+/// Chart values at the pointer position
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cursor {
+    /// Reflection coefficient
+    pub gamma: Complex<f32>,
+    /// Impedance normalized to the chart Z0
+    pub normalized: Complex<f32>,
+    /// Impedance in ohms
+    pub impedance: Complex<f32>,
+}
+impl Cursor {
+    /// Voltage standing wave ratio
+    pub fn vswr(&self) -> f32 {
+        let magnitude = self.gamma.norm();
+        (1.0 + magnitude) / (1.0 - magnitude)
+    }
+
+    /// Return loss in dB
+    pub fn return_loss(&self) -> f32 {
+        -20.0 * self.gamma.norm().log10()
+    }
+
+    /// Transmission coefficient T = 1 + Γ
+    pub fn transmission(&self) -> Complex<f32> {
+        1.0 + self.gamma
+    }
+
+    /// Position on the wavelengths toward generator scale, see
+    /// [`crate::measure::wavelengths_toward_generator`]
+    pub fn wavelengths_toward_generator(&self) -> f32 {
+        crate::measure::wavelengths_toward_generator(self.gamma)
+    }
+}
@@ -0,0 +1,175 @@
+//! Grid, overlay and style settings end users edit at runtime.
+
+use egui::{ComboBox, DragValue, Slider, Ui};
+
+use crate::{GridDensity, Layer, Locale, Palette, Plane, SmithChartStyle};
+
+/// What the settings popup of a [`crate::SmithChart`] edits, see
+/// [`crate::SmithChart::settings_popup`].
+///
+/// Starts out as the setup of the chart builder. Once the user changes something it is
+/// kept in [`crate::SmithChartState::settings`] and takes precedence over the builder,
+/// until it is reset to `None`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ChartSettings {
+    pub plane: Plane,
+    /// Grid detail, `None` leaves it to the chart size
+    pub grid_density: Option<GridDensity>,
+    /// Add finer grid circles as the chart is enlarged or zoomed
+    pub adaptive_grid: bool,
+    pub grid_labels: bool,
+    /// Degree ticks of ∠Γ around the rim
+    pub angle_ticks: bool,
+    pub legend: bool,
+    pub hidden_layers: Vec<Layer>,
+    pub style: SmithChartStyle,
+}
+
+/// Controls for `settings`, `true` if any of them changed
+pub(crate) fn edit(ui: &mut Ui, settings: &mut ChartSettings, locale: &Locale) -> bool {
+    let mut changed = false;
+
+    ui.strong(&locale.settings_grid);
+    egui::Grid::new("grid").num_columns(2).show(ui, |ui| {
+        ui.label(&locale.plane);
+        ComboBox::from_id_source("plane")
+            .selected_text(settings.plane.to_string())
+            .show_ui(ui, |ui| {
+                for plane in [Plane::Impedance, Plane::Admittance, Plane::Both] {
+                    changed |= ui
+                        .selectable_value(&mut settings.plane, plane, plane.to_string())
+                        .changed();
+                }
+            });
+        ui.end_row();
+
+        ui.label(&locale.density);
+        let density = |density: Option<GridDensity>| match density {
+            Some(density) => density.to_string(),
+            None => locale.density_by_size.clone(),
+        };
+        ComboBox::from_id_source("density")
+            .selected_text(density(settings.grid_density))
+            .show_ui(ui, |ui| {
+                for value in [
+                    None,
+                    Some(GridDensity::Coarse),
+                    Some(GridDensity::Medium),
+                    Some(GridDensity::Fine),
+                    Some(GridDensity::Paper),
+                ] {
+                    changed |= ui
+                        .selectable_value(&mut settings.grid_density, value, density(value))
+                        .changed();
+                }
+            });
+        ui.end_row();
+    });
+    changed |= ui
+        .checkbox(&mut settings.adaptive_grid, &locale.adaptive_grid)
+        .changed();
+    changed |= ui
+        .checkbox(&mut settings.grid_labels, &locale.grid_labels)
+        .changed();
+    changed |= ui
+        .checkbox(&mut settings.angle_ticks, &locale.angle_ticks)
+        .changed();
+
+    ui.separator();
+    ui.strong(&locale.settings_overlays);
+    changed |= ui.checkbox(&mut settings.legend, &locale.legend).changed();
+    for layer in Layer::ALL {
+        let mut visible = !settings.hidden_layers.contains(&layer);
+        if ui.checkbox(&mut visible, layer.to_string()).changed() {
+            settings.hidden_layers.retain(|&hidden| hidden != layer);
+            if !visible {
+                settings.hidden_layers.push(layer);
+            }
+            changed = true;
+        }
+    }
+
+    ui.separator();
+    ui.strong(&locale.settings_style);
+    let style = &mut settings.style;
+    egui::Grid::new("style").num_columns(2).show(ui, |ui| {
+        ui.label(&locale.preset);
+        ui.horizontal(|ui| {
+            let presets = [
+                (&locale.preset_default, SmithChartStyle::default()),
+                (&locale.preset_paper, SmithChartStyle::classic_paper()),
+                (&locale.preset_dark, SmithChartStyle::dark()),
+                (&locale.preset_print, SmithChartStyle::print()),
+            ];
+            for (name, preset) in presets {
+                if ui.selectable_label(*style == preset, name).clicked() {
+                    *style = preset;
+                    changed = true;
+                }
+            }
+        });
+        ui.end_row();
+
+        ui.label(&locale.palette);
+        let mut palette = style.palette;
+        ComboBox::from_id_source("palette")
+            .selected_text(palette.to_string())
+            .show_ui(ui, |ui| {
+                for value in [Palette::Default, Palette::OkabeIto, Palette::TolBright] {
+                    ui.selectable_value(&mut palette, value, value.to_string());
+                }
+            });
+        if palette != style.palette {
+            *style = style.clone().with_palette(palette);
+            changed = true;
+        }
+        ui.end_row();
+
+        ui.label(&locale.grid_width);
+        changed |= ui
+            .add(
+                DragValue::new(&mut style.grid_width)
+                    .clamp_range(0.5..=8.0)
+                    .speed(0.1),
+            )
+            .changed();
+        ui.end_row();
+
+        ui.label(&locale.strong_grid_width);
+        changed |= ui
+            .add(
+                DragValue::new(&mut style.strong_grid_width)
+                    .clamp_range(0.5..=8.0)
+                    .speed(0.1),
+            )
+            .changed();
+        ui.end_row();
+
+        ui.label(&locale.font_size);
+        changed |= ui
+            .add(DragValue::new(&mut style.font_size).clamp_range(8.0..=32.0))
+            .changed();
+        ui.end_row();
+
+        ui.label(&locale.unselected_opacity);
+        changed |= ui
+            .add(Slider::new(&mut style.unselected_opacity, 0.0..=1.0))
+            .changed();
+        ui.end_row();
+
+        ui.label(&locale.background);
+        let mut filled = style.background.is_some();
+        ui.horizontal(|ui| {
+            changed |= ui.checkbox(&mut filled, "").changed();
+            match (&mut style.background, filled) {
+                (Some(color), true) => changed |= ui.color_edit_button_srgba(color).changed(),
+                (background @ None, true) => *background = Some(ui.visuals().extreme_bg_color),
+                (background, false) => *background = None,
+            }
+        });
+        ui.end_row();
+    });
+
+    changed
+}
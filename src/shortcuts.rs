@@ -0,0 +1,128 @@
+//! Keyboard shortcuts of the chart and the commands they run.
+
+use egui::{Key, Modifiers};
+
+/// Action run by a keyboard shortcut
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Show the whole chart again
+    ResetView,
+    ZoomIn,
+    ZoomOut,
+    /// Cycle impedance, admittance and both grids, starting from the configured plane
+    TogglePlane,
+    /// Place a marker on the trace point under the cursor
+    AddMarker,
+    DeleteSelectedMarkers,
+    /// Flip the VSWR circle under the cursor on or off
+    ToggleVswrCircle,
+    /// Pin cursor A at the pointer, or remove it when the pointer is off the chart
+    PinCursorA,
+    /// Pin cursor B at the pointer, or remove it when the pointer is off the chart
+    PinCursorB,
+    /// Switch the selected markers, or else all readouts, between rectangular and
+    /// polar values
+    ToggleReadoutForm,
+}
+impl std::fmt::Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::ResetView => "reset view",
+            Self::ZoomIn => "zoom in",
+            Self::ZoomOut => "zoom out",
+            Self::TogglePlane => "toggle plane",
+            Self::AddMarker => "add marker",
+            Self::DeleteSelectedMarkers => "delete selected markers",
+            Self::ToggleVswrCircle => "toggle VSWR circle",
+            Self::PinCursorA => "pin cursor A",
+            Self::PinCursorB => "pin cursor B",
+            Self::ToggleReadoutForm => "toggle polar readout",
+        })
+    }
+}
+
+/// Key bindings of a [`crate::SmithChart`], handled while it has keyboard focus or is
+/// hovered.
+///
+/// Start from the defaults and rebind or remove what clashes with the host app:
+/// ```
+/// # use egui::{Key, Modifiers};
+/// # use egui_smith_chart::{Command, Shortcuts};
+/// let shortcuts = Shortcuts::default()
+///     .unbind(Command::TogglePlane)
+///     .bind(Modifiers::CTRL, Key::Num0, Command::ResetView);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shortcuts {
+    pub bindings: Vec<(Modifiers, Key, Command)>,
+    /// Held while dragging to slow the pointer down for fine positioning. Alt by
+    /// default, since Shift adds to the selection of a rubber band; `Modifiers::NONE`
+    /// turns precision dragging off.
+    pub precision: Modifiers,
+}
+impl Default for Shortcuts {
+    fn default() -> Self {
+        Self {
+            bindings: vec![
+                (Modifiers::NONE, Key::R, Command::ResetView),
+                (Modifiers::NONE, Key::PageUp, Command::ZoomIn),
+                (Modifiers::NONE, Key::PageDown, Command::ZoomOut),
+                (Modifiers::NONE, Key::P, Command::TogglePlane),
+                (Modifiers::NONE, Key::M, Command::AddMarker),
+                (Modifiers::NONE, Key::Delete, Command::DeleteSelectedMarkers),
+                (Modifiers::NONE, Key::V, Command::ToggleVswrCircle),
+                (Modifiers::NONE, Key::A, Command::PinCursorA),
+                (Modifiers::NONE, Key::B, Command::PinCursorB),
+                (Modifiers::NONE, Key::F, Command::ToggleReadoutForm),
+            ],
+            precision: Modifiers::ALT,
+        }
+    }
+}
+impl Shortcuts {
+    /// No shortcuts at all
+    pub fn none() -> Self {
+        Self {
+            bindings: Vec::new(),
+            precision: Modifiers::NONE,
+        }
+    }
+
+    /// Run `command` on `key` with `modifiers`, in addition to its other bindings
+    pub fn bind(mut self, modifiers: Modifiers, key: Key, command: Command) -> Self {
+        self.bindings.push((modifiers, key, command));
+        self
+    }
+
+    /// Remove every binding of `command`
+    pub fn unbind(mut self, command: Command) -> Self {
+        self.bindings.retain(|(_, _, bound)| *bound != command);
+        self
+    }
+
+    /// Slow the pointer down while dragging with `modifiers` held
+    pub fn precision(mut self, modifiers: Modifiers) -> Self {
+        self.precision = modifiers;
+        self
+    }
+
+    /// Whether all of the precision modifiers are among the held `modifiers`
+    pub(crate) fn precision_held(&self, modifiers: Modifiers) -> bool {
+        let wanted = self.precision;
+        wanted != Modifiers::NONE
+            && (modifiers.alt || !wanted.alt)
+            && (modifiers.ctrl || !wanted.ctrl)
+            && (modifiers.shift || !wanted.shift)
+            && (modifiers.mac_cmd || !wanted.mac_cmd)
+            && (modifiers.command || !wanted.command)
+    }
+
+    /// Commands whose shortcut was pressed this frame, consuming the key presses
+    pub(crate) fn pressed(&self, input: &mut egui::InputState) -> Vec<Command> {
+        self.bindings
+            .iter()
+            .filter(|(modifiers, key, _)| input.consume_key(*modifiers, *key))
+            .map(|(_, _, command)| *command)
+            .collect()
+    }
+}
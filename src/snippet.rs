@@ -0,0 +1,72 @@
+//! Chart values as code for analysis scripts.
+
+use crate::Complex;
+
+/// Language of copied code snippets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodeLanguage {
+    /// numpy arrays, plus a scikit-rf network when every point has a frequency
+    Python,
+    Matlab,
+}
+impl std::fmt::Display for CodeLanguage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Python => "Python",
+            Self::Matlab => "MATLAB",
+        })
+    }
+}
+
+/// Point to put in a snippet
+pub(crate) struct SnippetPoint {
+    pub label: String,
+    /// Frequency in Hz, `None` for points off a frequency sweep
+    pub frequency: Option<f64>,
+    /// Impedance in ohms
+    pub impedance: Complex<f32>,
+}
+
+/// Code defining the reference impedance `z0`, the frequencies if all points have one,
+/// and the impedances and reflection coefficients of `points`
+pub(crate) fn snippet(language: CodeLanguage, points: &[SnippetPoint], z0: Complex<f32>) -> String {
+    let labels: Vec<_> = points.iter().map(|p| p.label.as_str()).collect();
+    let frequencies: Option<Vec<_>> = points.iter().map(|p| p.frequency).collect();
+    let mut code = String::new();
+    match language {
+        CodeLanguage::Python => {
+            let complex = |z: Complex<f32>| format!("{}{:+}j", z.re, z.im);
+            code += "import numpy as np\n";
+            if frequencies.is_some() {
+                code += "import skrf as rf\n";
+            }
+            code += &format!("\n# {}\nz0 = {}\n", labels.join(", "), complex(z0));
+            if let Some(frequencies) = &frequencies {
+                code += &format!("f = np.array([{}])  # Hz\n", join(frequencies, ", "));
+            }
+            let z: Vec<_> = points.iter().map(|p| complex(p.impedance)).collect();
+            code += &format!("z = np.array([{}])  # ohms\n", z.join(", "));
+            code += "gamma = (z - z0) / (z + z0)\n";
+            if frequencies.is_some() {
+                code += "ntwk = rf.Network(frequency=rf.Frequency.from_f(f, unit='hz'), \
+                         s=gamma, z0=z0)\n";
+            }
+        }
+        CodeLanguage::Matlab => {
+            let complex = |z: Complex<f32>| format!("{}{:+}i", z.re, z.im);
+            code += &format!("% {}\nz0 = {};\n", labels.join(", "), complex(z0));
+            if let Some(frequencies) = &frequencies {
+                code += &format!("f = [{}]; % Hz\n", join(frequencies, " "));
+            }
+            let z: Vec<_> = points.iter().map(|p| complex(p.impedance)).collect();
+            code += &format!("z = [{}]; % ohms\n", z.join(" "));
+            code += "gamma = (z - z0) ./ (z + z0);\n";
+        }
+    }
+    code
+}
+
+fn join(values: &[f64], separator: &str) -> String {
+    let values: Vec<_> = values.iter().map(|v| format!("{:e}", v)).collect();
+    values.join(separator)
+}
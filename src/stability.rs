@@ -0,0 +1,159 @@
+//! Stability of two-port amplifiers: Rollett K, μ and stability circles.
+
+use egui::plot::{Legend, Line, Plot, PlotPoints, PlotUi, Polygon, VLine};
+use egui::{Color32, Response, Ui};
+
+use crate::{format::format_eng, Circle, Complex, Matrix2, Network};
+
+/// Stability metrics of a two-port at one frequency
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stability {
+    /// Frequency in Hz
+    pub frequency: f64,
+    /// Rollett stability factor
+    pub k: f32,
+    /// |Δ| = |S11 S22 − S12 S21|
+    pub delta: f32,
+    /// Distance from the center to the nearest unstable load, stable for μ > 1
+    pub mu: f32,
+    /// Distance from the center to the nearest unstable source, stable for μ′ > 1
+    pub mu_prime: f32,
+    /// Loads on this circle make |Γ_in| = 1
+    pub load_circle: Circle,
+    /// Whether the loads inside `load_circle` are the stable ones
+    pub load_stable_inside: bool,
+    /// Sources on this circle make |Γ_out| = 1
+    pub source_circle: Circle,
+    /// Whether the sources inside `source_circle` are the stable ones
+    pub source_stable_inside: bool,
+}
+impl Stability {
+    pub fn new(frequency: f64, s: &Matrix2) -> Self {
+        let [[s11, s12], [s21, s22]] = *s;
+        let delta = s11 * s22 - s12 * s21;
+        let loop_gain = (s12 * s21).norm();
+        let k = (1.0 - s11.norm_sqr() - s22.norm_sqr() + delta.norm_sqr()) / (2.0 * loop_gain);
+        let mu = (1.0 - s11.norm_sqr()) / ((s22 - delta * s11.conj()).norm() + loop_gain);
+        let mu_prime = (1.0 - s22.norm_sqr()) / ((s11 - delta * s22.conj()).norm() + loop_gain);
+
+        // |S_own|² − |Δ|² sets the size, S_other the offset
+        let circle = |own: Complex<f32>, other: Complex<f32>| {
+            let d = own.norm_sqr() - delta.norm_sqr();
+            Circle {
+                center: (own - delta * other.conj()).conj() / d,
+                radius: loop_gain / d.abs(),
+            }
+        };
+        let load_circle = circle(s22, s11);
+        let source_circle = circle(s11, s22);
+        // the chart center is stable if the port looking in from the other side is
+        let origin = Complex::new(0.0, 0.0);
+
+        Self {
+            frequency,
+            k,
+            delta: delta.norm(),
+            mu,
+            mu_prime,
+            load_circle,
+            load_stable_inside: load_circle.contains(origin) == (s11.norm() < 1.0),
+            source_circle,
+            source_stable_inside: source_circle.contains(origin) == (s22.norm() < 1.0),
+        }
+    }
+
+    /// Stable for any passive source and load
+    pub fn unconditionally_stable(&self) -> bool {
+        self.mu > 1.0
+    }
+}
+
+impl Network {
+    /// Stability metrics at each frequency, empty unless the network has two ports
+    pub fn stability(&self) -> Vec<Stability> {
+        (0..self.frequencies.len())
+            .filter_map(|k| Some(Stability::new(self.frequencies[k], &self.two_port(k)?)))
+            .collect()
+    }
+}
+
+/// K, μ and μ′ over frequency, with potentially unstable bands shaded
+pub struct StabilityPlot<'a> {
+    id_source: egui::Id,
+    stability: &'a [Stability],
+    height: f32,
+}
+impl<'a> StabilityPlot<'a> {
+    pub fn new(id_source: impl std::hash::Hash, stability: &'a [Stability]) -> Self {
+        Self {
+            id_source: egui::Id::new(id_source),
+            stability,
+            height: 160.0,
+        }
+    }
+
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn show(self, ui: &mut Ui) -> Response {
+        let stability = self.stability;
+        Plot::new(self.id_source)
+            .height(self.height)
+            .legend(Legend::default())
+            .x_axis_formatter(|x, _| format_eng(x, "Hz", 3))
+            .show(ui, |plot_ui| {
+                // shade runs of potentially unstable points
+                let mut start = None;
+                for (i, point) in stability.iter().enumerate() {
+                    let unstable = !point.unconditionally_stable();
+                    let last = i + 1 == stability.len();
+                    match (start, unstable) {
+                        (None, true) => start = Some(point.frequency),
+                        (Some(from), false) => {
+                            shade(plot_ui, from, point.frequency);
+                            start = None;
+                        }
+                        _ => {}
+                    }
+                    if let (Some(from), true) = (start, last) {
+                        shade(plot_ui, from, point.frequency);
+                    }
+                }
+
+                for (name, value) in [
+                    ("K", (|s: &Stability| s.k) as fn(&Stability) -> f32),
+                    ("μ", |s| s.mu),
+                    ("μ′", |s| s.mu_prime),
+                ] {
+                    let points: PlotPoints = stability
+                        .iter()
+                        .map(|s| [s.frequency, value(s) as f64])
+                        .collect();
+                    plot_ui.line(Line::new(points).name(name));
+                }
+            })
+            .response
+    }
+}
+
+/// Shade the frequency band from `from` to `to` below the stability limit of 1, or mark
+/// it with a line where it is a single frequency
+fn shade(plot_ui: &mut PlotUi, from: f64, to: f64) {
+    let name = "potentially unstable";
+    if from == to {
+        plot_ui.vline(VLine::new(from).color(Color32::RED).name(name));
+        return;
+    }
+    plot_ui.polygon(
+        Polygon::new(PlotPoints::from(vec![
+            [from, 0.0],
+            [to, 0.0],
+            [to, 1.0],
+            [from, 1.0],
+        ]))
+        .color(Color32::RED)
+        .name(name),
+    );
+}
@@ -0,0 +1,212 @@
+//! Interactive chart state kept in egui memory between frames.
+
+use egui::{Context, Id, Pos2, Rect};
+
+use crate::{ChartSettings, ChartTransform, Complex, ComplexForm};
+
+/// Marker attached to a trace.
+///
+/// Markers between two samples get their reflection coefficient by interpolation,
+/// see [`crate::Trace::gamma_at`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Marker {
+    /// Index of the trace in the order it was added to the chart
+    pub trace: usize,
+    /// Frequency in Hz, or the fractional sample index for traces without frequencies
+    pub frequency: f64,
+}
+
+/// State of a [`crate::SmithChart`] that persists between frames.
+///
+/// Stored in egui memory under the id given to [`crate::SmithChart::new`]. With the
+/// `serde` feature the state is persisted along with the rest of egui memory, so a
+/// session resumes where it was left when the app is restarted.
+/// ```ignore
+/// let mut state = SmithChartState::load(ctx, Id::new("my-chart")).unwrap_or_default();
+/// state.markers.push(Marker { trace: 0, frequency: 2.4e9 });
+/// state.store(ctx, Id::new("my-chart"));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SmithChartState {
+    /// Markers placed on traces
+    pub markers: Vec<Marker>,
+
+    /// Reflection coefficients of markers placed off the traces
+    pub free_markers: Vec<Complex<f32>>,
+
+    /// Reflection coefficient at the center of the view
+    pub center: Complex<f32>,
+
+    /// Magnification of the view, 1 shows the whole chart
+    pub zoom: f32,
+
+    /// Indices of traces hidden by the user
+    pub hidden_traces: Vec<usize>,
+
+    /// Names of trace groups hidden by the user, including the files of traces grouped
+    /// by [`crate::Trace::source`]
+    pub hidden_groups: Vec<String>,
+
+    /// Names of trace groups expanded in the legend
+    pub expanded_groups: Vec<String>,
+
+    /// Reflection coefficient the cursor readout is frozen at by clicking empty chart
+    /// space
+    pub frozen_cursor: Option<Complex<f32>>,
+
+    /// Reflection coefficients of the pinned A and B cursors, compared in the readout
+    pub pinned_cursors: [Option<Complex<f32>>; 2],
+
+    /// Trace selected by clicking it
+    pub selected_trace: Option<usize>,
+
+    /// Trace under the pointer
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) hovered_trace: Option<usize>,
+
+    /// Indices of markers selected by clicking, shift-clicking or a rubber band
+    pub selected_markers: Vec<usize>,
+
+    /// Form of readout values picked by the user, overriding
+    /// [`crate::SmithChart::readout_form`]
+    pub readout_form: Option<ComplexForm>,
+
+    /// Indices of markers whose readout uses the other form than the rest
+    pub flipped_markers: Vec<usize>,
+
+    /// Settings changed by the user in the settings popup, overriding the chart setup
+    pub settings: Option<ChartSettings>,
+
+    /// The settings popup is open
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) settings_open: bool,
+
+    /// Marker being dragged
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) dragged_marker: Option<usize>,
+
+    /// Pointer position of a drag in progress, moving slower than the pointer while
+    /// shift is held
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) drag_pos: Option<Pos2>,
+
+    /// Start of a rubber band selection in progress
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) rubber_band: Option<Pos2>,
+
+    /// Times the plane was toggled from the configured one with a shortcut
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) plane_toggles: u8,
+
+    /// VSWR circle flipped from the configured setting with a shortcut
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) vswr_toggled: bool,
+
+    /// Markers deleted this frame, handed over to the response
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) deleted_markers: Vec<Marker>,
+
+    /// Free markers deleted this frame
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) deleted_free_markers: Vec<Complex<f32>>,
+
+    /// Where the context menu was opened
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) context_gamma: Option<Complex<f32>>,
+
+    /// Start of a ruler measurement in progress
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) ruler: Option<Complex<f32>>,
+}
+impl Default for SmithChartState {
+    fn default() -> Self {
+        Self {
+            markers: Vec::new(),
+            free_markers: Vec::new(),
+            center: Complex::new(0.0, 0.0),
+            zoom: 1.0,
+            hidden_traces: Vec::new(),
+            hidden_groups: Vec::new(),
+            expanded_groups: Vec::new(),
+            frozen_cursor: None,
+            pinned_cursors: [None; 2],
+            selected_trace: None,
+            selected_markers: Vec::new(),
+            readout_form: None,
+            flipped_markers: Vec::new(),
+            settings: None,
+            settings_open: false,
+            hovered_trace: None,
+            dragged_marker: None,
+            drag_pos: None,
+            rubber_band: None,
+            ruler: None,
+            plane_toggles: 0,
+            vswr_toggled: false,
+            deleted_markers: Vec::new(),
+            deleted_free_markers: Vec::new(),
+            context_gamma: None,
+        }
+    }
+}
+impl SmithChartState {
+    pub fn load(ctx: &Context, id: Id) -> Option<Self> {
+        #[cfg(feature = "serde")]
+        let state = ctx.data().get_persisted(id);
+        #[cfg(not(feature = "serde"))]
+        let state = ctx.data().get_temp(id);
+        state
+    }
+
+    pub fn store(self, ctx: &Context, id: Id) {
+        #[cfg(feature = "serde")]
+        ctx.data().insert_persisted(id, self);
+        #[cfg(not(feature = "serde"))]
+        ctx.data().insert_temp(id, self);
+    }
+
+    /// Transform of the current view into `rect`
+    pub fn transform(&self, rect: Rect) -> ChartTransform {
+        ChartTransform::with_view(rect, self.center, self.zoom)
+    }
+
+    /// Show the whole chart again
+    pub fn reset_view(&mut self) {
+        self.center = Complex::new(0.0, 0.0);
+        self.zoom = 1.0;
+    }
+
+    /// Remove the markers at `indices`, keeping the selection of the others
+    pub(crate) fn delete_markers(&mut self, mut indices: Vec<usize>) {
+        indices.sort_unstable();
+        indices.dedup();
+        indices.retain(|&i| i < self.markers.len());
+        for (removed, &i) in indices.iter().enumerate() {
+            let marker = self.markers.remove(i - removed);
+            self.deleted_markers.push(marker);
+        }
+        for kept in [&mut self.selected_markers, &mut self.flipped_markers] {
+            kept.retain(|i| !indices.contains(i));
+            for marker in kept {
+                *marker -= indices.iter().filter(|&&i| i < *marker).count();
+            }
+        }
+        self.dragged_marker = self
+            .dragged_marker
+            .filter(|i| !indices.contains(i))
+            .map(|marker| marker - indices.iter().filter(|&&i| i < marker).count());
+    }
+
+    /// Zoom by `factor` keeping the reflection coefficient `anchor` in place
+    pub fn zoom_around(&mut self, anchor: Complex<f32>, factor: f32) {
+        let zoom = (self.zoom * factor).clamp(1.0, 100.0);
+        self.center = anchor - (anchor - self.center) * (self.zoom / zoom);
+        self.zoom = zoom;
+        if zoom == 1.0 {
+            self.reset_view();
+        }
+    }
+}
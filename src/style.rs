@@ -0,0 +1,353 @@
+//! Colors and strokes of the chart.
+
+use egui::{Color32, Stroke};
+
+const DEFAULT_COLORS: [Color32; 6] = [
+    Color32::from_rgb(0x40, 0xa0, 0xff),
+    Color32::GOLD,
+    Color32::LIGHT_GREEN,
+    Color32::LIGHT_RED,
+    Color32::from_rgb(0xc0, 0x80, 0xff),
+    Color32::from_rgb(0x40, 0xe0, 0xe0),
+];
+const OKABE_ITO_COLORS: [Color32; 7] = [
+    Color32::from_rgb(0xe6, 0x9f, 0x00),
+    Color32::from_rgb(0x56, 0xb4, 0xe9),
+    Color32::from_rgb(0x00, 0x9e, 0x73),
+    Color32::from_rgb(0xf0, 0xe4, 0x42),
+    Color32::from_rgb(0x00, 0x72, 0xb2),
+    Color32::from_rgb(0xd5, 0x5e, 0x00),
+    Color32::from_rgb(0xcc, 0x79, 0xa7),
+];
+const TOL_BRIGHT_COLORS: [Color32; 7] = [
+    Color32::from_rgb(0x44, 0x77, 0xaa),
+    Color32::from_rgb(0xee, 0x66, 0x77),
+    Color32::from_rgb(0x22, 0x88, 0x33),
+    Color32::from_rgb(0xcc, 0xbb, 0x44),
+    Color32::from_rgb(0x66, 0xcc, 0xee),
+    Color32::from_rgb(0xaa, 0x33, 0x77),
+    Color32::from_rgb(0xbb, 0xbb, 0xbb),
+];
+
+/// Trace color palette
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Palette {
+    /// Bright saturated colors
+    Default,
+    /// Okabe-Ito palette, distinguishable with protanopia, deuteranopia and tritanopia
+    OkabeIto,
+    /// Paul Tol's "bright" qualitative palette, also color-blind safe
+    TolBright,
+}
+impl Palette {
+    /// All colors of the palette in order
+    pub fn colors(&self) -> &'static [Color32] {
+        match self {
+            Self::Default => &DEFAULT_COLORS,
+            Self::OkabeIto => &OKABE_ITO_COLORS,
+            Self::TolBright => &TOL_BRIGHT_COLORS,
+        }
+    }
+
+    /// Color number `i`, wrapping around at the end of the palette
+    pub fn color(&self, i: usize) -> Color32 {
+        let colors = self.colors();
+        colors[i % colors.len()]
+    }
+
+    /// Colors of the resistance and reactance circles under the cursor
+    pub fn cursor_colors(&self) -> (Color32, Color32) {
+        match self {
+            // orange/blue stays apart for every common color-vision deficiency, unlike
+            // green/red
+            Self::Default => (
+                Color32::from_rgb(0xff, 0x99, 0x33),
+                Color32::from_rgb(0x33, 0x99, 0xff),
+            ),
+            Self::OkabeIto => (
+                Color32::from_rgb(0xe6, 0x9f, 0x00),
+                Color32::from_rgb(0x56, 0xb4, 0xe9),
+            ),
+            Self::TolBright => (
+                Color32::from_rgb(0xcc, 0xbb, 0x44),
+                Color32::from_rgb(0x66, 0xcc, 0xee),
+            ),
+        }
+    }
+}
+impl std::fmt::Display for Palette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Default => "default",
+            Self::OkabeIto => "Okabe-Ito",
+            Self::TolBright => "Tol bright",
+        })
+    }
+}
+
+/// Dash pattern of grid lines
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum LineStyle {
+    Solid,
+    /// Dashes of `length` points separated by `gap` points
+    Dashed {
+        length: f32,
+        gap: f32,
+    },
+    /// Dots as wide as the line, `spacing` points apart
+    Dotted {
+        spacing: f32,
+    },
+}
+impl LineStyle {
+    /// Shortest dash, gap or dot spacing. Patterns that don't advance along the line
+    /// would never finish painting.
+    pub const MIN_LENGTH: f32 = 0.5;
+
+    /// Dashes of `length` points separated by `gap` points, both at least
+    /// [`LineStyle::MIN_LENGTH`]
+    pub fn dashed(length: f32, gap: f32) -> Self {
+        Self::Dashed { length, gap }.clamped()
+    }
+
+    /// Dots `spacing` points apart, at least [`LineStyle::MIN_LENGTH`]
+    pub fn dotted(spacing: f32) -> Self {
+        Self::Dotted { spacing }.clamped()
+    }
+
+    /// The pattern with non-positive or NaN lengths raised to [`LineStyle::MIN_LENGTH`]
+    pub fn clamped(self) -> Self {
+        let clamp = |length: f32| length.max(Self::MIN_LENGTH);
+        match self {
+            Self::Solid => Self::Solid,
+            Self::Dashed { length, gap } => Self::Dashed {
+                length: clamp(length),
+                gap: clamp(gap),
+            },
+            Self::Dotted { spacing } => Self::Dotted {
+                spacing: clamp(spacing),
+            },
+        }
+    }
+}
+
+/// Visual style of a [`crate::SmithChart`]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SmithChartStyle {
+    /// Fill behind the chart, `None` leaves the panel background visible
+    pub background: Option<Color32>,
+
+    /// Color of the grid, `None` follows the egui visuals
+    pub grid_color: Option<Color32>,
+    /// Width of regular grid lines
+    pub grid_width: f32,
+    /// Width of emphasized grid lines
+    pub strong_grid_width: f32,
+    /// Color of emphasized grid lines, `None` uses the color of their grid
+    pub strong_grid_color: Option<Color32>,
+    /// Dash pattern of the impedance grid
+    pub grid_line_style: LineStyle,
+    /// Dash pattern of the admittance grid. Emphasized circles are always solid.
+    pub admittance_grid_line_style: LineStyle,
+
+    /// Color of readout text, `None` follows the egui visuals
+    pub text_color: Option<Color32>,
+    /// Font size of readout text
+    pub font_size: f32,
+
+    /// Resistance circle under the cursor
+    pub cursor_resistance: Color32,
+    /// Reactance arc under the cursor
+    pub cursor_reactance: Color32,
+    /// VSWR circle under the cursor
+    pub cursor_vswr: Color32,
+    /// Width of the cursor circles
+    pub cursor_width: f32,
+
+    /// Admittance grid color when both grids are shown
+    pub admittance_grid_color: Color32,
+
+    /// Trace colors
+    pub palette: Palette,
+    /// Extra width of hovered and selected traces
+    pub trace_highlight_width: f32,
+    /// Opacity the other traces are dimmed to while a trace is selected, 1 to leave
+    /// them alone
+    pub unselected_opacity: f32,
+    /// Width of the halo under the selected trace on either side, 0 for none
+    pub selection_halo_width: f32,
+    /// Color of the halo, `None` uses the trace color faded out
+    pub selection_halo_color: Option<Color32>,
+
+    /// Fill of the area meeting every contour set's highlight threshold
+    pub contour_highlight: Color32,
+    /// Color of spec mask circles and of trace segments meeting their masks
+    pub spec_pass: Color32,
+    /// Color of trace segments violating a spec mask
+    pub spec_fail: Color32,
+
+    /// Fill behind the legend, `None` leaves the chart visible
+    pub legend_background: Option<Color32>,
+    /// Frame around the legend
+    pub legend_border: Stroke,
+}
+impl Default for SmithChartStyle {
+    fn default() -> Self {
+        let (cursor_resistance, cursor_reactance) = Palette::Default.cursor_colors();
+        Self {
+            background: None,
+            grid_color: None,
+            grid_width: 1.0,
+            strong_grid_width: 3.0,
+            strong_grid_color: None,
+            grid_line_style: LineStyle::Solid,
+            admittance_grid_line_style: LineStyle::Solid,
+            text_color: None,
+            font_size: 14.0,
+            cursor_resistance,
+            cursor_reactance,
+            cursor_vswr: Color32::GOLD,
+            cursor_width: 1.0,
+            admittance_grid_color: Color32::from_rgb(210, 100, 100),
+            palette: Palette::Default,
+            trace_highlight_width: 2.0,
+            unselected_opacity: 1.0,
+            selection_halo_width: 4.0,
+            selection_halo_color: None,
+            contour_highlight: Color32::from_rgba_unmultiplied(255, 215, 0, 60),
+            spec_pass: Color32::from_rgb(0x40, 0xc0, 0x40),
+            spec_fail: Color32::from_rgb(0xe0, 0x40, 0x40),
+            legend_background: None,
+            legend_border: Stroke::none(),
+        }
+    }
+}
+impl SmithChartStyle {
+    /// Pure black/white with thick strokes and large text, for projectors and
+    /// monitors viewed from a distance.
+    ///
+    /// `dark` selects white on black, otherwise black on white.
+    pub fn high_contrast(dark: bool) -> Self {
+        let (background, foreground) = if dark {
+            (Color32::BLACK, Color32::WHITE)
+        } else {
+            (Color32::WHITE, Color32::BLACK)
+        };
+        let (cursor_resistance, cursor_reactance, cursor_vswr) = if dark {
+            (
+                Color32::YELLOW,
+                Color32::from_rgb(0, 255, 255),
+                Color32::WHITE,
+            )
+        } else {
+            (
+                Color32::from_rgb(0, 0, 200),
+                Color32::from_rgb(200, 80, 0),
+                Color32::BLACK,
+            )
+        };
+        Self {
+            background: Some(background),
+            grid_color: Some(foreground),
+            grid_width: 2.0,
+            strong_grid_width: 4.0,
+            strong_grid_color: None,
+            grid_line_style: LineStyle::Solid,
+            admittance_grid_line_style: LineStyle::Solid,
+            text_color: Some(foreground),
+            font_size: 20.0,
+            cursor_resistance,
+            cursor_reactance,
+            cursor_vswr,
+            cursor_width: 3.0,
+            admittance_grid_color: if dark {
+                Color32::from_rgb(255, 110, 110)
+            } else {
+                Color32::from_rgb(170, 0, 0)
+            },
+            palette: Palette::OkabeIto,
+            trace_highlight_width: 3.0,
+            unselected_opacity: 1.0,
+            selection_halo_width: 6.0,
+            selection_halo_color: Some(Color32::GRAY),
+            contour_highlight: Color32::from_rgba_unmultiplied(128, 128, 128, 100),
+            spec_pass: if dark {
+                Color32::from_rgb(0, 255, 0)
+            } else {
+                Color32::from_rgb(0, 140, 0)
+            },
+            spec_fail: if dark {
+                Color32::from_rgb(255, 80, 80)
+            } else {
+                Color32::from_rgb(200, 0, 0)
+            },
+            legend_background: Some(background),
+            legend_border: Stroke::new(2.0, foreground),
+        }
+    }
+
+    /// Cream paper with a red-brown impedance and a blue admittance grid, like the
+    /// printed charts of the lab drawer
+    pub fn classic_paper() -> Self {
+        let ink = Color32::from_rgb(0x40, 0x30, 0x28);
+        Self {
+            background: Some(Color32::from_rgb(0xfb, 0xf5, 0xe3)),
+            grid_color: Some(Color32::from_rgb(0xb0, 0x4a, 0x36)),
+            strong_grid_width: 2.0,
+            text_color: Some(ink),
+            admittance_grid_color: Color32::from_rgb(0x3a, 0x6e, 0xa8),
+            legend_background: Some(Color32::from_rgb(0xfb, 0xf5, 0xe3)),
+            legend_border: Stroke::new(1.0, ink),
+            ..Self::default()
+        }
+        .with_palette(Palette::TolBright)
+    }
+
+    /// Muted grid on a near-black background, independent of the egui theme
+    pub fn dark() -> Self {
+        let background = Color32::from_rgb(0x1b, 0x1b, 0x1f);
+        Self {
+            background: Some(background),
+            grid_color: Some(Color32::from_gray(0x58)),
+            strong_grid_color: Some(Color32::from_gray(0x90)),
+            text_color: Some(Color32::from_gray(0xdc)),
+            admittance_grid_color: Color32::from_rgb(0x9a, 0x55, 0x55),
+            legend_background: Some(background),
+            legend_border: Stroke::new(1.0, Color32::from_gray(0x58)),
+            ..Self::default()
+        }
+    }
+
+    /// Thin black lines on white for reports and printers: a dashed admittance grid
+    /// instead of a second color, no selection halo or dimming
+    pub fn print() -> Self {
+        Self {
+            background: Some(Color32::WHITE),
+            grid_color: Some(Color32::from_gray(0x60)),
+            grid_width: 0.5,
+            strong_grid_width: 1.5,
+            strong_grid_color: Some(Color32::BLACK),
+            admittance_grid_line_style: LineStyle::dashed(4.0, 3.0),
+            text_color: Some(Color32::BLACK),
+            font_size: 12.0,
+            admittance_grid_color: Color32::from_gray(0x90),
+            trace_highlight_width: 1.0,
+            selection_halo_width: 0.0,
+            legend_background: Some(Color32::WHITE),
+            legend_border: Stroke::new(0.5, Color32::BLACK),
+            ..Self::default()
+        }
+        .with_palette(Palette::OkabeIto)
+    }
+
+    /// Use `palette` for traces and the cursor circles
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        (self.cursor_resistance, self.cursor_reactance) = palette.cursor_colors();
+        self.palette = palette;
+        self
+    }
+}
@@ -0,0 +1,186 @@
+//! SVG documents of painted shapes, e.g. for [`crate::report`].
+
+use std::fmt::Write;
+
+use egui::epaint::{ClippedShape, TextShape};
+use egui::{Color32, Pos2, Rect, Stroke};
+
+use crate::vector::{self, num, Backend};
+
+/// SVG element of `shapes` with a view box covering `page`
+pub(crate) fn document(shapes: &[ClippedShape], page: Rect) -> String {
+    let mut svg = Svg {
+        svg: format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+             viewBox=\"{} {} {} {}\" stroke-linecap=\"round\" stroke-linejoin=\"round\">\n",
+            num(page.width()),
+            num(page.height()),
+            num(page.min.x),
+            num(page.min.y),
+            num(page.width()),
+            num(page.height())
+        ),
+        clips: Vec::new(),
+    };
+    vector::walk(&mut svg, shapes, page);
+    svg.svg.push_str("</svg>\n");
+    svg.svg
+}
+
+/// Elements written so far and the clip paths they defined
+struct Svg {
+    svg: String,
+    clips: Vec<Rect>,
+}
+impl Backend for Svg {
+    fn path(&mut self, points: &[Pos2], closed: bool, fill: Color32, stroke: Stroke) {
+        let d = path(points, closed);
+        element(&mut self.svg, "path", &format!("d=\"{}\"", d), fill, stroke);
+    }
+
+    fn circle(&mut self, center: Pos2, radius: f32, fill: Color32, stroke: Stroke) {
+        let attributes = format!(
+            "cx=\"{}\" cy=\"{}\" r=\"{}\"",
+            num(center.x),
+            num(center.y),
+            num(radius)
+        );
+        element(&mut self.svg, "circle", &attributes, fill, stroke);
+    }
+
+    fn rect(&mut self, r: Rect, rounding: f32, fill: Color32, stroke: Stroke) {
+        let attributes = format!(
+            "x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\"",
+            num(r.min.x),
+            num(r.min.y),
+            num(r.width()),
+            num(r.height()),
+            num(rounding)
+        );
+        element(&mut self.svg, "rect", &attributes, fill, stroke);
+    }
+
+    fn cubic(&mut self, [p0, p1, p2, p3]: [Pos2; 4], closed: bool, fill: Color32, stroke: Stroke) {
+        let mut d = format!(
+            "M{} {}C{} {} {} {} {} {}",
+            num(p0.x),
+            num(p0.y),
+            num(p1.x),
+            num(p1.y),
+            num(p2.x),
+            num(p2.y),
+            num(p3.x),
+            num(p3.y)
+        );
+        if closed {
+            d.push('Z');
+        }
+        element(&mut self.svg, "path", &format!("d=\"{}\"", d), fill, stroke);
+    }
+
+    /// Each row as a text element on the baseline egui laid it out at
+    fn text(&mut self, text: &TextShape) {
+        let galley = &text.galley;
+        for row in &galley.rows {
+            let glyph = match row.glyphs.first() {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+            let format = &galley.job.sections[glyph.section_index as usize].format;
+            let size = format.font_id.size;
+            let content: String = row.glyphs.iter().map(|glyph| glyph.chr).collect();
+            write!(
+                self.svg,
+                "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" fill=\"{}\"",
+                num(text.pos.x + glyph.pos.x),
+                num(text.pos.y + row.rect.min.y + 0.8 * size),
+                num(size),
+                paint(text.override_text_color.unwrap_or(format.color))
+            )
+            .ok();
+            if text.angle != 0.0 {
+                write!(
+                    self.svg,
+                    " transform=\"rotate({} {} {})\"",
+                    num(text.angle.to_degrees()),
+                    num(text.pos.x),
+                    num(text.pos.y)
+                )
+                .ok();
+            }
+            writeln!(self.svg, ">{}</text>", escape(&content)).ok();
+        }
+    }
+
+    /// Clip paths are defined once and reused by every group clipped to the same rect
+    fn begin_clip(&mut self, clip: Rect) {
+        let index = match self.clips.iter().position(|c| *c == clip) {
+            Some(index) => index,
+            None => {
+                writeln!(
+                    self.svg,
+                    "<clipPath id=\"clip{}\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/></clipPath>",
+                    self.clips.len(),
+                    num(clip.min.x),
+                    num(clip.min.y),
+                    num(clip.width()),
+                    num(clip.height())
+                )
+                .ok();
+                self.clips.push(clip);
+                self.clips.len() - 1
+            }
+        };
+        writeln!(self.svg, "<g clip-path=\"url(#clip{})\">", index).ok();
+    }
+
+    fn end_clip(&mut self) {
+        self.svg.push_str("</g>\n");
+    }
+}
+
+/// Path data through `points`
+fn path(points: &[Pos2], closed: bool) -> String {
+    let mut d = String::new();
+    for (i, p) in points.iter().enumerate() {
+        let command = if i == 0 { 'M' } else { 'L' };
+        write!(d, "{}{} {}", command, num(p.x), num(p.y)).ok();
+    }
+    if closed {
+        d.push('Z');
+    }
+    d
+}
+
+/// Element `name` with `attributes`, filled and stroked where visible
+fn element(svg: &mut String, name: &str, attributes: &str, fill: Color32, stroke: Stroke) {
+    write!(svg, "<{} {} fill=\"{}\"", name, attributes, paint(fill)).ok();
+    if stroke.width > 0.0 && stroke.color.a() > 0 {
+        write!(
+            svg,
+            " stroke=\"{}\" stroke-width=\"{}\"",
+            paint(stroke.color),
+            num(stroke.width)
+        )
+        .ok();
+    }
+    svg.push_str("/>\n");
+}
+
+/// Fill or stroke color of `color` with its opacity, `none` if transparent
+fn paint(color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    match a {
+        0 => "none".to_string(),
+        255 => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        a => format!("rgba({},{},{},{})", r, g, b, num(a as f32 / 255.0)),
+    }
+}
+
+/// `text` with the characters XML reserves replaced by entities
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
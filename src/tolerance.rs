@@ -0,0 +1,98 @@
+//! Uncertainty of a reflection coefficient from component tolerances.
+
+use egui::Color32;
+
+use crate::{Complex, Curve};
+
+/// Relative step of the finite differences in [`Uncertainty::from_tolerances`]
+const STEP: f64 = 1e-4;
+
+/// Gaussian spread of a reflection coefficient around its nominal value, drawn as
+/// σ ellipses
+#[derive(Clone, Debug, PartialEq)]
+pub struct Uncertainty {
+    /// Nominal reflection coefficient
+    pub center: Complex<f32>,
+    /// Covariance of the real and imaginary parts of Γ
+    pub covariance: [[f32; 2]; 2],
+    pub color: Color32,
+}
+impl Uncertainty {
+    pub fn new(center: Complex<f32>, covariance: [[f32; 2]; 2]) -> Self {
+        Self {
+            center,
+            covariance,
+            color: Color32::LIGHT_BLUE,
+        }
+    }
+
+    /// Spread of `gamma(values)` when each value varies independently with a relative
+    /// standard deviation from `tolerances`, linearized around `nominal`. Values without
+    /// a tolerance are fixed and extra tolerances are ignored:
+    /// ```
+    /// # use egui_smith_chart::Uncertainty;
+    /// # use egui_smith_chart::Complex;
+    /// // 50 Ω load with a 5 % (1σ) resistor
+    /// let spread = Uncertainty::from_tolerances(
+    ///     |values| {
+    ///         let z = values[0] as f32 / 50.0;
+    ///         Complex::new((z - 1.0) / (z + 1.0), 0.0)
+    ///     },
+    ///     &[50.0],
+    ///     &[0.05],
+    /// );
+    /// assert!((spread.covariance[0][0].sqrt() - 0.025).abs() < 1e-3);
+    /// ```
+    pub fn from_tolerances(
+        gamma: impl Fn(&[f64]) -> Complex<f32>,
+        nominal: &[f64],
+        tolerances: &[f64],
+    ) -> Self {
+        let center = gamma(nominal);
+        let mut covariance = [[0.0; 2]; 2];
+        let mut values = nominal.to_vec();
+        for (i, tolerance) in tolerances.iter().take(nominal.len()).enumerate() {
+            let step = nominal[i] * STEP;
+            values[i] = nominal[i] + step;
+            // change of Γ for a 1σ change of value i
+            let d = (gamma(&values) - center) * (tolerance / STEP) as f32;
+            values[i] = nominal[i];
+            covariance[0][0] += d.re * d.re;
+            covariance[0][1] += d.re * d.im;
+            covariance[1][1] += d.im * d.im;
+        }
+        covariance[1][0] = covariance[0][1];
+        Self::new(center, covariance)
+    }
+
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Ellipse containing the values within `sigma` standard deviations
+    pub fn ellipse(&self, sigma: f32) -> Curve<'static> {
+        // symmetric square root of the covariance maps the unit circle onto the 1σ ellipse
+        let [[a, b], [_, c]] = self.covariance;
+        let s = (a * c - b * b).max(0.0).sqrt();
+        let t = (a + c + 2.0 * s).sqrt();
+        let root = if t > 0.0 {
+            [[(a + s) / t, b / t], [b / t, (c + s) / t]]
+        } else {
+            [[0.0; 2]; 2]
+        };
+        let center = self.center;
+        Curve::new(
+            move |angle| {
+                let (sin, cos) = angle.sin_cos();
+                center
+                    + sigma
+                        * Complex::new(
+                            root[0][0] * cos + root[0][1] * sin,
+                            root[1][0] * cos + root[1][1] * sin,
+                        )
+            },
+            0.0..=std::f32::consts::TAU,
+        )
+    }
+}
@@ -0,0 +1,188 @@
+//! Reading S-parameters from Touchstone (.sNp) files.
+
+use std::path::Path;
+
+use crate::{network::Network, Complex, SmithChartError};
+
+/// Read a Touchstone file, the port count is taken from the extension
+pub fn load(path: impl AsRef<Path>) -> Result<Network, SmithChartError> {
+    let path = path.as_ref();
+    let ports = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(ports)
+        .ok_or(SmithChartError::UnknownPorts)?;
+    parse(&std::fs::read_to_string(path)?, ports)
+}
+
+/// Port count from the extension of a Touchstone file, e.g. 2 for "s2p"
+pub(crate) fn ports(extension: &str) -> Option<usize> {
+    extension
+        .to_ascii_lowercase()
+        .strip_prefix('s')?
+        .strip_suffix('p')?
+        .parse()
+        .ok()
+}
+
+/// Parse the contents of a Touchstone file of a network with `ports` ports.
+/// ```
+/// let network = egui_smith_chart::touchstone::parse(
+///     "# MHz S RI R 50
+///      100 0.5 0.0  0.8 0.0  0.8 0.0  0.1 0.0",
+///     2,
+/// )
+/// .unwrap();
+/// assert_eq!(network.frequencies, [100e6]);
+/// // S11 with the output shorted
+/// let trace = network.gamma_in("in", |_| (-1.0).into()).unwrap();
+/// assert!((trace.gamma[0].re - (0.5 - 0.64 / 1.1)).abs() < 1e-6);
+/// ```
+/// Version 2 keywords such as `[Number of Ports]`, `[Two-Port Data Order]` and
+/// `[Reference]` are read, the others skipped. Reading stops at the noise parameters
+/// of a two-port, which aren't S-parameters.
+pub fn parse(text: &str, ports: usize) -> Result<Network, SmithChartError> {
+    let mut ports = ports;
+    let mut scale = 1e9;
+    let mut format = "MA".to_string();
+    let mut z0 = 50.0;
+    let mut has_options = false;
+    // version 1 lists two-port data as S11 S21 S12 S22
+    let mut order_21_12 = true;
+    // impedances of [Reference], which may continue on the following lines
+    let mut references: Option<Vec<f64>> = None;
+    let mut information = false;
+
+    let mut values = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        let line = line.split('!').next().unwrap_or("").trim();
+        let number = |token: &str| {
+            token
+                .parse::<f64>()
+                .map_err(|_| SmithChartError::InvalidNumber {
+                    line: line_index + 1,
+                    text: token.to_string(),
+                })
+        };
+        if let Some(keyword) = line.strip_prefix('[') {
+            let (keyword, argument) = keyword.split_once(']').unwrap_or((keyword, ""));
+            let argument = argument.trim();
+            match keyword.to_ascii_lowercase().as_str() {
+                "number of ports" => ports = number(argument)? as usize,
+                "two-port data order" => order_21_12 = argument == "21_12",
+                "reference" => {
+                    let values = argument.split_whitespace().map(number);
+                    references = Some(values.collect::<Result<_, _>>()?);
+                }
+                "matrix format" if !argument.eq_ignore_ascii_case("full") => {
+                    return Err(SmithChartError::UnsupportedFormat(format!(
+                        "[Matrix Format] {}",
+                        argument
+                    )));
+                }
+                "begin information" => information = true,
+                "end information" => information = false,
+                "noise data" | "end" => break,
+                // [Version], [Number of Frequencies], [Network Data] and the like
+                _ => {}
+            }
+            continue;
+        }
+        if information || line.is_empty() {
+            continue;
+        }
+        if let Some(options) = line.strip_prefix('#') {
+            has_options = true;
+            let mut options = options.split_whitespace().map(str::to_ascii_uppercase);
+            while let Some(option) = options.next() {
+                match option.as_str() {
+                    "HZ" => scale = 1.0,
+                    "KHZ" => scale = 1e3,
+                    "MHZ" => scale = 1e6,
+                    "GHZ" => scale = 1e9,
+                    "S" => {}
+                    "MA" | "DB" | "RI" => format = option,
+                    "R" => z0 = number(&options.next().unwrap_or_default())? as f32,
+                    _ => return Err(SmithChartError::UnsupportedFormat(option)),
+                }
+            }
+            continue;
+        }
+        if let Some(references) = references.as_mut().filter(|r| r.len() < ports) {
+            for token in line.split_whitespace() {
+                references.push(number(token)?);
+            }
+            continue;
+        }
+
+        // the noise parameters of a two-port start at a frequency that isn't higher
+        // than the last one of the network data
+        let point_len = 1 + 2 * ports * ports;
+        if ports == 2 && values.len() % point_len == 0 {
+            let last = values.len().checked_sub(point_len).map(|i| values[i]);
+            let frequency = line.split_whitespace().next().map(number).transpose()?;
+            if matches!((last, frequency), (Some(last), Some(frequency)) if frequency <= last) {
+                break;
+            }
+        }
+
+        for token in line.split_whitespace() {
+            values.push(number(token)?);
+        }
+    }
+
+    if let Some(references) = references {
+        if references.iter().any(|&r| r != references[0]) {
+            diagnostic!(
+                warn,
+                "touchstone: ports of different references, using the first"
+            );
+        }
+        z0 = references.first().map_or(z0, |&r| r as f32);
+    }
+    if !has_options {
+        diagnostic!(warn, "touchstone: no option line, assuming # GHz S MA R 50");
+    }
+
+    let point_len = 1 + 2 * ports * ports;
+    if values.len() % point_len != 0 {
+        return Err(SmithChartError::Incomplete);
+    }
+    let mut frequencies = Vec::new();
+    let mut s = Vec::new();
+    for point in values.chunks(point_len) {
+        frequencies.push(point[0] * scale);
+        let mut parameters: Vec<Complex<f32>> = point[1..]
+            .chunks(2)
+            .map(|pair| {
+                let (a, b) = (pair[0] as f32, pair[1] as f32);
+                match format.as_str() {
+                    "RI" => Complex::new(a, b),
+                    "DB" => Complex::from_polar(10f32.powf(a / 20.0), b.to_radians()),
+                    _ => Complex::from_polar(a, b.to_radians()),
+                }
+            })
+            .collect();
+        // to S11 S12 S21 S22
+        if ports == 2 && order_21_12 {
+            parameters.swap(1, 2);
+        }
+        s.push(parameters);
+    }
+    if frequencies.windows(2).any(|pair| pair[1] <= pair[0]) {
+        diagnostic!(warn, "touchstone: frequencies aren't increasing");
+    }
+    diagnostic!(
+        debug,
+        "touchstone: read {} points of {} ports",
+        frequencies.len(),
+        ports
+    );
+
+    Ok(Network {
+        ports,
+        z0: Complex::new(z0, 0.0),
+        frequencies,
+        s,
+    })
+}
@@ -0,0 +1,850 @@
+//! Data plotted on the chart.
+
+use std::borrow::Cow;
+use std::ops::RangeInclusive;
+
+use egui::{vec2, Color32, Vec2};
+
+use crate::network::{self, Matrix2, Parameters};
+use crate::{math, Complex, Locale, SmithChartError, SmithPoints};
+
+/// Text label with a leader arrow pointing at a trace.
+///
+/// The anchor is given by frequency, so the annotation follows the point when the
+/// trace data is updated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+    /// Frequency in Hz, or the fractional sample index for traces without frequencies
+    pub frequency: f64,
+    pub text: String,
+    /// Position of the label relative to the annotated point, in points
+    pub offset: Vec2,
+}
+impl Annotation {
+    pub fn new(frequency: f64, text: impl Into<String>) -> Self {
+        Self {
+            frequency,
+            text: text.into(),
+            offset: vec2(40.0, -40.0),
+        }
+    }
+
+    /// Position of the label relative to the annotated point, in points
+    pub fn offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+/// Where the frequency ticks of a trace go, see [`Trace::frequency_ticks`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FrequencyTicks {
+    /// A round step giving about ten ticks over the sweep
+    Auto,
+    /// Multiples of a step in Hz
+    Step(f64),
+    /// Powers of ten, for sweeps spanning several decades
+    Decades,
+}
+impl FrequencyTicks {
+    /// Most ticks placed on one trace, finer steps leave the trace without ticks
+    pub const MAX_TICKS: usize = 100;
+
+    /// Frequencies of the ticks within `range`:
+    /// ```
+    /// # use egui_smith_chart::FrequencyTicks;
+    /// assert_eq!(FrequencyTicks::Step(0.5e9).frequencies(0.8e9..=2.2e9), [1e9, 1.5e9, 2e9]);
+    /// assert_eq!(FrequencyTicks::Auto.frequencies(0.0..=45.0).len(), 10);
+    /// assert_eq!(FrequencyTicks::Decades.frequencies(5e3..=2e6), [1e4, 1e5, 1e6]);
+    /// ```
+    pub fn frequencies(&self, range: RangeInclusive<f64>) -> Vec<f64> {
+        let (start, end) = range.into_inner();
+        match *self {
+            Self::Auto => {
+                // 1, 2 or 5 times a power of ten
+                let rough = (end - start) / 10.0;
+                let power = 10f64.powf(rough.log10().floor());
+                let step = [1.0, 2.0, 5.0, 10.0]
+                    .iter()
+                    .map(|factor| factor * power)
+                    .find(|&step| step >= rough)
+                    .unwrap_or(power);
+                Self::Step(step).frequencies(start..=end)
+            }
+            Self::Step(step) if step > 0.0 && (end - start) / step <= Self::MAX_TICKS as f64 => {
+                let first = (start / step).ceil() as i64;
+                let last = (end / step).floor() as i64;
+                (first..=last).map(|i| i as f64 * step).collect()
+            }
+            Self::Decades if start > 0.0 => {
+                let first = start.log10().ceil() as i32;
+                let last = end.log10().floor() as i32;
+                (first..=last).map(|i| 10f64.powi(i)).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Frequencies of the ticks within `range` for a trace on `scale`. On a log scale
+    /// automatic ticks spanning a decade or more go at 1, 2 and 5 times each power of
+    /// ten, or at the powers alone beyond four decades:
+    /// ```
+    /// # use egui_smith_chart::{FrequencyScale, FrequencyTicks};
+    /// let ticks = FrequencyTicks::Auto.scaled_frequencies(3e6..=300e6, FrequencyScale::Log);
+    /// assert_eq!(ticks, [5e6, 10e6, 20e6, 50e6, 100e6, 200e6]);
+    /// ```
+    pub fn scaled_frequencies(
+        &self,
+        range: RangeInclusive<f64>,
+        scale: FrequencyScale,
+    ) -> Vec<f64> {
+        let (start, end) = range.clone().into_inner();
+        match (self, scale) {
+            (Self::Auto, FrequencyScale::Log) if start > 0.0 && end / start >= 10.0 => {
+                let mantissas: &[f64] = if end / start > 1e4 {
+                    &[1.0]
+                } else {
+                    &[1.0, 2.0, 5.0]
+                };
+                let (first, last) = (start.log10().floor() as i32, end.log10().ceil() as i32);
+                (first..=last)
+                    .flat_map(|power| mantissas.iter().map(move |m| m * 10f64.powi(power)))
+                    .filter(|frequency| range.contains(frequency))
+                    .collect()
+            }
+            _ => self.frequencies(range),
+        }
+    }
+}
+
+/// How the automatic frequency ticks of a trace are spread over its sweep, see
+/// [`Trace::frequency_scale`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FrequencyScale {
+    /// Evenly in Hz
+    #[default]
+    Linear,
+    /// Evenly in decades, so the top octave of a decade sweep doesn't take most of the
+    /// ticks. Sweeps reaching down to 0 Hz fall back to linear.
+    Log,
+}
+impl std::fmt::Display for FrequencyScale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Linear => "linear",
+            Self::Log => "logarithmic",
+        })
+    }
+}
+
+/// How values between two samples of a trace are obtained
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Interpolation {
+    /// Straight line between neighbouring samples in the gamma plane
+    #[default]
+    Linear,
+    /// Catmull-Rom spline through the samples, smoother for coarse sweeps
+    Spline,
+}
+
+/// How far past |Γ| = 1 a sample may lie before it's flagged, which passive data
+/// reaches through rounding alone
+const UNIT_CIRCLE_TOLERANCE: f32 = 1e-6;
+
+/// Samples of a trace that can't be drawn as they are, see [`Trace::validity`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Validity {
+    /// Indices of samples whose reflection coefficient is NaN or infinite
+    pub non_finite: Vec<usize>,
+    /// Indices of samples with |Γ| > 1, which only active devices produce
+    pub outside: Vec<usize>,
+}
+impl Validity {
+    pub fn is_valid(&self) -> bool {
+        self.non_finite.is_empty() && self.outside.is_empty()
+    }
+}
+
+/// A curve of reflection coefficients, e.g. a measured S11 sweep
+#[derive(Clone, Debug)]
+pub struct Trace {
+    /// Name shown in readouts and legends
+    pub name: String,
+
+    /// Name of the [`crate::TraceGroup`] the trace belongs to
+    pub group: Option<String>,
+
+    /// File the trace was loaded from, shown in its tooltip
+    pub source: Option<String>,
+
+    /// Ports (i, j) of the S-parameter S<sub>ij</sub> the trace shows, counted from 0
+    pub port: Option<(usize, usize)>,
+
+    /// Reflection coefficient of each sample
+    pub gamma: Vec<Complex<f32>>,
+
+    /// Reference impedance of `gamma` in ohms, `None` if it is the chart Z0
+    pub z0: Option<Complex<f32>>,
+
+    /// Reference the trace was created with, kept when it is renormalized for plotting
+    pub(crate) native_z0: Option<Complex<f32>>,
+
+    /// Validity worked out when the trace was added to a chart, see
+    /// [`Trace::checked_validity`]
+    pub(crate) checked: Option<Validity>,
+
+    /// Frequency of each sample in Hz, empty if the samples aren't swept over frequency
+    pub frequencies: Vec<f64>,
+
+    /// Band of interest in Hz, samples outside it are neither drawn nor analyzed by the
+    /// chart. `None` keeps the whole sweep.
+    pub frequency_range: Option<RangeInclusive<f64>>,
+
+    /// Line color, `None` picks the next color of the style palette
+    pub color: Option<Color32>,
+
+    /// Line width
+    pub width: f32,
+
+    /// Opacity of the line from 0 to 1, lower to keep many overlaid sweeps legible
+    pub opacity: f32,
+
+    /// Hidden traces are neither painted nor picked
+    pub visible: bool,
+
+    /// How markers between samples are placed
+    pub interpolation: Interpolation,
+
+    /// Frequencies of markers placed by the application, see [`Trace::marker_at`]
+    pub markers: Vec<f64>,
+
+    /// Labels attached to points of the trace
+    pub annotations: Vec<Annotation>,
+
+    /// Small labelled ticks across a swept trace at regular frequencies
+    pub frequency_ticks: Option<FrequencyTicks>,
+
+    /// How automatic frequency ticks are spread over the sweep
+    pub frequency_scale: FrequencyScale,
+
+    /// Computed by [`Trace::difference`] or [`Trace::ratio`], whose samples aren't
+    /// reflection coefficients and may lie anywhere
+    pub derived: bool,
+}
+impl Trace {
+    /// Trace from reflection coefficients
+    pub fn new(name: impl Into<String>, gamma: Vec<Complex<f32>>) -> Self {
+        Self::from_points(name, gamma)
+    }
+
+    /// Trace from any [`SmithPoints`], taking the frequencies of generated points
+    pub fn from_points<'a>(name: impl Into<String>, gamma: impl Into<SmithPoints<'a>>) -> Self {
+        let gamma = gamma.into();
+        Self {
+            name: name.into(),
+            group: None,
+            source: None,
+            port: None,
+            frequencies: gamma.frequencies(),
+            gamma: gamma.into_vec(),
+            frequency_range: None,
+            z0: None,
+            native_z0: None,
+            checked: None,
+            color: None,
+            width: 2.0,
+            opacity: 1.0,
+            visible: true,
+            interpolation: Interpolation::default(),
+            markers: Vec::new(),
+            annotations: Vec::new(),
+            frequency_ticks: None,
+            frequency_scale: FrequencyScale::default(),
+            derived: false,
+        }
+    }
+
+    /// Samples that are NaN or infinite, or outside the unit circle by more than
+    /// rounding, e.g. from a corrupted file. Only the former for derived traces:
+    /// ```
+    /// # use egui_smith_chart::Trace;
+    /// # use egui_smith_chart::Complex;
+    /// let trace = Trace::new(
+    ///     "s11",
+    ///     vec![
+    ///         Complex::new(0.5, 0.0),
+    ///         Complex::new(f32::NAN, 0.0),
+    ///         Complex::new(1.2, 0.0),
+    ///         Complex::new(0.0, 1.000_000_1),
+    ///     ],
+    /// );
+    /// let validity = trace.validity();
+    /// assert_eq!((validity.non_finite, validity.outside), (vec![1], vec![2]));
+    /// ```
+    pub fn validity(&self) -> Validity {
+        let mut validity = Validity::default();
+        for (i, gamma) in self.gamma.iter().enumerate() {
+            if !gamma.is_finite() {
+                validity.non_finite.push(i);
+            } else if gamma.norm() > 1.0 + UNIT_CIRCLE_TOLERANCE && !self.derived {
+                validity.outside.push(i);
+            }
+        }
+        validity
+    }
+
+    /// Validity found when the trace was added to a chart, saving a pass over the
+    /// samples on every paint, or else worked out now
+    pub(crate) fn checked_validity(&self) -> Cow<'_, Validity> {
+        match &self.checked {
+            Some(validity) => Cow::Borrowed(validity),
+            None => Cow::Owned(self.validity()),
+        }
+    }
+
+    /// Trace from impedances in ohms, normalized to `z0`
+    pub fn from_impedance(
+        name: impl Into<String>,
+        impedance: &[Complex<f32>],
+        z0: Complex<f32>,
+    ) -> Self {
+        let gamma: Vec<_> = impedance.iter().map(|z| (z - z0) / (z + z0)).collect();
+        Self::new(name, gamma).z0(z0)
+    }
+
+    /// Trace from admittances in siemens, normalized to `z0`
+    pub fn from_admittance(
+        name: impl Into<String>,
+        admittance: &[Complex<f32>],
+        z0: Complex<f32>,
+    ) -> Self {
+        let y0 = 1.0 / z0;
+        let gamma: Vec<_> = admittance.iter().map(|y| (y0 - y) / (y0 + y)).collect();
+        Self::new(name, gamma).z0(z0)
+    }
+
+    /// Input reflection coefficient of two-ports given as `kind` parameters,
+    /// with port 2 terminated in `z0`
+    pub fn from_two_port(
+        name: impl Into<String>,
+        kind: Parameters,
+        parameters: &[Matrix2],
+        z0: Complex<f32>,
+    ) -> Self {
+        let gamma: Vec<_> = parameters
+            .iter()
+            .map(|m| network::to_s(m, kind, z0)[0][0])
+            .collect();
+        Self::new(name, gamma).z0(z0)
+    }
+
+    /// Reference impedance of the reflection coefficients, if it isn't the chart Z0.
+    /// The trace is renormalized to the chart Z0 for plotting.
+    pub fn z0(mut self, z0: Complex<f32>) -> Self {
+        self.z0 = Some(z0);
+        self.native_z0 = Some(z0);
+        self
+    }
+
+    /// Convert the reflection coefficients to reference impedance `z0`, or fail if
+    /// `z0` is zero, infinite or NaN:
+    /// ```
+    /// # use egui_smith_chart::{SmithChartError, Trace};
+    /// # use egui_smith_chart::Complex;
+    /// let trace = Trace::new("s11", vec![Complex::new(0.2, 0.1)]).z0(Complex::new(75.0, 0.0));
+    /// assert!(matches!(
+    ///     trace.try_renormalize(Complex::new(0.0, 0.0)),
+    ///     Err(SmithChartError::InvalidRenormalization(_))
+    /// ));
+    /// ```
+    pub fn try_renormalize(self, z0: Complex<f32>) -> Result<Self, SmithChartError> {
+        if z0 == Complex::new(0.0, 0.0) || !z0.is_finite() {
+            return Err(SmithChartError::InvalidRenormalization(z0));
+        }
+        Ok(self.renormalize(z0))
+    }
+
+    /// Convert the reflection coefficients to reference impedance `z0`.
+    /// Traces without a reference impedance, and derived ones such as differences, are
+    /// left alone. See [`Trace::try_renormalize`] for impedances that may be invalid.
+    /// The open circuit stays at Γ = 1:
+    /// ```
+    /// # use egui_smith_chart::{Complex, Trace};
+    /// let open = Trace::new("open", vec![Complex::new(1.0, 0.0)])
+    ///     .z0(Complex::new(50.0, 0.0))
+    ///     .renormalize(Complex::new(75.0, 0.0));
+    /// assert_eq!(open.gamma, [Complex::new(1.0, 0.0)]);
+    /// ```
+    pub fn renormalize(mut self, z0: Complex<f32>) -> Self {
+        if self.derived {
+            return self;
+        }
+        if let Some(from) = self.z0.filter(|&from| from != z0) {
+            self.native_z0.get_or_insert(from);
+            for gamma in &mut self.gamma {
+                // through ∞ rather than 0/0 at the open circuit
+                let z = math::scale(math::gamma_to_z(*gamma), from / z0);
+                *gamma = math::z_to_gamma(z);
+            }
+            self.z0 = Some(z0);
+        }
+        self
+    }
+
+    /// Reference impedance the trace was given in, before any renormalization
+    pub fn native_z0(&self) -> Option<Complex<f32>> {
+        self.native_z0.or(self.z0)
+    }
+
+    /// File the trace was loaded from
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// S-parameter S<sub>ij</sub> of a network the trace shows, counted from 0
+    pub fn port(mut self, i: usize, j: usize) -> Self {
+        self.port = Some((i, j));
+        self
+    }
+
+    /// Group of the trace in the legend: its own group, or else the file it was loaded
+    /// from
+    pub(crate) fn legend_group(&self) -> Option<&String> {
+        self.group.as_ref().or(self.source.as_ref())
+    }
+
+    /// "port 1" for a reflection, "port 1 → port 2" for S21, `None` without a port
+    pub(crate) fn port_label(&self, locale: &Locale) -> Option<String> {
+        let (i, j) = self.port?;
+        let port = &locale.port;
+        Some(if i == j {
+            format!("{} {}", port, i + 1)
+        } else {
+            format!("{} {} → {} {}", port, j + 1, port, i + 1)
+        })
+    }
+
+    /// Frequency of each sample in Hz
+    pub fn frequencies(mut self, frequencies: Vec<f64>) -> Self {
+        self.frequencies = frequencies;
+        self
+    }
+
+    /// Only draw and analyze the samples from `range.start()` to `range.end()` Hz,
+    /// without trimming the data beforehand. Traces without frequencies are left whole.
+    pub fn frequency_range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.frequency_range = Some(range);
+        self
+    }
+
+    /// The trace without the samples outside its [`Trace::frequency_range`], as the
+    /// chart draws and analyzes it. The range is kept, so trimming again changes nothing:
+    /// ```
+    /// # use egui_smith_chart::{Complex, Trace};
+    /// let trace = Trace::new("s11", vec![Complex::new(0.5, 0.0); 4])
+    ///     .frequencies(vec![1e9, 2e9, 3e9, 4e9])
+    ///     .frequency_range(1.5e9..=3e9);
+    /// let trimmed = trace.trim();
+    /// assert_eq!(trimmed.frequencies, vec![2e9, 3e9]);
+    /// assert_eq!(trimmed.frequency_range, Some(1.5e9..=3e9));
+    /// ```
+    pub fn trim(mut self) -> Self {
+        let range = match self.frequency_range.clone() {
+            Some(range) if self.is_swept() => range,
+            _ => return self,
+        };
+        (self.gamma, self.frequencies) = (self.gamma.iter())
+            .zip(&self.frequencies)
+            .filter(|(_, f)| range.contains(f))
+            .map(|(&gamma, &f)| (gamma, f))
+            .unzip();
+        self
+    }
+
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Opacity of the line from 0 (invisible) to 1 (opaque, the default). While another
+    /// trace is selected this is multiplied by
+    /// [`crate::SmithChartStyle::unselected_opacity`].
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    /// How markers between samples are placed
+    pub fn interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Place a marker at `frequency`, or at a fractional sample index if the trace
+    /// has no frequencies. The marker follows the value given on each frame.
+    pub fn marker_at(mut self, frequency: f64) -> Self {
+        self.markers.push(frequency);
+        self
+    }
+
+    /// Attach `annotation` to the trace
+    pub fn annotation(mut self, annotation: Annotation) -> Self {
+        self.annotations.push(annotation);
+        self
+    }
+
+    /// Label the point at `frequency` with `text` and a leader arrow
+    pub fn annotate(self, frequency: f64, text: impl Into<String>) -> Self {
+        self.annotation(Annotation::new(frequency, text))
+    }
+
+    /// Label the sweep with small ticks at regular frequencies, e.g. every 100 MHz with
+    /// `FrequencyTicks::Step(100e6)`. Unlike markers they can't be selected or dragged.
+    pub fn frequency_ticks(mut self, ticks: FrequencyTicks) -> Self {
+        self.frequency_ticks = Some(ticks);
+        self
+    }
+
+    /// Spread automatic frequency ticks by decade with [`FrequencyScale::Log`], so a
+    /// sweep over several decades gets ticks in each of them
+    pub fn frequency_scale(mut self, scale: FrequencyScale) -> Self {
+        self.frequency_scale = scale;
+        self
+    }
+
+    /// Frequency of sample `index`, if known
+    pub fn frequency_at(&self, index: usize) -> Option<f64> {
+        self.frequencies.get(index).copied()
+    }
+
+    /// Sweep coordinate of sample `index`: its frequency, or the index itself if the
+    /// trace has no frequencies
+    pub fn sweep_at(&self, index: usize) -> f64 {
+        if self.is_swept() {
+            self.frequencies[index]
+        } else {
+            index as f64
+        }
+    }
+
+    /// Whether the samples are reflection coefficients, not a transmission
+    /// S-parameter or a [`Trace::derived`] trace
+    pub(crate) fn is_reflection(&self) -> bool {
+        !self.derived && !matches!(self.port, Some((i, j)) if i != j)
+    }
+
+    /// Whether every sample has a frequency
+    pub(crate) fn is_swept(&self) -> bool {
+        !self.frequencies.is_empty() && self.frequencies.len() == self.gamma.len()
+    }
+
+    /// Range of the sweep coordinate, `None` for an empty trace
+    pub fn sweep_range(&self) -> Option<RangeInclusive<f64>> {
+        let last = self.gamma.len().checked_sub(1)?;
+        Some(self.sweep_at(0)..=self.sweep_at(last))
+    }
+
+    /// Deviation from `reference`, compared at the sweep coordinates of this trace's
+    /// samples that fall within the reference sweep. `None` if there are no such samples.
+    pub fn deviation(&self, reference: &Trace) -> Option<Deviation> {
+        let mut deviation = Deviation {
+            max: 0.0,
+            mean: 0.0,
+            max_sweep: 0.0,
+        };
+        let mut count = 0;
+        for (i, gamma) in self.gamma.iter().enumerate() {
+            let sweep = self.sweep_at(i);
+            if let Some(expected) = reference.gamma_at(sweep) {
+                let distance = (gamma - expected).norm();
+                if distance >= deviation.max {
+                    deviation.max = distance;
+                    deviation.max_sweep = sweep;
+                }
+                deviation.mean += distance;
+                count += 1;
+            }
+        }
+        (count > 0).then(|| Deviation {
+            mean: deviation.mean / count as f32,
+            ..deviation
+        })
+    }
+
+    /// Point-wise difference Γ − Γ<sub>reference</sub> at this trace's samples within the
+    /// reference sweep, with the reference interpolated between its samples, e.g. after
+    /// minus before tuning:
+    /// ```
+    /// # use egui_smith_chart::{Complex, Trace};
+    /// let before = Trace::new("before", vec![Complex::new(0.5, 0.0), Complex::new(0.3, 0.0)])
+    ///     .frequencies(vec![1e9, 2e9]);
+    /// let after = Trace::new("after", vec![Complex::new(0.25, 0.0)]).frequencies(vec![1.5e9]);
+    /// assert_eq!(after.difference(&before).gamma, vec![Complex::new(-0.15, 0.0)]);
+    /// ```
+    /// Both traces are taken to share a reference impedance, as they do once added to a
+    /// chart.
+    pub fn difference(&self, reference: &Trace) -> Trace {
+        let name = format!("{} − {}", self.name, reference.name);
+        self.combine(reference, name, |gamma, expected| gamma - expected)
+    }
+
+    /// Point-wise ratio Γ / Γ<sub>reference</sub>, like [`Trace::difference`]
+    pub fn ratio(&self, reference: &Trace) -> Trace {
+        let name = format!("{} / {}", self.name, reference.name);
+        self.combine(reference, name, |gamma, expected| gamma / expected)
+    }
+
+    /// Trace of `op` applied to each sample and the reference at its sweep coordinate,
+    /// leaving out samples outside the reference sweep
+    fn combine(
+        &self,
+        reference: &Trace,
+        name: String,
+        op: impl Fn(Complex<f32>, Complex<f32>) -> Complex<f32>,
+    ) -> Trace {
+        let (mut gamma, mut frequencies) = (Vec::new(), Vec::new());
+        for (i, &sample) in self.gamma.iter().enumerate() {
+            if let Some(expected) = reference.gamma_at(self.sweep_at(i)) {
+                gamma.push(op(sample, expected));
+                frequencies.extend(self.frequency_at(i).filter(|_| self.is_swept()));
+            }
+        }
+        // not a reflection coefficient of any reference, so never renormalized
+        let mut trace = Trace::new(name, gamma).frequencies(frequencies);
+        trace.derived = true;
+        trace
+    }
+
+    /// VSWR extremes and best match over the finite samples, `None` if there are none:
+    /// ```
+    /// # use egui_smith_chart::{Complex, Trace};
+    /// let trace = Trace::new("s11", vec![Complex::new(0.5, 0.0), Complex::new(0.0, 0.2)])
+    ///     .frequencies(vec![1e9, 2e9]);
+    /// let stats = trace.stats().unwrap();
+    /// assert_eq!((stats.max_vswr, stats.best_match_sweep), (3.0, 2e9));
+    /// ```
+    pub fn stats(&self) -> Option<TraceStats> {
+        let mut samples = self
+            .gamma
+            .iter()
+            .enumerate()
+            .filter(|(_, gamma)| gamma.is_finite())
+            .map(|(i, gamma)| (i, gamma.norm()));
+        let (first, magnitude) = samples.next()?;
+        let (mut best, mut min, mut max) = (first, magnitude, magnitude);
+        for (i, magnitude) in samples {
+            if magnitude < min {
+                (best, min) = (i, magnitude);
+            }
+            max = max.max(magnitude);
+        }
+        Some(TraceStats {
+            min_vswr: vswr(min),
+            max_vswr: vswr(max),
+            best_match_index: best,
+            best_match: self.gamma[best],
+            best_match_sweep: self.sweep_at(best),
+        })
+    }
+
+    /// Where the reactance crosses zero, in sweep order. Each crossing is interpolated
+    /// between the two samples around it:
+    /// ```
+    /// # use egui_smith_chart::{Complex, ResonanceKind, Trace};
+    /// let z = [Complex::new(0.4, -0.01), Complex::new(0.4, 0.01)];
+    /// let trace = Trace::from_impedance("rlc", &z, Complex::new(1.0, 0.0))
+    ///     .frequencies(vec![1e9, 3e9]);
+    /// let resonance = trace.resonances()[0];
+    /// assert_eq!(resonance.kind, ResonanceKind::Series);
+    /// assert!((resonance.frequency - 2e9).abs() < 1e3);
+    /// assert!((resonance.resistance - 0.4).abs() < 1e-3);
+    /// ```
+    /// Reactances falling through zero are parallel resonances, including lossless ones
+    /// where the reactance jumps through infinity at the open circuit. Their resistance
+    /// is the one of the interpolated crossing, infinite on a sample at the open circuit:
+    /// ```
+    /// # use egui_smith_chart::{Complex, ResonanceKind, Trace};
+    /// let gamma = [Complex::from_polar(1.0, 0.1), Complex::from_polar(1.0, -0.1)];
+    /// let resonance = Trace::new("tank", gamma.to_vec()).resonances()[0];
+    /// assert_eq!(resonance.kind, ResonanceKind::Parallel);
+    /// assert!(resonance.resistance > 100.0);
+    ///
+    /// let open = [Complex::new(1.0, 0.0), Complex::from_polar(1.0, -0.1)];
+    /// let resonance = Trace::new("tank", open.to_vec()).resonances()[0];
+    /// assert_eq!(resonance.resistance, f32::INFINITY);
+    /// ```
+    pub fn resonances(&self) -> Vec<Resonance> {
+        let mut resonances = Vec::new();
+        for (i, pair) in self.gamma.windows(2).enumerate() {
+            let (a, b) = (pair[0], pair[1]);
+            // Im(Γ) has the sign of the reactance
+            if !a.is_finite() || !b.is_finite() || a.im.signum() == b.im.signum() {
+                continue;
+            }
+            let t = a.im / (a.im - b.im);
+            let z = math::gamma_to_z(a + (b - a) * t);
+            let (x0, x1) = (self.sweep_at(i), self.sweep_at(i + 1));
+            resonances.push(Resonance {
+                frequency: x0 + (x1 - x0) * t as f64,
+                kind: if b.im > a.im {
+                    ResonanceKind::Series
+                } else {
+                    ResonanceKind::Parallel
+                },
+                resistance: if z.is_finite() { z.re } else { f32::INFINITY },
+            });
+        }
+        resonances
+    }
+
+    /// Reflection coefficient at sweep coordinate `sweep`, interpolated between the
+    /// surrounding samples. `None` outside the swept range.
+    pub fn gamma_at(&self, sweep: f64) -> Option<Complex<f32>> {
+        let range = self.sweep_range()?;
+        if !range.contains(&sweep) {
+            return None;
+        }
+        let last = self.gamma.len() - 1;
+        if last == 0 {
+            return Some(self.gamma[0]);
+        }
+
+        // first segment ending at or after `sweep`
+        let i = if self.is_swept() {
+            self.frequencies
+                .partition_point(|f| *f < sweep)
+                .clamp(1, last)
+                - 1
+        } else {
+            (sweep.floor() as usize).min(last - 1)
+        };
+        let (x0, x1) = (self.sweep_at(i), self.sweep_at(i + 1));
+        let t = if x1 > x0 {
+            ((sweep - x0) / (x1 - x0)) as f32
+        } else {
+            0.0
+        };
+
+        let (p1, p2) = (self.gamma[i], self.gamma[i + 1]);
+        Some(match self.interpolation {
+            Interpolation::Linear => p1 + (p2 - p1) * t,
+            Interpolation::Spline => {
+                let p0 = self.gamma[i.saturating_sub(1)];
+                let p3 = self.gamma[(i + 2).min(last)];
+                let (t2, t3) = (t * t, t * t * t);
+                (p1 * 2.0
+                    + (p2 - p0) * t
+                    + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+                    + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+                    * 0.5
+            }
+        })
+    }
+}
+
+/// Trace sample found near a screen position
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceHit {
+    /// Index of the trace in the order it was added to the chart
+    pub trace: usize,
+    /// Index of the sample within the trace
+    pub index: usize,
+    /// Screen distance from the queried position in points
+    pub distance: f32,
+    /// Frequency of the sample, if known
+    pub frequency: Option<f64>,
+    /// Reflection coefficient of the sample
+    pub gamma: Complex<f32>,
+}
+
+/// Summary of a trace's match, see [`Trace::stats`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TraceStats {
+    /// Smallest VSWR, infinite if every sample is on or outside the unit circle
+    pub min_vswr: f32,
+    /// Largest VSWR, infinite if any sample is on or outside the unit circle
+    pub max_vswr: f32,
+    /// Index of the sample with the smallest |Γ|
+    pub best_match_index: usize,
+    /// Reflection coefficient of the best match
+    pub best_match: Complex<f32>,
+    /// Sweep coordinate of the best match
+    pub best_match_sweep: f64,
+}
+impl TraceStats {
+    /// Return loss of the best match in dB
+    pub fn return_loss(&self) -> f32 {
+        -20.0 * self.best_match.norm().log10()
+    }
+}
+
+/// Zero crossing of the reactance of a trace, see [`Trace::resonances`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Resonance {
+    /// Sweep coordinate of the crossing
+    pub frequency: f64,
+    pub kind: ResonanceKind,
+    /// Resistance at resonance, normalized to the reference impedance of the trace
+    pub resistance: f32,
+}
+
+/// Direction of a reactance zero crossing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResonanceKind {
+    /// Reactance rising through zero, like a series LC circuit
+    Series,
+    /// Reactance falling through zero, like a parallel LC circuit
+    Parallel,
+}
+impl std::fmt::Display for ResonanceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Series => "series",
+            Self::Parallel => "parallel",
+        })
+    }
+}
+
+/// How a [`crate::SmithChart::compare`] comparison is drawn
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ComparisonLocus {
+    /// Lines connecting corresponding points of measurement and reference
+    #[default]
+    Whiskers,
+    /// An extra trace of Γ − Γ<sub>reference</sub>, see [`Trace::difference`]
+    Difference,
+    /// An extra trace of Γ / Γ<sub>reference</sub>, see [`Trace::ratio`]
+    Ratio,
+}
+
+/// How far a measured trace is from a reference, see [`Trace::deviation`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Deviation {
+    /// Largest |ΔΓ|
+    pub max: f32,
+    /// Mean |ΔΓ|
+    pub mean: f32,
+    /// Sweep coordinate of the largest deviation
+    pub max_sweep: f64,
+}
+
+/// VSWR of a reflection coefficient magnitude, infinite from |Γ| = 1 on
+pub(crate) fn vswr(magnitude: f32) -> f32 {
+    if magnitude >= 1.0 {
+        f32::INFINITY
+    } else {
+        (1.0 + magnitude) / (1.0 - magnitude)
+    }
+}
@@ -0,0 +1,147 @@
+//! Mapping between the reflection coefficient plane and the screen.
+
+use egui::{pos2, Pos2, Rect};
+
+use crate::{Complex, Trace, TraceHit};
+
+/// Maps reflection coefficients to screen positions of a chart.
+///
+/// Without zoom the unit circle |Γ| = 1 touches the edges of the chart rect,
+/// positive reactance is drawn in the upper half.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChartTransform {
+    rect: Rect,
+
+    /// Reflection coefficient at the center of the rect
+    center: Complex<f32>,
+
+    /// Magnification, 1 shows the whole chart
+    zoom: f32,
+}
+impl ChartTransform {
+    pub fn new(rect: Rect) -> Self {
+        Self::with_view(rect, Complex::new(0.0, 0.0), 1.0)
+    }
+
+    /// Transform showing the gamma plane around `center` magnified by `zoom`
+    pub fn with_view(rect: Rect, center: Complex<f32>, zoom: f32) -> Self {
+        Self { rect, center, zoom }
+    }
+
+    /// Screen rect of the chart
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Reflection coefficient at the center of the rect
+    pub fn center(&self) -> Complex<f32> {
+        self.center
+    }
+
+    /// Magnification, 1 shows the whole chart
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Screen position of reflection coefficient `gamma`
+    pub fn gamma_to_screen(&self, gamma: &Complex<f32>) -> Pos2 {
+        let view = (gamma - self.center) * self.zoom;
+        let x_normalized = (view.re + 1.0) / 2.0;
+        let y_normalized = (view.im + 1.0) / 2.0;
+        let origin = self.rect.left_top();
+        pos2(
+            origin.x + x_normalized * self.rect.width(),
+            origin.y + (1.0 - y_normalized) * self.rect.height(),
+        )
+    }
+
+    /// Reflection coefficient at screen position `pos`
+    pub fn screen_to_gamma(&self, pos: Pos2) -> Complex<f32> {
+        let origin = self.rect.left_top();
+        let view = Complex {
+            re: (pos.x - origin.x) / self.rect.width() * 2.0 - 1.0,
+            im: -(pos.y - origin.y) / self.rect.height() * 2.0 + 1.0,
+        };
+        view / self.zoom + self.center
+    }
+
+    /// Screen length of distance `length` in the gamma plane
+    pub fn scale(&self, length: f32) -> f32 {
+        length * self.zoom * self.rect.width() / 2.0
+    }
+
+    /// Sample of `traces` closest to screen position `pos`
+    pub fn nearest_trace_point(&self, traces: &[Trace], pos: Pos2) -> Option<TraceHit> {
+        traces
+            .iter()
+            .enumerate()
+            .filter(|(_, trace)| trace.visible)
+            .filter_map(|(i, trace)| {
+                let (index, distance) = self.nearest_sample(trace, pos)?;
+                Some(TraceHit {
+                    trace: i,
+                    index,
+                    distance,
+                    frequency: trace.frequency_at(index),
+                    gamma: trace.gamma[index],
+                })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /// Index and screen distance of the visible trace whose line passes closest to
+    /// `pos`, between samples as well as at them
+    pub(crate) fn nearest_trace(&self, traces: &[Trace], pos: Pos2) -> Option<(usize, f32)> {
+        traces
+            .iter()
+            .enumerate()
+            .filter(|(_, trace)| trace.visible)
+            .filter_map(|(i, trace)| Some((i, self.nearest_segment(trace, pos)?.2)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Sweep coordinate of the point on `trace` closest to `pos`, with the samples
+    /// connected by straight lines
+    pub(crate) fn nearest_sweep(&self, trace: &Trace, pos: Pos2) -> Option<f64> {
+        let (i, t, _) = self.nearest_segment(trace, pos)?;
+        if trace.gamma.len() == 1 {
+            return Some(trace.sweep_at(0));
+        }
+        let (x0, x1) = (trace.sweep_at(i), trace.sweep_at(i + 1));
+        Some(x0 + (x1 - x0) * t as f64)
+    }
+
+    /// Segment of `trace` closest to `pos`: index of its first sample, position of the
+    /// closest point along it from 0 to 1 and its screen distance
+    fn nearest_segment(&self, trace: &Trace, pos: Pos2) -> Option<(usize, f32, f32)> {
+        if trace.gamma.len() == 1 {
+            return Some((0, 0.0, self.gamma_to_screen(&trace.gamma[0]).distance(pos)));
+        }
+        trace
+            .gamma
+            .windows(2)
+            .enumerate()
+            .map(|(i, segment)| {
+                let a = self.gamma_to_screen(&segment[0]);
+                let b = self.gamma_to_screen(&segment[1]);
+                let ab = b - a;
+                let t = if ab.length_sq() > 0.0 {
+                    ((pos - a).dot(ab) / ab.length_sq()).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                (i, t, (a + ab * t).distance(pos))
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+    }
+
+    /// Index and screen distance of the sample of `trace` closest to `pos`
+    pub(crate) fn nearest_sample(&self, trace: &Trace, pos: Pos2) -> Option<(usize, f32)> {
+        trace
+            .gamma
+            .iter()
+            .map(|gamma| self.gamma_to_screen(gamma).distance(pos))
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
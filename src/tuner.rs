@@ -0,0 +1,251 @@
+//! Virtual antenna tuner: a matching network with variable elements.
+
+use std::f64::consts::TAU;
+use std::ops::RangeInclusive;
+
+use egui::{Response, Ui};
+
+use crate::{Complex, NumberFormat, Trace};
+
+/// Arrangement of the tuner elements
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    /// Series inductor at the load, shunt capacitor at the source, both variable
+    L,
+    /// Variable series capacitors on either side of a fixed shunt inductor
+    T,
+    /// Variable shunt capacitors on either side of a fixed series inductor
+    Pi,
+}
+impl std::fmt::Display for Topology {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::L => "L",
+            Self::T => "T",
+            Self::Pi => "Pi",
+        })
+    }
+}
+
+/// Kind of a tuner element
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Component {
+    /// Value in farads
+    Capacitor,
+    /// Value in henries
+    Inductor,
+}
+
+/// One element of a [`Tuner`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub component: Component,
+    /// In series with the line, otherwise across it
+    pub series: bool,
+    pub value: f64,
+    /// Range of the slider of a variable element
+    pub range: RangeInclusive<f64>,
+    pub variable: bool,
+}
+impl Element {
+    fn new(
+        name: &str,
+        component: Component,
+        series: bool,
+        range: RangeInclusive<f64>,
+        variable: bool,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            component,
+            series,
+            // geometric middle of the range
+            value: (range.start() * range.end()).sqrt(),
+            range,
+            variable,
+        }
+    }
+
+    /// Impedance in ohms at `frequency` in Hz
+    pub fn impedance(&self, frequency: f64) -> Complex<f64> {
+        let w = TAU * frequency;
+        match self.component {
+            Component::Capacitor => Complex::new(0.0, -1.0 / (w * self.value)),
+            Component::Inductor => Complex::new(0.0, w * self.value),
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self.component {
+            Component::Capacitor => "F",
+            Component::Inductor => "H",
+        }
+    }
+}
+
+/// Matching network between a load and the source, whose variable elements are set
+/// with sliders while the chart shows the transformed load:
+/// ```
+/// # use egui_smith_chart::{SmithChart, Topology, Trace, Tuner};
+/// # fn ui(ui: &mut egui::Ui, tuner: &mut Tuner, antenna: &Trace) {
+/// tuner.ui(ui);
+/// SmithChart::new("tuner")
+///     .trace(antenna.clone())
+///     .trace(tuner.apply(antenna, egui_smith_chart::Complex::new(50.0, 0.0)))
+///     .show(ui);
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tuner {
+    pub topology: Topology,
+    /// Elements in order from the load to the source
+    pub elements: Vec<Element>,
+    /// Frequency in Hz for loads without frequencies
+    pub frequency: f64,
+}
+impl Tuner {
+    /// Tuner with typical HF element ranges
+    pub fn new(topology: Topology, frequency: f64) -> Self {
+        use Component::*;
+        let (pf, nh) = (1e-12..=1e-9, 10e-9..=10e-6);
+        let elements = match topology {
+            Topology::L => vec![
+                Element::new("L", Inductor, true, nh, true),
+                Element::new("C", Capacitor, false, pf, true),
+            ],
+            Topology::T => vec![
+                Element::new("C2", Capacitor, true, pf.clone(), true),
+                Element::new("L", Inductor, false, nh, false),
+                Element::new("C1", Capacitor, true, pf, true),
+            ],
+            Topology::Pi => vec![
+                Element::new("C2", Capacitor, false, pf.clone(), true),
+                Element::new("L", Inductor, true, nh, false),
+                Element::new("C1", Capacitor, false, pf, true),
+            ],
+        };
+        Self {
+            topology,
+            elements,
+            frequency,
+        }
+    }
+
+    /// Impedance in ohms seen by the source with `load` in ohms at `frequency` in Hz
+    pub fn input_impedance(&self, load: Complex<f32>, frequency: f64) -> Complex<f32> {
+        let load = Complex::new(load.re as f64, load.im as f64);
+        let z = self.elements.iter().fold(load, |z, element| {
+            let element_z = element.impedance(frequency);
+            if element.series {
+                z + element_z
+            } else {
+                z * element_z / (z + element_z)
+            }
+        });
+        Complex::new(z.re as f32, z.im as f32)
+    }
+
+    /// `load` as seen through the tuner. Samples are taken at their own frequency,
+    /// or [`Tuner::frequency`] for loads without a frequency for every sample. `z0` is
+    /// the reference of loads that don't have their own.
+    pub fn apply(&self, load: &Trace, z0: Complex<f32>) -> Trace {
+        let z0 = load.z0.unwrap_or(z0);
+        let swept = load.is_swept();
+        let gamma: Vec<_> = load
+            .gamma
+            .iter()
+            .enumerate()
+            .map(|(i, gamma)| {
+                let frequency = if swept {
+                    load.frequencies[i]
+                } else {
+                    self.frequency
+                };
+                let z = self.input_impedance(z0 * (1.0 + gamma) / (1.0 - gamma), frequency);
+                (z - z0) / (z + z0)
+            })
+            .collect();
+        Trace::new(format!("{} (tuned)", load.name), gamma)
+            .frequencies(load.frequencies.clone())
+            .z0(z0)
+    }
+
+    /// Locus of the input impedance as element number `element` is swept over
+    /// `values` at [`Tuner::frequency`], with `load` in ohms and the other elements
+    /// at their current values. `labels` evenly spaced points, ends included, are
+    /// annotated with their element value. `None` if the tuner has no element number
+    /// `element`.
+    /// ```
+    /// # use egui_smith_chart::{Topology, Tuner};
+    /// # use egui_smith_chart::Complex;
+    /// let tuner = Tuner::new(Topology::L, 100e6);
+    /// // what the shunt capacitor can reach
+    /// let (load, z0) = (Complex::new(20.0, -15.0), Complex::new(50.0, 0.0));
+    /// let reach = tuner.sweep(1, 1e-12..=10e-12, load, z0, 4).unwrap();
+    /// assert_eq!(reach.annotations.len(), 4);
+    /// // an L network has two elements
+    /// assert!(tuner.sweep(2, 1e-12..=10e-12, load, z0, 4).is_none());
+    /// ```
+    pub fn sweep(
+        &self,
+        element: usize,
+        values: RangeInclusive<f64>,
+        load: Complex<f32>,
+        z0: Complex<f32>,
+        labels: usize,
+    ) -> Option<Trace> {
+        const SAMPLES: usize = 101;
+        let swept = self.elements.get(element)?;
+        let mut tuner = self.clone();
+        let value_at = |i: f64| values.start() + (values.end() - values.start()) * i;
+        let gamma: Vec<_> = (0..SAMPLES)
+            .map(|i| {
+                tuner.elements[element].value = value_at(i as f64 / (SAMPLES - 1) as f64);
+                let z = tuner.input_impedance(load, self.frequency);
+                (z - z0) / (z + z0)
+            })
+            .collect();
+
+        // without frequencies the annotations are placed by sample index
+        let format = NumberFormat::default();
+        let unit = swept.unit();
+        let mut trace = Trace::new(format!("{} sweep", swept.name), gamma);
+        for i in 0..labels {
+            let fraction = i as f64 / labels.saturating_sub(1).max(1) as f64;
+            trace = trace.annotate(
+                fraction * (SAMPLES - 1) as f64,
+                format.eng(value_at(fraction) as f32, unit),
+            );
+        }
+        Some(trace.z0(z0))
+    }
+
+    /// Topology selector and sliders for the variable elements
+    pub fn ui(&mut self, ui: &mut Ui) -> Response {
+        let format = NumberFormat::default();
+        let mut topology = self.topology;
+        let mut response = egui::ComboBox::from_label("Topology")
+            .selected_text(topology.to_string())
+            .show_ui(ui, |ui| {
+                for option in [Topology::L, Topology::T, Topology::Pi] {
+                    ui.selectable_value(&mut topology, option, option.to_string());
+                }
+            })
+            .response;
+        if topology != self.topology {
+            *self = Self::new(topology, self.frequency);
+            response.mark_changed();
+        }
+        for element in self.elements.iter_mut().filter(|e| e.variable).rev() {
+            let unit = element.unit();
+            response |= ui.add(
+                egui::Slider::new(&mut element.value, element.range.clone())
+                    .logarithmic(true)
+                    .custom_formatter(|value, _| format.eng(value as f32, unit))
+                    .text(&element.name),
+            );
+        }
+        response
+    }
+}
@@ -0,0 +1,87 @@
+//! Walking painted shapes for the vector exports, shared by [`crate::pdf`] and
+//! [`crate::svg`].
+
+use egui::epaint::{ClippedShape, PathShape, TextShape};
+use egui::{Color32, Pos2, Rect, Shape, Stroke};
+
+/// Drawing operations of a vector format. Fills and strokes that are invisible are
+/// passed on, for the format to skip.
+pub(crate) trait Backend {
+    /// Path through `points`, back to the first one when `closed`
+    fn path(&mut self, points: &[Pos2], closed: bool, fill: Color32, stroke: Stroke);
+    fn circle(&mut self, center: Pos2, radius: f32, fill: Color32, stroke: Stroke);
+    fn rect(&mut self, rect: Rect, rounding: f32, fill: Color32, stroke: Stroke);
+    /// Cubic Bézier curve, quadratic ones are raised to cubic before
+    fn cubic(&mut self, points: [Pos2; 4], closed: bool, fill: Color32, stroke: Stroke);
+    fn text(&mut self, text: &TextShape);
+    /// Clip the following operations to `clip` until [`Backend::end_clip`]
+    fn begin_clip(&mut self, clip: Rect);
+    fn end_clip(&mut self);
+}
+
+/// Hand `shapes` to `backend`, clipping those whose clip rect doesn't cover `page`
+pub(crate) fn walk(backend: &mut impl Backend, shapes: &[ClippedShape], page: Rect) {
+    for ClippedShape(clip, shape) in shapes {
+        let clipped = !clip.contains_rect(page);
+        if clipped {
+            backend.begin_clip(*clip);
+        }
+        self::shape(backend, shape);
+        if clipped {
+            backend.end_clip();
+        }
+    }
+}
+
+fn shape(backend: &mut impl Backend, shape: &Shape) {
+    match shape {
+        Shape::Vec(shapes) => shapes.iter().for_each(|shape| self::shape(backend, shape)),
+        Shape::LineSegment { points, stroke } => {
+            backend.path(points, false, Color32::TRANSPARENT, *stroke)
+        }
+        Shape::Path(PathShape {
+            points,
+            closed,
+            fill,
+            stroke,
+        }) => {
+            let fill = if *closed { *fill } else { Color32::TRANSPARENT };
+            backend.path(points, *closed, fill, *stroke);
+        }
+        Shape::Circle(circle) => {
+            backend.circle(circle.center, circle.radius, circle.fill, circle.stroke)
+        }
+        Shape::Rect(rect) => backend.rect(rect.rect, rect.rounding.nw, rect.fill, rect.stroke),
+        Shape::QuadraticBezier(bezier) => {
+            let [p0, p1, p2] = bezier.points;
+            let c1 = p0 + (p1 - p0) * (2.0 / 3.0);
+            let c2 = p2 + (p1 - p2) * (2.0 / 3.0);
+            backend.cubic([p0, c1, c2, p2], bezier.closed, bezier.fill, bezier.stroke);
+        }
+        Shape::CubicBezier(bezier) => {
+            backend.cubic(bezier.points, bezier.closed, bezier.fill, bezier.stroke)
+        }
+        // flat-shaded triangles colored by their first vertex, leaving out textured
+        // meshes such as images
+        Shape::Mesh(mesh) if mesh.texture_id == egui::TextureId::default() => {
+            for triangle in mesh.indices.chunks_exact(3) {
+                let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize]);
+                if a.color.a() > 0 {
+                    backend.path(&[a.pos, b.pos, c.pos], true, a.color, Stroke::none());
+                }
+            }
+        }
+        Shape::Text(text) => backend.text(text),
+        Shape::Mesh(_) | Shape::Noop | Shape::Callback(_) => {}
+    }
+}
+
+/// `value` with at most two decimals, as PDF has no exponent notation
+pub(crate) fn num(value: f32) -> String {
+    let s = format!("{:.2}", value);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    match s {
+        "-0" | "" => "0".to_string(),
+        s => s.to_string(),
+    }
+}
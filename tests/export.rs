@@ -0,0 +1,103 @@
+//! Structure tests of the PDF, SVG and image exports.
+//!
+//! The documents are checked for what readers rely on, the header, the cross-reference
+//! table and the drawing operators, and images for a few known pixels, rather than
+//! compared byte by byte.
+
+use egui::{Color32, Context, Id};
+use egui_smith_chart::{Complex, SmithChart, SmithChartState, SmithChartStyle, Trace};
+
+const TRACE_COLOR: Color32 = Color32::from_rgb(0x12, 0x34, 0x56);
+
+fn chart() -> SmithChart<'static> {
+    let gamma = vec![
+        Complex::new(0.5, 0.0),
+        Complex::new(0.0, 0.5),
+        Complex::new(-0.3, -0.2),
+    ];
+    SmithChart::new("export").trace(Trace::new("load", gamma).color(TRACE_COLOR))
+}
+
+/// Position of the first occurrence of `needle` in `haystack`
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[test]
+fn pdf_structure() {
+    let pdf = chart().export_pdf(&Context::default(), 200.0);
+    assert!(pdf.starts_with(b"%PDF-1.4\n"));
+    assert!(pdf.ends_with(b"%%EOF\n"));
+
+    // startxref points at the table, whose entries point at their objects
+    let start = find(&pdf, b"startxref\n").unwrap() + b"startxref\n".len();
+    let end = start + find(&pdf[start..], b"\n").unwrap();
+    let xref: usize = std::str::from_utf8(&pdf[start..end])
+        .unwrap()
+        .parse()
+        .unwrap();
+    let table = std::str::from_utf8(&pdf[xref..]).unwrap();
+    let mut lines = table.lines();
+    assert_eq!(lines.next(), Some("xref"));
+    let count: usize = lines.next().unwrap()["0 ".len()..].parse().unwrap();
+    assert_eq!(lines.next(), Some("0000000000 65535 f "));
+    for object in 1..count {
+        let entry = lines.next().unwrap();
+        let offset: usize = entry[..10].parse().unwrap();
+        let header = format!("{} 0 obj\n", object);
+        assert!(
+            pdf[offset..].starts_with(header.as_bytes()),
+            "object {} isn't at offset {}",
+            object,
+            offset
+        );
+    }
+
+    // the grid and the trace are stroked paths
+    for operator in [&b" m\n"[..], b" l\n", b"\nS\n"] {
+        assert!(find(&pdf, operator).is_some());
+    }
+}
+
+#[test]
+fn svg_structure() {
+    let svg = chart().export_svg(&Context::default(), 200.0);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.ends_with("</svg>\n"));
+    assert!(svg.contains("<path d=\"M"));
+    assert!(svg.contains("#123456"));
+}
+
+#[test]
+fn hidden_traces_stay_hidden() {
+    let ctx = Context::default();
+    let mut state = SmithChartState::default();
+    state.hidden_traces = vec![0];
+    state.store(&ctx, Id::new("export"));
+
+    let svg = chart().export_svg(&ctx, 200.0);
+    assert!(!svg.contains("#123456"));
+}
+
+#[test]
+fn image_pixels() {
+    let background = Color32::from_rgb(0x20, 0x40, 0x60);
+    let style = SmithChartStyle {
+        background: Some(background),
+        ..Default::default()
+    };
+    let trace = Trace::new("load", vec![Complex::new(0.2, 0.6), Complex::new(0.6, 0.2)])
+        .color(TRACE_COLOR)
+        .width(6.0);
+    let mut chart = SmithChart::new("image").style(style).trace(trace);
+
+    let image = chart.export_image(&Context::default(), 64.0);
+    assert_eq!(image.size, [64, 64]);
+    assert_eq!(image.pixels.len(), 64 * 64);
+    // the corners are outside the unit circle, the trace is painted opaque over the grid
+    assert_eq!(image[(0, 0)], background);
+    assert_eq!(image[(63, 63)], background);
+    assert!(image.pixels.contains(&TRACE_COLOR));
+}
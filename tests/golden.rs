@@ -0,0 +1,152 @@
+//! Golden-image tests of the static chart rendering.
+//!
+//! The chart is painted offscreen with [`SmithChart::paint_static`], tessellated by
+//! egui and rasterized into a grayscale image which is compared against the
+//! reference in `tests/snapshots/`. Run with `UPDATE_SNAPSHOTS=1` to rewrite the
+//! references after an intentional change.
+
+use std::path::PathBuf;
+
+use egui::epaint::{Primitive, Vertex};
+use egui::{Color32, Context, LayerId, Painter, Pos2, RawInput, Rect};
+use egui_smith_chart::{SmithChart, SmithChartState, SmithChartStyle};
+
+const SIZE: usize = 256;
+
+/// Share of pixels allowed to differ before a snapshot is considered broken
+const MAX_DIFFERING: f32 = 0.005;
+
+/// Paint `chart` into a `SIZE` x `SIZE` grayscale image
+fn render(chart: &SmithChart) -> Vec<u8> {
+    render_in(&Context::default(), chart)
+}
+
+/// Paint `chart` like [`render`] with `ctx`, which may have painted charts before
+fn render_in(ctx: &Context, chart: &SmithChart) -> Vec<u8> {
+    ctx.tessellation_options().feathering = false;
+
+    let rect = Rect::from_min_size(Pos2::ZERO, egui::vec2(SIZE as f32, SIZE as f32));
+    ctx.begin_frame(RawInput {
+        screen_rect: Some(rect),
+        pixels_per_point: Some(1.0),
+        ..Default::default()
+    });
+    chart.paint_static(
+        &Painter::new(ctx.clone(), LayerId::background(), rect),
+        rect,
+    );
+    let output = ctx.end_frame();
+
+    let mut image = vec![0.0f32; SIZE * SIZE];
+    for primitive in ctx.tessellate(output.shapes) {
+        if let Primitive::Mesh(mesh) = primitive.primitive {
+            for triangle in mesh.indices.chunks_exact(3) {
+                let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize]);
+                fill_triangle(&mut image, a, b, c);
+            }
+        }
+    }
+    image.iter().map(|v| (v * 255.0).round() as u8).collect()
+}
+
+/// Blend a flat-shaded triangle into `image`, sampling at pixel centers
+fn fill_triangle(image: &mut [f32], a: Vertex, b: Vertex, c: Vertex) {
+    let edge = |p: Pos2, q: Pos2, x: f32, y: f32| (q.x - p.x) * (y - p.y) - (q.y - p.y) * (x - p.x);
+    let area = edge(a.pos, b.pos, c.pos.x, c.pos.y);
+    if area == 0.0 {
+        return;
+    }
+
+    let color = a.color;
+    let alpha = color.a() as f32 / 255.0;
+    let luma =
+        (0.299 * color.r() as f32 + 0.587 * color.g() as f32 + 0.114 * color.b() as f32) / 255.0;
+
+    let min_x = a.pos.x.min(b.pos.x).min(c.pos.x).floor().max(0.0) as usize;
+    let max_x = a.pos.x.max(b.pos.x).max(c.pos.x).ceil().min(SIZE as f32) as usize;
+    let min_y = a.pos.y.min(b.pos.y).min(c.pos.y).floor().max(0.0) as usize;
+    let max_y = a.pos.y.max(b.pos.y).max(c.pos.y).ceil().min(SIZE as f32) as usize;
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let w = [
+                edge(b.pos, c.pos, px, py),
+                edge(c.pos, a.pos, px, py),
+                edge(a.pos, b.pos, px, py),
+            ];
+            if w.iter().all(|w| w * area >= 0.0) {
+                // colors are premultiplied
+                let pixel = &mut image[y * SIZE + x];
+                *pixel = luma + *pixel * (1.0 - alpha);
+            }
+        }
+    }
+}
+
+/// Compare `image` to the stored reference `name`, or store it when `UPDATE_SNAPSHOTS` is set
+fn check_snapshot(name: &str, image: &[u8]) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.pgm", name));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        let mut pgm = format!("P5\n{} {}\n255\n", SIZE, SIZE).into_bytes();
+        pgm.extend_from_slice(image);
+        std::fs::write(&path, pgm).unwrap();
+        return;
+    }
+
+    let pgm = std::fs::read(&path)
+        .unwrap_or_else(|e| panic!("{}: {}, run with UPDATE_SNAPSHOTS=1", path.display(), e));
+    let reference = &pgm[pgm.len() - SIZE * SIZE..];
+    let differing = image
+        .iter()
+        .zip(reference)
+        .filter(|(a, b)| a.abs_diff(**b) > 32)
+        .count();
+    assert!(
+        differing as f32 <= MAX_DIFFERING * (SIZE * SIZE) as f32,
+        "{} differs from its reference in {} pixels",
+        name,
+        differing
+    );
+}
+
+fn fixed_style() -> SmithChartStyle {
+    SmithChartStyle {
+        background: Some(Color32::BLACK),
+        grid_color: Some(Color32::WHITE),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn impedance_grid() {
+    let chart = SmithChart::new("golden").style(fixed_style());
+    check_snapshot("impedance_grid", &render(&chart));
+}
+
+#[test]
+fn high_contrast_grid() {
+    let chart = SmithChart::new("golden").style(SmithChartStyle::high_contrast(true));
+    check_snapshot("high_contrast_grid", &render(&chart));
+}
+
+#[test]
+fn independent_of_the_context() {
+    // a zoomed view stored for the chart and a grid cached with feathering stay out
+    let ctx = Context::default();
+    let chart = SmithChart::new("golden").style(fixed_style());
+    let mut state = SmithChartState::default();
+    state.zoom = 2.0;
+    state.store(&ctx, egui::Id::new("golden"));
+    let rect = Rect::from_min_size(Pos2::ZERO, egui::vec2(SIZE as f32, SIZE as f32));
+    ctx.begin_frame(RawInput::default());
+    chart.paint_at(
+        &Painter::new(ctx.clone(), LayerId::background(), rect),
+        rect,
+    );
+    let _ = ctx.end_frame();
+
+    check_snapshot("impedance_grid", &render_in(&ctx, &chart));
+}
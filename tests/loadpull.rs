@@ -0,0 +1,29 @@
+use egui_smith_chart::{loadpull, Complex};
+
+#[test]
+fn comma_separated() {
+    let text = "# 2.4 GHz load-pull\n\
+                gamma_mag, gamma_ang, pout_dbm, pae_pct\n\
+                0.5, 180, 39.2, 61\n\
+                0.6, 90, 39.6, 58.5\n";
+    let data = loadpull::parse(text, 50.0).unwrap();
+    assert_eq!(data.columns, ["pout_dbm", "pae_pct"]);
+    assert_eq!(data.column("pae_pct").unwrap(), [61.0, 58.5]);
+    assert!((data.gamma[0] - Complex::new(-0.5, 0.0)).norm() < 1e-6);
+    assert!((data.gamma[1] - Complex::new(0.0, 0.6)).norm() < 1e-6);
+}
+
+#[test]
+fn semicolon_separated_with_decimal_commas() {
+    let text = "! exported with a German locale\n\
+                gamma_re; gamma_im; pout_dbm\n\
+                0,25; -0,5; 38,75\n\
+                0; 0,1; 40\n";
+    let data = loadpull::parse(text, 50.0).unwrap();
+    assert_eq!(data.columns, ["pout_dbm"]);
+    assert_eq!(data.column("pout_dbm").unwrap(), [38.75, 40.0]);
+    assert_eq!(
+        data.gamma,
+        [Complex::new(0.25, -0.5), Complex::new(0.0, 0.1)]
+    );
+}
@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+use egui_smith_chart::{touchstone, Complex};
+
+fn data(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/data")
+        .join(name)
+}
+
+#[test]
+fn stops_at_noise_parameters() {
+    let network = touchstone::load(data("noise.s2p")).unwrap();
+    assert_eq!(network.frequencies, [1e9, 2e9, 3e9]);
+    let s21 = network.parameter(1, 0);
+    assert!((s21[2] - Complex::from_polar(3.0, 75f32.to_radians())).norm() < 1e-6);
+}
+
+#[test]
+fn version_2_keywords() {
+    let network = touchstone::load(data("version2.s2p")).unwrap();
+    assert_eq!(network.frequencies, [100e6, 200e6]);
+    assert_eq!(network.z0, Complex::new(75.0, 0.0));
+    // rows in order 12_21, S12 is listed before S21
+    let s12 = network.parameter(0, 1);
+    let s21 = network.parameter(1, 0);
+    assert_eq!(s12, [Complex::new(0.1, 0.0), Complex::new(0.1, 0.0)]);
+    assert_eq!(s21, [Complex::new(0.8, 0.0), Complex::new(0.8, 0.1)]);
+}